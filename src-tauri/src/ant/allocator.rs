@@ -0,0 +1,225 @@
+// ANT+ Channel Allocator
+//
+// Hands out the stick's channels to sensor profiles on demand and keeps
+// the channel-to-profile map that routes inbound messages. The channel
+// count comes from the stick's capabilities reply.
+
+use super::channel::{AntChannel, ChannelId, ChannelStatus, StickCapabilities};
+use super::monitor::{ChannelMonitor, ChannelState};
+use super::pairing::PairedDevices;
+use super::profile::SensorProfile;
+use serde::Serialize;
+
+// Assumed until the stick answers the capabilities request (smallest ANT chips)
+const DEFAULT_MAX_CHANNELS: u8 = 4;
+
+/// A channel in use by one sensor profile
+pub struct Allocation {
+    pub profile: SensorProfile,
+    pub channel: AntChannel,
+    pub monitor: ChannelMonitor,
+}
+
+/// One sensor channel as reported by the stick
+#[derive(Debug, Clone, Serialize)]
+pub struct ChannelInfo {
+    pub profile: SensorProfile,
+    pub channel_number: u8,
+    pub status: Option<ChannelStatus>,
+    /// Device the channel paired with (the configured ID while searching)
+    pub channel_id: Option<ChannelId>,
+}
+
+/// Stick details for the troubleshooting screen (`get_stick_info`)
+#[derive(Debug, Clone, Serialize)]
+pub struct StickInfo {
+    pub capabilities: Option<StickCapabilities>,
+    pub features: Vec<&'static str>,
+    pub channels: Vec<ChannelInfo>,
+    pub framing_errors: u64,
+}
+
+pub struct ChannelAllocator {
+    max_channels: u8,
+    capabilities: Option<StickCapabilities>,
+    allocations: Vec<Allocation>,
+}
+
+impl ChannelAllocator {
+    pub fn new() -> Self {
+        Self {
+            max_channels: DEFAULT_MAX_CHANNELS,
+            capabilities: None,
+            allocations: Vec::new(),
+        }
+    }
+
+    pub fn max_channels(&self) -> u8 {
+        self.max_channels
+    }
+
+    /// Capabilities reported when the stick was last opened
+    pub fn capabilities(&self) -> Option<StickCapabilities> {
+        self.capabilities
+    }
+
+    /// Use the channel count from the stick's capabilities reply
+    pub fn set_capabilities(&mut self, capabilities: &StickCapabilities) {
+        self.max_channels = capabilities.max_channels;
+        self.capabilities = Some(*capabilities);
+    }
+
+    /// Give a profile a channel configured for its paired device
+    ///
+    /// A profile that already has a channel keeps its number and monitor,
+    /// so re-opening after the stick was reset doesn't reshuffle channels.
+    pub fn allocate(
+        &mut self,
+        profile: SensorProfile,
+        paired: &PairedDevices,
+    ) -> Result<&mut Allocation, String> {
+        if let Some(index) = self.index_of(profile) {
+            let allocation = &mut self.allocations[index];
+            let number = allocation.channel.channel_number();
+            allocation.channel = profile.channel(number, paired);
+            return Ok(allocation);
+        }
+
+        let number = (0..self.max_channels)
+            .find(|number| self.profile_for(*number).is_none())
+            .ok_or_else(|| {
+                format!(
+                    "No free ANT+ channel for {} (all {} in use)",
+                    profile.name(),
+                    self.max_channels
+                )
+            })?;
+
+        self.allocations.push(Allocation {
+            profile,
+            channel: profile.channel(number, paired),
+            monitor: ChannelMonitor::new(),
+        });
+        Ok(self.allocations.last_mut().unwrap())
+    }
+
+    /// Free a profile's channel
+    pub fn release(&mut self, profile: SensorProfile) -> Option<Allocation> {
+        let index = self.index_of(profile)?;
+        Some(self.allocations.remove(index))
+    }
+
+    /// Free every channel, e.g. on disconnect
+    pub fn release_all(&mut self) -> Vec<Allocation> {
+        std::mem::take(&mut self.allocations)
+    }
+
+    /// Profile using a channel number
+    pub fn profile_for(&self, channel_number: u8) -> Option<SensorProfile> {
+        self.for_channel(channel_number).map(|a| a.profile)
+    }
+
+    pub fn get(&self, profile: SensorProfile) -> Option<&Allocation> {
+        self.allocations.iter().find(|a| a.profile == profile)
+    }
+
+    pub fn for_channel(&self, channel_number: u8) -> Option<&Allocation> {
+        self.allocations
+            .iter()
+            .find(|a| a.channel.channel_number() == channel_number)
+    }
+
+    pub fn for_channel_mut(&mut self, channel_number: u8) -> Option<&mut Allocation> {
+        self.allocations
+            .iter_mut()
+            .find(|a| a.channel.channel_number() == channel_number)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Allocation> {
+        self.allocations.iter()
+    }
+
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = &mut Allocation> {
+        self.allocations.iter_mut()
+    }
+
+    /// Channel state of a profile; Closed when it has no channel
+    pub fn state(&self, profile: SensorProfile) -> ChannelState {
+        self.get(profile)
+            .map(|a| a.monitor.state())
+            .unwrap_or(ChannelState::Closed)
+    }
+
+    fn index_of(&self, profile: SensorProfile) -> Option<usize> {
+        self.allocations.iter().position(|a| a.profile == profile)
+    }
+}
+
+impl Default for ChannelAllocator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ant::pairing::PairedDevice;
+
+    #[test]
+    fn test_allocates_lowest_free_channel_per_profile() {
+        let mut allocator = ChannelAllocator::new();
+        let paired = PairedDevices::default();
+
+        allocator.allocate(SensorProfile::Fec, &paired).unwrap();
+        allocator.allocate(SensorProfile::Hrm, &paired).unwrap();
+        assert_eq!(allocator.profile_for(0), Some(SensorProfile::Fec));
+        assert_eq!(allocator.profile_for(1), Some(SensorProfile::Hrm));
+
+        // A freed channel is reused
+        allocator.release(SensorProfile::Fec).unwrap();
+        assert_eq!(allocator.profile_for(0), None);
+        assert_eq!(allocator.state(SensorProfile::Fec), ChannelState::Closed);
+        allocator.allocate(SensorProfile::Fec, &paired).unwrap();
+        assert_eq!(allocator.profile_for(0), Some(SensorProfile::Fec));
+    }
+
+    #[test]
+    fn test_reallocating_keeps_channel_and_picks_up_pairing() {
+        let mut allocator = ChannelAllocator::new();
+        allocator
+            .allocate(SensorProfile::Hrm, &PairedDevices::default())
+            .unwrap()
+            .monitor
+            .opened();
+
+        let paired = PairedDevices {
+            fec: None,
+            hrm: Some(PairedDevice {
+                device_number: 54321,
+                transmission_type: 0x01,
+            }),
+        };
+        let allocation = allocator.allocate(SensorProfile::Hrm, &paired).unwrap();
+
+        assert_eq!(allocation.channel.channel_number(), 0);
+        assert_eq!(allocation.channel.device_number(), 54321);
+        assert_eq!(allocation.monitor.state(), ChannelState::Searching);
+    }
+
+    #[test]
+    fn test_channel_count_comes_from_capabilities() {
+        let mut allocator = ChannelAllocator::new();
+        let paired = PairedDevices::default();
+        allocator.set_capabilities(&StickCapabilities {
+            max_channels: 1,
+            max_networks: 1,
+            ..Default::default()
+        });
+
+        allocator.allocate(SensorProfile::Fec, &paired).unwrap();
+        assert!(allocator.allocate(SensorProfile::Hrm, &paired).is_err());
+        assert_eq!(allocator.max_channels(), 1);
+        assert_eq!(allocator.capabilities().unwrap().max_networks, 1);
+    }
+}
@@ -0,0 +1,341 @@
+// ANT+ FE-C Trainer Calibration
+//
+// Spin-down and zero-offset calibration: the display sends Page 1 with the
+// requested mode, the trainer repeats Page 2 with the speed and temperature
+// it is waiting for, and answers with Page 1 once the calibration is done.
+// This module follows those pages so the panel can tell the rider what to do.
+
+use super::fec::{
+    FecDataPage, CALIBRATION_CONDITION_OK, CALIBRATION_CONDITION_TOO_HIGH,
+    CALIBRATION_CONDITION_TOO_LOW,
+};
+use serde::{Deserialize, Serialize};
+use std::time::{Duration, Instant};
+
+// Page 1/2 temperature: 0.5°C units, offset by -25°C
+const TEMPERATURE_OFFSET_C: f32 = -25.0;
+
+/// Silence after the request or the last Page 2 before giving up on a calibration
+pub const CALIBRATION_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// Calibration a rider can start
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CalibrationKind {
+    /// Resistance calibration: ride up to speed, then coast
+    SpinDown,
+    /// Power calibration with the pedals unloaded
+    ZeroOffset,
+}
+
+/// Where a calibration stands
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CalibrationPhase {
+    /// No calibration requested
+    Idle,
+    /// Request sent, the trainer hasn't reported progress yet
+    Requested,
+    /// Trainer is calibrating (Page 2)
+    Running,
+    /// Trainer reported a successful calibration (Page 1)
+    Succeeded,
+    /// Trainer reported the calibration as failed (Page 1)
+    Failed,
+    /// Trainer stopped answering before reporting a result
+    #[serde(rename = "timed_out")]
+    TimedOut,
+}
+
+/// Speed or temperature condition reported in Page 2
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CalibrationCondition {
+    NotApplicable,
+    TooLow,
+    Ok,
+    TooHigh,
+}
+
+impl CalibrationCondition {
+    fn from_bits(bits: u8) -> Self {
+        match bits {
+            CALIBRATION_CONDITION_TOO_LOW => CalibrationCondition::TooLow,
+            CALIBRATION_CONDITION_OK => CalibrationCondition::Ok,
+            CALIBRATION_CONDITION_TOO_HIGH => CalibrationCondition::TooHigh,
+            _ => CalibrationCondition::NotApplicable,
+        }
+    }
+}
+
+/// Calibration progress, pushed to the webview as `calibration-status`
+#[derive(Debug, Clone, Serialize)]
+pub struct CalibrationStatus {
+    /// Requested calibration; `None` for one started on the trainer itself
+    pub kind: Option<CalibrationKind>,
+    pub phase: CalibrationPhase,
+    pub speed_condition: CalibrationCondition,
+    pub temperature_condition: CalibrationCondition,
+    /// Trainer temperature in °C
+    pub temperature: Option<f32>,
+    /// Speed to reach before coasting, in km/h
+    pub target_speed: Option<f32>,
+    /// Spin-down time the trainer expects, in ms
+    pub target_spin_down_time_ms: Option<u16>,
+    /// Measured spin-down time, in ms
+    pub spin_down_time_ms: Option<u16>,
+    /// Measured zero offset (trainer-specific units)
+    pub zero_offset: Option<u16>,
+    /// Request or last Page 2, for the timeout
+    #[serde(skip)]
+    last_heard: Option<Instant>,
+}
+
+// Equal when the panel would show the same; `last_heard` is bookkeeping
+impl PartialEq for CalibrationStatus {
+    fn eq(&self, other: &Self) -> bool {
+        self.kind == other.kind
+            && self.phase == other.phase
+            && self.speed_condition == other.speed_condition
+            && self.temperature_condition == other.temperature_condition
+            && self.temperature == other.temperature
+            && self.target_speed == other.target_speed
+            && self.target_spin_down_time_ms == other.target_spin_down_time_ms
+            && self.spin_down_time_ms == other.spin_down_time_ms
+            && self.zero_offset == other.zero_offset
+    }
+}
+
+impl Default for CalibrationStatus {
+    fn default() -> Self {
+        Self {
+            kind: None,
+            phase: CalibrationPhase::Idle,
+            speed_condition: CalibrationCondition::NotApplicable,
+            temperature_condition: CalibrationCondition::NotApplicable,
+            temperature: None,
+            target_speed: None,
+            target_spin_down_time_ms: None,
+            spin_down_time_ms: None,
+            zero_offset: None,
+            last_heard: None,
+        }
+    }
+}
+
+impl CalibrationStatus {
+    /// Status right after a calibration request went out
+    pub fn requested(kind: CalibrationKind, now: Instant) -> Self {
+        Self {
+            kind: Some(kind),
+            phase: CalibrationPhase::Requested,
+            last_heard: Some(now),
+            ..Self::default()
+        }
+    }
+
+    /// Whether the trainer is still expected to report on a calibration
+    pub fn is_active(&self) -> bool {
+        matches!(
+            self.phase,
+            CalibrationPhase::Requested | CalibrationPhase::Running
+        )
+    }
+
+    /// Whether a result (or timeout) ended the last calibration
+    pub fn is_finished(&self) -> bool {
+        matches!(
+            self.phase,
+            CalibrationPhase::Succeeded | CalibrationPhase::Failed | CalibrationPhase::TimedOut
+        )
+    }
+
+    /// Give up on a calibration the trainer stopped reporting on; returns
+    /// whether it timed out
+    pub fn expire(&mut self, now: Instant) -> bool {
+        let silent = self
+            .last_heard
+            .is_some_and(|heard| now.duration_since(heard) >= CALIBRATION_TIMEOUT);
+        if !self.is_active() || !silent {
+            return false;
+        }
+
+        self.phase = CalibrationPhase::TimedOut;
+        true
+    }
+
+    /// Follow a Page 1 or Page 2 broadcast; returns whether anything changed
+    pub fn update(&mut self, page: &FecDataPage, now: Instant) -> bool {
+        let before = self.clone();
+
+        match *page {
+            FecDataPage::CalibrationProgress {
+                zero_offset_pending,
+                spin_down_pending,
+                temperature_condition,
+                speed_condition,
+                temperature,
+                target_speed_mms,
+                target_spin_down_time_ms,
+            } => {
+                // Trainers keep sending Page 2 for a while after Page 1;
+                // only a new request starts following progress again
+                if self.is_finished() {
+                    return false;
+                }
+
+                // A calibration started from the trainer's own controls
+                if self.kind.is_none() || !self.is_active() {
+                    self.kind = if spin_down_pending {
+                        Some(CalibrationKind::SpinDown)
+                    } else if zero_offset_pending {
+                        Some(CalibrationKind::ZeroOffset)
+                    } else {
+                        self.kind
+                    };
+                }
+                self.phase = CalibrationPhase::Running;
+                self.speed_condition = CalibrationCondition::from_bits(speed_condition);
+                self.temperature_condition = CalibrationCondition::from_bits(temperature_condition);
+                self.temperature = temperature.map(temperature_celsius);
+                self.target_speed = target_speed_mms.map(|mms| mms as f32 / 1000.0 * 3.6);
+                self.target_spin_down_time_ms = target_spin_down_time_ms;
+                self.last_heard = Some(now);
+            }
+            FecDataPage::CalibrationResponse {
+                zero_offset_success,
+                spin_down_success,
+                temperature,
+                zero_offset,
+                spin_down_time_ms,
+            } => {
+                let success = match self.kind {
+                    Some(CalibrationKind::SpinDown) => spin_down_success,
+                    Some(CalibrationKind::ZeroOffset) => zero_offset_success,
+                    None => spin_down_success || zero_offset_success,
+                };
+                self.phase = if success {
+                    CalibrationPhase::Succeeded
+                } else {
+                    CalibrationPhase::Failed
+                };
+                self.temperature = temperature.map(temperature_celsius).or(self.temperature);
+                self.spin_down_time_ms = spin_down_time_ms;
+                self.zero_offset = zero_offset;
+            }
+            _ => return false,
+        }
+
+        *self != before
+    }
+}
+
+fn temperature_celsius(raw: u8) -> f32 {
+    raw as f32 * 0.5 + TEMPERATURE_OFFSET_C
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ant::fec::FecParser;
+
+    fn page(data: [u8; 8]) -> FecDataPage {
+        FecParser::parse_data_page(&data).unwrap()
+    }
+
+    #[test]
+    fn test_spin_down_follows_progress_and_response() {
+        let now = Instant::now();
+        let mut status = CalibrationStatus::requested(CalibrationKind::SpinDown, now);
+        assert!(status.is_active());
+
+        // Rider still too slow for the 30 km/h target
+        assert!(status.update(&page([0x02, 0x80, 0x60, 91, 0x8D, 0x20, 0x88, 0x13]), now));
+        assert_eq!(status.phase, CalibrationPhase::Running);
+        assert_eq!(status.speed_condition, CalibrationCondition::TooLow);
+        assert_eq!(status.temperature_condition, CalibrationCondition::Ok);
+        assert_eq!(status.temperature, Some(20.5));
+        assert!((status.target_speed.unwrap() - 30.0).abs() < 0.01);
+
+        // Same page again: nothing new for the panel
+        assert!(!status.update(&page([0x02, 0x80, 0x60, 91, 0x8D, 0x20, 0x88, 0x13]), now));
+
+        assert!(status.update(&page([0x01, 0x80, 0x00, 92, 0xFF, 0xFF, 0xC0, 0x12]), now));
+        assert_eq!(status.phase, CalibrationPhase::Succeeded);
+        assert_eq!(status.spin_down_time_ms, Some(4800));
+        assert_eq!(status.zero_offset, None);
+        assert_eq!(status.temperature, Some(21.0));
+        assert!(!status.is_active());
+    }
+
+    #[test]
+    fn test_response_without_requested_mode_is_a_failure() {
+        let now = Instant::now();
+        let mut status = CalibrationStatus::requested(CalibrationKind::ZeroOffset, now);
+        status.update(&page([0x01, 0x00, 0x00, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF]), now);
+        assert_eq!(status.phase, CalibrationPhase::Failed);
+    }
+
+    #[test]
+    fn test_calibration_started_on_the_trainer_is_followed() {
+        let now = Instant::now();
+        let mut status = CalibrationStatus::default();
+        status.update(&page([0x02, 0x40, 0x00, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF]), now);
+        assert_eq!(status.kind, Some(CalibrationKind::ZeroOffset));
+        assert_eq!(status.phase, CalibrationPhase::Running);
+
+        // Other pages leave the status alone
+        assert!(!status.update(&page([0x10, 25, 0, 0, 0x8D, 0x20, 0xFF, 0x30]), now));
+    }
+
+    #[test]
+    fn test_progress_after_the_result_is_ignored() {
+        let now = Instant::now();
+        let mut status = CalibrationStatus::requested(CalibrationKind::SpinDown, now);
+        status.update(&page([0x02, 0x80, 0x60, 91, 0x8D, 0x20, 0x88, 0x13]), now);
+        status.update(&page([0x01, 0x80, 0x00, 92, 0xFF, 0xFF, 0xC0, 0x12]), now);
+
+        // A trailing Page 2 doesn't undo the result
+        assert!(!status.update(&page([0x02, 0x80, 0x60, 91, 0x8D, 0x20, 0x88, 0x13]), now));
+        assert_eq!(status.phase, CalibrationPhase::Succeeded);
+
+        // A new request follows progress again
+        let mut status = CalibrationStatus::requested(CalibrationKind::SpinDown, now);
+        status.update(&page([0x02, 0x80, 0x60, 91, 0x8D, 0x20, 0x88, 0x13]), now);
+        assert_eq!(status.phase, CalibrationPhase::Running);
+    }
+
+    #[test]
+    fn test_unanswered_request_times_out() {
+        let now = Instant::now();
+        let mut status = CalibrationStatus::requested(CalibrationKind::ZeroOffset, now);
+
+        assert!(!status.expire(now + CALIBRATION_TIMEOUT - Duration::from_secs(1)));
+        assert_eq!(status.phase, CalibrationPhase::Requested);
+
+        assert!(status.expire(now + CALIBRATION_TIMEOUT));
+        assert_eq!(status.phase, CalibrationPhase::TimedOut);
+        assert!(!status.is_active());
+    }
+
+    #[test]
+    fn test_running_calibration_times_out_when_progress_stops() {
+        let now = Instant::now();
+        let mut status = CalibrationStatus::requested(CalibrationKind::SpinDown, now);
+        let progress_at = now + Duration::from_secs(10);
+        status.update(
+            &page([0x02, 0x80, 0x60, 91, 0x8D, 0x20, 0x88, 0x13]),
+            progress_at,
+        );
+
+        // Each Page 2 pushes the deadline back
+        assert!(!status.expire(now + CALIBRATION_TIMEOUT));
+        assert_eq!(status.phase, CalibrationPhase::Running);
+
+        assert!(status.expire(progress_at + CALIBRATION_TIMEOUT));
+        assert_eq!(status.phase, CalibrationPhase::TimedOut);
+
+        // Nothing left to time out
+        assert!(!CalibrationStatus::default().expire(progress_at + CALIBRATION_TIMEOUT));
+    }
+}
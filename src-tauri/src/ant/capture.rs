@@ -0,0 +1,331 @@
+// ANT+ Traffic Capture and Replay
+//
+// `RecordingTransport` wraps any transport and logs every frame to and
+// from the stick, one per line:
+//
+//     <microseconds since start> <'>' to stick | '<' from stick> <hex bytes>
+//
+// `ReplayTransport` feeds the inbound frames of a capture back through the
+// normal read path, so a trainer's bug report can be reproduced and kept as
+// a parser regression fixture.
+
+use super::channel::AntChannel;
+use super::framer::AntFramer;
+use super::transport::AntTransport;
+use std::collections::VecDeque;
+use std::fs::{self, File};
+use std::io::{BufWriter, Write};
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+const CAPTURE_HEADER: &str = "# NotchRider ANT capture v1";
+
+/// Which way a captured frame went
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    /// Host to stick
+    Outbound,
+    /// Stick to host
+    Inbound,
+}
+
+/// One captured ANT frame, sync byte through checksum
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CaptureEntry {
+    pub at: Duration,
+    pub direction: Direction,
+    pub frame: Vec<u8>,
+}
+
+impl CaptureEntry {
+    pub fn to_line(&self) -> String {
+        let direction = match self.direction {
+            Direction::Outbound => '>',
+            Direction::Inbound => '<',
+        };
+        let bytes: Vec<String> = self.frame.iter().map(|b| format!("{:02X}", b)).collect();
+        format!("{} {} {}", self.at.as_micros(), direction, bytes.join(" "))
+    }
+
+    /// Parse a capture line; None for comments and blank lines
+    pub fn parse_line(line: &str) -> Result<Option<Self>, String> {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            return Ok(None);
+        }
+
+        let mut fields = line.split_whitespace();
+        let at = fields
+            .next()
+            .and_then(|micros| micros.parse::<u64>().ok())
+            .map(Duration::from_micros)
+            .ok_or_else(|| format!("Bad capture timestamp: {}", line))?;
+        let direction = match fields.next() {
+            Some(">") => Direction::Outbound,
+            Some("<") => Direction::Inbound,
+            _ => return Err(format!("Bad capture direction: {}", line)),
+        };
+        let frame = fields
+            .map(|byte| u8::from_str_radix(byte, 16))
+            .collect::<Result<Vec<u8>, _>>()
+            .map_err(|_| format!("Bad capture bytes: {}", line))?;
+
+        Ok(Some(Self {
+            at,
+            direction,
+            frame,
+        }))
+    }
+}
+
+/// Parse a whole capture
+pub fn parse_capture(text: &str) -> Result<Vec<CaptureEntry>, String> {
+    text.lines()
+        .filter_map(|line| CaptureEntry::parse_line(line).transpose())
+        .collect()
+}
+
+pub fn read_capture(path: &Path) -> Result<Vec<CaptureEntry>, String> {
+    let text = fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read capture {}: {}", path.display(), e))?;
+    parse_capture(&text)
+}
+
+/// Transport wrapper that logs every frame to a capture file
+pub struct RecordingTransport {
+    inner: Box<dyn AntTransport>,
+    capture: BufWriter<File>,
+    started: Instant,
+    outbound: AntFramer, // Splits writes that carry several messages
+}
+
+impl RecordingTransport {
+    pub fn new(inner: Box<dyn AntTransport>, file: File) -> Self {
+        let mut capture = BufWriter::new(file);
+        if let Err(e) = writeln!(capture, "{}", CAPTURE_HEADER) {
+            eprintln!("ANT+ capture write failed: {}", e);
+        }
+
+        Self {
+            inner,
+            capture,
+            started: Instant::now(),
+            outbound: AntFramer::new(),
+        }
+    }
+
+    fn record(&mut self, direction: Direction, messages: &[(u8, u8, Vec<u8>)]) {
+        let at = self.started.elapsed();
+        let written = messages.iter().try_for_each(|(msg_id, _, data)| {
+            let entry = CaptureEntry {
+                at,
+                direction,
+                frame: AntChannel::build_message(*msg_id, data),
+            };
+            writeln!(self.capture, "{}", entry.to_line())
+        });
+
+        // Flush per batch so a crash still leaves a usable capture
+        if let Err(e) = written.and_then(|_| self.capture.flush()) {
+            eprintln!("ANT+ capture write failed: {}", e);
+        }
+    }
+}
+
+impl AntTransport for RecordingTransport {
+    fn open(&mut self) -> Result<(), String> {
+        self.outbound.clear();
+        self.inner.open()
+    }
+
+    fn close(&mut self) {
+        self.inner.close()
+    }
+
+    fn write(&mut self, data: &[u8]) -> Result<usize, String> {
+        let written = self.inner.write(data)?;
+        let messages = self.outbound.push(data);
+        self.record(Direction::Outbound, &messages);
+        Ok(written)
+    }
+
+    fn read(&mut self, buffer: &mut [u8]) -> Result<usize, String> {
+        self.inner.read(buffer)
+    }
+
+    fn framer(&mut self) -> &mut AntFramer {
+        self.inner.framer()
+    }
+
+    // Frames are recorded once the inner transport has reassembled them
+    fn read_messages(&mut self) -> Result<Vec<(u8, u8, Vec<u8>)>, String> {
+        let messages = self.inner.read_messages()?;
+        self.record(Direction::Inbound, &messages);
+        Ok(messages)
+    }
+}
+
+/// Plays back the inbound side of a capture; writes are accepted and dropped
+pub struct ReplayTransport {
+    frames: VecDeque<CaptureEntry>,
+    speed: f64,
+    started: Option<Instant>,
+    framer: AntFramer,
+}
+
+impl ReplayTransport {
+    /// `speed` 1.0 replays in real time, 10.0 ten times faster, and
+    /// `f64::INFINITY` hands over every frame as soon as it is read
+    pub fn new(entries: Vec<CaptureEntry>, speed: f64) -> Self {
+        Self {
+            frames: entries
+                .into_iter()
+                .filter(|entry| entry.direction == Direction::Inbound)
+                .collect(),
+            speed,
+            started: None,
+            framer: AntFramer::new(),
+        }
+    }
+
+    pub fn from_file(path: &Path, speed: f64) -> Result<Self, String> {
+        Ok(Self::new(read_capture(path)?, speed))
+    }
+
+    /// Every captured frame has been delivered
+    pub fn is_finished(&self) -> bool {
+        self.frames.is_empty()
+    }
+
+    fn is_due(&self, entry: &CaptureEntry, elapsed: Duration) -> bool {
+        entry.at.as_secs_f64() / self.speed <= elapsed.as_secs_f64()
+    }
+}
+
+impl AntTransport for ReplayTransport {
+    fn open(&mut self) -> Result<(), String> {
+        // The clock keeps running across reopens, like the original session
+        self.started.get_or_insert_with(Instant::now);
+        self.framer.clear();
+        Ok(())
+    }
+
+    fn close(&mut self) {}
+
+    fn write(&mut self, data: &[u8]) -> Result<usize, String> {
+        Ok(data.len())
+    }
+
+    fn read(&mut self, buffer: &mut [u8]) -> Result<usize, String> {
+        let elapsed = self
+            .started
+            .ok_or("Replay not open. Call open() first.")?
+            .elapsed();
+
+        let mut len = 0;
+        while let Some(entry) = self.frames.front() {
+            if !self.is_due(entry, elapsed) || len + entry.frame.len() > buffer.len() {
+                break;
+            }
+            buffer[len..len + entry.frame.len()].copy_from_slice(&entry.frame);
+            len += entry.frame.len();
+            self.frames.pop_front();
+        }
+
+        Ok(len)
+    }
+
+    fn framer(&mut self) -> &mut AntFramer {
+        &mut self.framer
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ant::channel::{MESG_BROADCAST_DATA, MESG_SYSTEM_RESET};
+    use crate::ant::sim::SimulatedStick;
+
+    fn inbound(at_ms: u64, page: u8) -> CaptureEntry {
+        CaptureEntry {
+            at: Duration::from_millis(at_ms),
+            direction: Direction::Inbound,
+            frame: AntChannel::build_message(MESG_BROADCAST_DATA, &[0, page, 0, 0, 0, 0, 0, 0, 0]),
+        }
+    }
+
+    fn read_all(ant: &mut dyn AntTransport) -> Vec<(u8, u8, Vec<u8>)> {
+        let mut messages = Vec::new();
+        for _ in 0..100 {
+            messages.extend(ant.read_messages().unwrap());
+        }
+        messages
+    }
+
+    #[test]
+    fn test_line_round_trip() {
+        let entry = inbound(1500, 16);
+        let line = entry.to_line();
+
+        assert!(line.starts_with("1500000 < A4 09 4E 00 10"));
+        assert_eq!(CaptureEntry::parse_line(&line).unwrap(), Some(entry));
+        assert_eq!(CaptureEntry::parse_line(CAPTURE_HEADER).unwrap(), None);
+        assert!(CaptureEntry::parse_line("12 ? A4").is_err());
+        assert!(CaptureEntry::parse_line("12 < ZZ").is_err());
+    }
+
+    #[test]
+    fn test_records_simulated_session_and_replays_it() {
+        let path =
+            std::env::temp_dir().join(format!("notchrider-capture-{}.txt", std::process::id()));
+        let mut recording = RecordingTransport::new(
+            Box::new(SimulatedStick::new()),
+            File::create(&path).unwrap(),
+        );
+
+        recording.open().unwrap();
+        let channel = AntChannel::new(0);
+        for msg in channel.get_init_sequence() {
+            recording.write(&msg).unwrap();
+        }
+        let live = read_all(&mut recording);
+        drop(recording);
+
+        let entries = read_capture(&path).unwrap();
+        let _ = fs::remove_file(&path);
+
+        assert_eq!(entries[0].direction, Direction::Outbound);
+        assert_eq!(entries[0].frame, AntChannel::reset_system());
+        assert_eq!(entries[0].frame[2], MESG_SYSTEM_RESET);
+        assert!(live
+            .iter()
+            .any(|(msg_id, _, _)| *msg_id == MESG_BROADCAST_DATA));
+
+        let mut replay = ReplayTransport::new(entries, f64::INFINITY);
+        replay.open().unwrap();
+        assert_eq!(read_all(&mut replay), live);
+        assert!(replay.is_finished());
+    }
+
+    #[test]
+    fn test_real_time_replay_waits_for_capture_timestamps() {
+        let entries = vec![inbound(0, 16), inbound(60_000, 25)];
+
+        let mut replay = ReplayTransport::new(entries.clone(), 1.0);
+        replay.open().unwrap();
+        let messages = read_all(&mut replay);
+        assert_eq!(messages.len(), 1);
+        assert_eq!(messages[0].2[1], 16);
+        assert!(!replay.is_finished());
+
+        let mut fast = ReplayTransport::new(entries, f64::INFINITY);
+        fast.open().unwrap();
+        assert_eq!(read_all(&mut fast).len(), 2);
+    }
+
+    #[test]
+    fn test_replay_must_be_opened() {
+        let mut replay = ReplayTransport::new(vec![inbound(0, 16)], 1.0);
+        assert!(replay.read_messages().is_err());
+    }
+}
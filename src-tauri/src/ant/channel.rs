@@ -3,35 +3,53 @@
 // This module implements ANT+ channel configuration for communicating
 // with fitness equipment devices using the FE-C protocol.
 
+use serde::Serialize;
+
 // ANT+ message types
-const MESG_BROADCAST_DATA: u8 = 0x4E;
-const MESG_CHANNEL_ID: u8 = 0x51;
-const MESG_CHANNEL_FREQUENCY: u8 = 0x45;
-const MESG_CHANNEL_PERIOD: u8 = 0x43;
-const MESG_NETWORK_KEY: u8 = 0x46;
-const MESG_ASSIGN_CHANNEL: u8 = 0x42;
-const MESG_OPEN_CHANNEL: u8 = 0x4B;
-const MESG_CLOSE_CHANNEL: u8 = 0x4C;
-const MESG_SYSTEM_RESET: u8 = 0x4A;
-const MESG_REQUEST_MESSAGE: u8 = 0x4D;
+pub const MESG_BROADCAST_DATA: u8 = 0x4E;
+pub const MESG_ACKNOWLEDGED_DATA: u8 = 0x4F;
+pub const MESG_BURST_DATA: u8 = 0x50;
+pub const MESG_CHANNEL_ID: u8 = 0x51;
+pub const MESG_CHANNEL_FREQUENCY: u8 = 0x45;
+pub const MESG_CHANNEL_PERIOD: u8 = 0x43;
+pub const MESG_NETWORK_KEY: u8 = 0x46;
+pub const MESG_ASSIGN_CHANNEL: u8 = 0x42;
+pub const MESG_OPEN_CHANNEL: u8 = 0x4B;
+pub const MESG_CLOSE_CHANNEL: u8 = 0x4C;
+pub const MESG_SYSTEM_RESET: u8 = 0x4A;
+pub const MESG_REQUEST_MESSAGE: u8 = 0x4D;
+pub const MESG_ID_LIST_ADD: u8 = 0x59;
+pub const MESG_ID_LIST_CONFIG: u8 = 0x5A;
+pub const MESG_LIB_CONFIG: u8 = 0x6E;
+pub const MESG_OPEN_RX_SCAN_MODE: u8 = 0x5B;
 
 // ANT+ response message types
 pub const MESG_RESPONSE_EVENT: u8 = 0x40;
 pub const MESG_CHANNEL_STATUS: u8 = 0x52;
 pub const MESG_CAPABILITIES: u8 = 0x54;
+pub const MESG_STARTUP: u8 = 0x6F;
+
+// Message ID field of a channel response that carries an RF event
+pub const MESG_EVENT_ID: u8 = 0x01;
 
 // ANT+ Network Key (public, same for all ANT+ devices)
 const ANT_PLUS_NETWORK_KEY: [u8; 8] = [0xB9, 0xA5, 0x21, 0xFB, 0xBD, 0x72, 0xC3, 0x45];
 const ANT_PLUS_RF_FREQUENCY: u8 = 57; // 2457 MHz (base 2400 + 57)
 
 // ANT+ FE-C (Fitness Equipment) profile
-const FEC_DEVICE_TYPE: u8 = 17; // Fitness Equipment
+pub const FEC_DEVICE_TYPE: u8 = 17; // Fitness Equipment
 const FEC_CHANNEL_PERIOD: u16 = 8192; // 4Hz message rate (32768/8192 = 4)
 
 // ANT+ HRM (Heart Rate Monitor) profile
-const HRM_DEVICE_TYPE: u8 = 120; // Heart Rate Monitor
+pub const HRM_DEVICE_TYPE: u8 = 120; // Heart Rate Monitor
 const HRM_CHANNEL_PERIOD: u16 = 8070; // ~4.06Hz message rate (32768/8070)
 
+// ANT+ Bicycle Power profile (heard in scan mode only)
+pub const POWER_DEVICE_TYPE: u8 = 11;
+
+// Channel ID device type that matches every device type
+pub const WILDCARD_DEVICE_TYPE: u8 = 0;
+
 // Channel types
 const CHANNEL_TYPE_SLAVE: u8 = 0x00; // Receive channel
 const CHANNEL_TYPE_MASTER: u8 = 0x10; // Transmit channel
@@ -51,6 +69,301 @@ pub const EVENT_CHANNEL_COLLISION: u8 = 0x09;
 /// ANT+ sync byte that starts every message
 pub const ANT_SYNC_BYTE: u8 = 0xA4;
 
+// Lib Config flags: extra fields appended to received data messages
+pub const LIB_CONFIG_CHANNEL_ID: u8 = 0x80;
+pub const LIB_CONFIG_RSSI: u8 = 0x40;
+pub const LIB_CONFIG_RX_TIMESTAMP: u8 = 0x20;
+
+// Capabilities reply: standard option bits (set = feature missing)
+const CAPABILITIES_NO_RECEIVE_CHANNELS: u8 = 0x01;
+const CAPABILITIES_NO_TRANSMIT_CHANNELS: u8 = 0x02;
+const CAPABILITIES_NO_ACKD_MESSAGES: u8 = 0x10;
+const CAPABILITIES_NO_BURST_MESSAGES: u8 = 0x20;
+
+// Capabilities reply: advanced option bits
+const CAPABILITIES_NETWORK_ENABLED: u8 = 0x02;
+const CAPABILITIES_SERIAL_NUMBER_ENABLED: u8 = 0x08;
+const CAPABILITIES_PER_CHANNEL_TX_POWER_ENABLED: u8 = 0x10;
+const CAPABILITIES_LOW_PRIORITY_SEARCH_ENABLED: u8 = 0x20;
+const CAPABILITIES_SEARCH_LIST_ENABLED: u8 = 0x80;
+
+// Capabilities reply: advanced options 2 bits
+const CAPABILITIES_LED_ENABLED: u8 = 0x01;
+const CAPABILITIES_EXT_MESSAGE_ENABLED: u8 = 0x02;
+const CAPABILITIES_SCAN_MODE_ENABLED: u8 = 0x04;
+const CAPABILITIES_PROX_SEARCH_ENABLED: u8 = 0x10;
+const CAPABILITIES_EXT_ASSIGN_ENABLED: u8 = 0x20;
+
+// Channel status reply: state in bits 0-1, network in bits 2-3, type in bits 4-7
+const CHANNEL_STATUS_STATE_MASK: u8 = 0x03;
+const CHANNEL_STATUS_NETWORK_SHIFT: u8 = 2;
+const CHANNEL_STATUS_NETWORK_MASK: u8 = 0x03;
+const CHANNEL_STATUS_TYPE_SHIFT: u8 = 4;
+
+// Inclusion/exclusion list: entries the stick can hold per channel
+pub const ID_LIST_MAX_SIZE: u8 = 4;
+
+// Burst packets: sequence number in bits 5-6 of the channel byte,
+// bit 7 marks the last packet of the transfer
+const BURST_SEQUENCE_SHIFT: u8 = 5;
+const BURST_LAST_PACKET: u8 = 0x80;
+
+/// Channel response or RF event (message 0x40)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ChannelEvent {
+    /// RF event on a channel (EVENT_TX, EVENT_TRANSFER_TX_COMPLETED, ...)
+    Event { channel: u8, code: u8 },
+    /// Reply to a configuration message (RESPONSE_NO_ERROR or an error)
+    Response { channel: u8, msg_id: u8, code: u8 },
+}
+
+impl ChannelEvent {
+    /// Decode the data of a MESG_RESPONSE_EVENT message: [channel][msg id][code]
+    pub fn parse(msg_id: u8, data: &[u8]) -> Option<Self> {
+        if msg_id != MESG_RESPONSE_EVENT || data.len() < 3 {
+            return None;
+        }
+
+        let channel = data[0];
+        let code = data[2];
+
+        if data[1] == MESG_EVENT_ID {
+            Some(ChannelEvent::Event { channel, code })
+        } else {
+            Some(ChannelEvent::Response {
+                channel,
+                msg_id: data[1],
+                code,
+            })
+        }
+    }
+
+    /// Channel number the event belongs to
+    pub fn channel(&self) -> u8 {
+        match self {
+            ChannelEvent::Event { channel, .. } | ChannelEvent::Response { channel, .. } => {
+                *channel
+            }
+        }
+    }
+}
+
+/// Flagged extended data trailing a received data message
+///
+/// With Lib Config enabled, the stick appends a flag byte and the enabled
+/// fields after the 8-byte payload: [channel][payload x8][flag][fields...]
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct ExtendedData {
+    /// Device number of the transmitting device
+    pub device_number: Option<u16>,
+    pub device_type: Option<u8>,
+    pub transmission_type: Option<u8>,
+    /// Received signal strength in dBm
+    pub rssi: Option<i8>,
+    /// Stick clock at reception, in 1/32768 s (rolls over every 2 s)
+    pub rx_timestamp: Option<u16>,
+}
+
+impl ExtendedData {
+    /// Decode the extended fields of a broadcast/acknowledged/burst message
+    ///
+    /// Returns None when the message carries no flag byte.
+    pub fn parse(data: &[u8]) -> Option<Self> {
+        let flags = *data.get(9)?;
+        let mut fields = &data[10..];
+        let mut extended = ExtendedData::default();
+
+        if flags & LIB_CONFIG_CHANNEL_ID != 0 {
+            let id = fields.get(..4)?;
+            extended.device_number = Some(u16::from_le_bytes([id[0], id[1]]));
+            extended.device_type = Some(id[2]);
+            extended.transmission_type = Some(id[3]);
+            fields = &fields[4..];
+        }
+
+        if flags & LIB_CONFIG_RSSI != 0 {
+            // [measurement type][RSSI][threshold]
+            let rssi = fields.get(..3)?;
+            extended.rssi = Some(rssi[1] as i8);
+            fields = &fields[3..];
+        }
+
+        if flags & LIB_CONFIG_RX_TIMESTAMP != 0 {
+            let timestamp = fields.get(..2)?;
+            extended.rx_timestamp = Some(u16::from_le_bytes([timestamp[0], timestamp[1]]));
+        }
+
+        Some(extended)
+    }
+}
+
+/// Limits and features reported by the stick (message 0x54)
+///
+/// Option bytes missing from older sticks' shorter replies read as 0.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct StickCapabilities {
+    pub max_channels: u8,
+    pub max_networks: u8,
+    pub standard_options: u8,
+    pub advanced_options: u8,
+    pub advanced_options_2: u8,
+}
+
+impl StickCapabilities {
+    /// Decode the data of a MESG_CAPABILITIES reply
+    pub fn parse(data: &[u8]) -> Option<Self> {
+        if data.len() < 4 {
+            return None;
+        }
+
+        Some(Self {
+            max_channels: data[0],
+            max_networks: data[1],
+            standard_options: data[2],
+            advanced_options: data[3],
+            advanced_options_2: data.get(4).copied().unwrap_or(0),
+        })
+    }
+
+    pub fn receive_channels(&self) -> bool {
+        self.standard_options & CAPABILITIES_NO_RECEIVE_CHANNELS == 0
+    }
+
+    pub fn transmit_channels(&self) -> bool {
+        self.standard_options & CAPABILITIES_NO_TRANSMIT_CHANNELS == 0
+    }
+
+    pub fn acknowledged_messages(&self) -> bool {
+        self.standard_options & CAPABILITIES_NO_ACKD_MESSAGES == 0
+    }
+
+    pub fn burst_messages(&self) -> bool {
+        self.standard_options & CAPABILITIES_NO_BURST_MESSAGES == 0
+    }
+
+    pub fn network_keys(&self) -> bool {
+        self.advanced_options & CAPABILITIES_NETWORK_ENABLED != 0
+    }
+
+    pub fn serial_number(&self) -> bool {
+        self.advanced_options & CAPABILITIES_SERIAL_NUMBER_ENABLED != 0
+    }
+
+    pub fn extended_messages(&self) -> bool {
+        self.advanced_options_2 & CAPABILITIES_EXT_MESSAGE_ENABLED != 0
+    }
+
+    /// Continuous scan mode (receive from every device on the frequency)
+    pub fn scan_mode(&self) -> bool {
+        self.advanced_options_2 & CAPABILITIES_SCAN_MODE_ENABLED != 0
+    }
+
+    /// Names of the optional features the stick has, for troubleshooting
+    pub fn features(&self) -> Vec<&'static str> {
+        [
+            (self.acknowledged_messages(), "acknowledged messages"),
+            (self.burst_messages(), "burst messages"),
+            (self.network_keys(), "network keys"),
+            (self.serial_number(), "serial number"),
+            (
+                self.advanced_options & CAPABILITIES_PER_CHANNEL_TX_POWER_ENABLED != 0,
+                "per-channel tx power",
+            ),
+            (
+                self.advanced_options & CAPABILITIES_LOW_PRIORITY_SEARCH_ENABLED != 0,
+                "low priority search",
+            ),
+            (
+                self.advanced_options & CAPABILITIES_SEARCH_LIST_ENABLED != 0,
+                "search list",
+            ),
+            (
+                self.advanced_options_2 & CAPABILITIES_LED_ENABLED != 0,
+                "LED",
+            ),
+            (self.extended_messages(), "extended messages"),
+            (self.scan_mode(), "scan mode"),
+            (
+                self.advanced_options_2 & CAPABILITIES_PROX_SEARCH_ENABLED != 0,
+                "proximity search",
+            ),
+            (
+                self.advanced_options_2 & CAPABILITIES_EXT_ASSIGN_ENABLED != 0,
+                "extended assign",
+            ),
+        ]
+        .into_iter()
+        .filter_map(|(supported, name)| supported.then_some(name))
+        .collect()
+    }
+}
+
+/// Channel state as the stick sees it, from a channel status reply
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RadioState {
+    Unassigned,
+    Assigned,
+    Searching,
+    Tracking,
+}
+
+/// Reply to a channel status request (message 0x52)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct ChannelStatus {
+    pub channel: u8,
+    pub state: RadioState,
+    pub network_number: u8,
+    pub channel_type: u8,
+}
+
+impl ChannelStatus {
+    pub fn parse(data: &[u8]) -> Option<Self> {
+        let [channel, status, ..] = *data else {
+            return None;
+        };
+
+        let state = match status & CHANNEL_STATUS_STATE_MASK {
+            0 => RadioState::Unassigned,
+            1 => RadioState::Assigned,
+            2 => RadioState::Searching,
+            _ => RadioState::Tracking,
+        };
+
+        Some(Self {
+            channel,
+            state,
+            network_number: (status >> CHANNEL_STATUS_NETWORK_SHIFT) & CHANNEL_STATUS_NETWORK_MASK,
+            channel_type: status >> CHANNEL_STATUS_TYPE_SHIFT,
+        })
+    }
+}
+
+/// Channel ID reply (message 0x51): once the channel is tracking, the ID
+/// of the device it paired with rather than the configured wildcard
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct ChannelId {
+    pub channel: u8,
+    pub device_number: u16,
+    pub device_type: u8,
+    pub transmission_type: u8,
+}
+
+impl ChannelId {
+    pub fn parse(data: &[u8]) -> Option<Self> {
+        let [channel, number_lo, number_hi, device_type, transmission_type, ..] = *data else {
+            return None;
+        };
+
+        Some(Self {
+            channel,
+            device_number: u16::from_le_bytes([number_lo, number_hi]),
+            device_type,
+            transmission_type,
+        })
+    }
+}
+
 /// ANT+ channel for FE-C communication
 pub struct AntChannel {
     channel_number: u8,
@@ -85,6 +398,11 @@ impl AntChannel {
         self.channel_number
     }
 
+    /// Device number to pair with (0 = wildcard)
+    pub fn device_number(&self) -> u16 {
+        self.device_number
+    }
+
     /// Build ANT message with sync byte, length, message ID, data, and checksum
     ///
     /// ANT message format:
@@ -144,6 +462,11 @@ impl AntChannel {
         Self::build_message(MESG_SYSTEM_RESET, &[0x00])
     }
 
+    /// Enable flagged extended data on received messages (0 disables)
+    pub fn set_lib_config(flags: u8) -> Vec<u8> {
+        Self::build_message(MESG_LIB_CONFIG, &[0x00, flags])
+    }
+
     /// Set network key - required before opening channels
     pub fn set_network_key(&self) -> Vec<u8> {
         let mut data = vec![self.network_number];
@@ -214,6 +537,20 @@ impl AntChannel {
         )
     }
 
+    /// Set channel ID matching any device type, for scan mode
+    pub fn set_channel_id_any_type(&self) -> Vec<u8> {
+        Self::build_message(
+            MESG_CHANNEL_ID,
+            &[
+                self.channel_number,
+                (self.device_number & 0xFF) as u8,
+                (self.device_number >> 8) as u8,
+                WILDCARD_DEVICE_TYPE,
+                self.transmission_type,
+            ],
+        )
+    }
+
     /// Set channel period for HRM (~4.06Hz)
     pub fn set_channel_period_hrm(&self) -> Vec<u8> {
         Self::build_message(
@@ -243,11 +580,91 @@ impl AntChannel {
         Self::build_message(MESG_OPEN_CHANNEL, &[self.channel_number])
     }
 
+    /// Open continuous scan mode: the radio listens all the time and
+    /// receives every device matching channel 0's ID
+    ///
+    /// Channel 0 must be assigned as a slave first, and no other channel
+    /// may be open while scanning.
+    pub fn open_rx_scan_mode() -> Vec<u8> {
+        Self::build_message(MESG_OPEN_RX_SCAN_MODE, &[0x00])
+    }
+
     /// Close channel
     pub fn close_channel(&self) -> Vec<u8> {
         Self::build_message(MESG_CLOSE_CHANNEL, &[self.channel_number])
     }
 
+    /// Add a device to the channel's inclusion/exclusion list
+    ///
+    /// `index` must be below ID_LIST_MAX_SIZE.
+    pub fn add_to_id_list(
+        &self,
+        device_number: u16,
+        device_type: u8,
+        transmission_type: u8,
+        index: u8,
+    ) -> Vec<u8> {
+        Self::build_message(
+            MESG_ID_LIST_ADD,
+            &[
+                self.channel_number,
+                (device_number & 0xFF) as u8,
+                (device_number >> 8) as u8,
+                device_type,
+                transmission_type,
+                index,
+            ],
+        )
+    }
+
+    /// Use the first `size` list entries as an exclusion list, so a
+    /// wildcard search skips devices that were already found
+    pub fn config_exclusion_list(&self, size: u8) -> Vec<u8> {
+        Self::build_message(MESG_ID_LIST_CONFIG, &[self.channel_number, size, 0x01])
+    }
+
+    /// Acknowledged data message carrying an 8-byte page
+    ///
+    /// Used for control pages: the stick retries the transfer until the
+    /// receiving device acknowledges it or the transfer fails.
+    pub fn acknowledged_data(&self, payload: &[u8; 8]) -> Vec<u8> {
+        let mut data = vec![self.channel_number];
+        data.extend_from_slice(payload);
+        Self::build_message(MESG_ACKNOWLEDGED_DATA, &data)
+    }
+
+    /// Burst transfer: split a payload into 8-byte burst data messages
+    ///
+    /// The last packet is padded with zeros. Messages must be written
+    /// back-to-back; the stick reports the outcome with a single
+    /// EVENT_TRANSFER_TX_COMPLETED or EVENT_TRANSFER_TX_FAILED.
+    pub fn burst_data(&self, payload: &[u8]) -> Vec<Vec<u8>> {
+        let chunks: Vec<&[u8]> = if payload.is_empty() {
+            vec![&[]]
+        } else {
+            payload.chunks(8).collect()
+        };
+        let last = chunks.len() - 1;
+
+        chunks
+            .iter()
+            .enumerate()
+            .map(|(i, chunk)| {
+                // Sequence starts at 0 and then cycles 1, 2, 3, 1, ...
+                let sequence = if i == 0 { 0 } else { ((i - 1) % 3 + 1) as u8 };
+                let mut channel_byte = self.channel_number | (sequence << BURST_SEQUENCE_SHIFT);
+                if i == last {
+                    channel_byte |= BURST_LAST_PACKET;
+                }
+
+                let mut data = vec![channel_byte];
+                data.extend_from_slice(chunk);
+                data.resize(9, 0x00);
+                Self::build_message(MESG_BURST_DATA, &data)
+            })
+            .collect()
+    }
+
     /// Request channel status
     pub fn request_channel_status(&self) -> Vec<u8> {
         Self::build_message(
@@ -256,6 +673,14 @@ impl AntChannel {
         )
     }
 
+    /// Request the channel ID, i.e. the device the channel paired with
+    pub fn request_channel_id(&self) -> Vec<u8> {
+        Self::build_message(
+            MESG_REQUEST_MESSAGE,
+            &[self.channel_number, MESG_CHANNEL_ID],
+        )
+    }
+
     /// Request device capabilities
     pub fn request_capabilities(&self) -> Vec<u8> {
         Self::build_message(MESG_REQUEST_MESSAGE, &[0x00, MESG_CAPABILITIES])
@@ -343,10 +768,206 @@ mod tests {
         assert_eq!(data[1], ANT_PLUS_RF_FREQUENCY);
     }
 
+    #[test]
+    fn test_acknowledged_data_message() {
+        let channel = AntChannel::new(2);
+        let msg = channel.acknowledged_data(&[0x31, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xE8, 0x03]);
+
+        assert_eq!(msg[1], 9); // Length: channel + 8 byte page
+        assert_eq!(msg[2], MESG_ACKNOWLEDGED_DATA);
+        assert_eq!(msg[3], 2); // Channel number
+        assert_eq!(msg[4], 0x31); // Page number
+
+        let (msg_id, ch, data) = AntChannel::parse_message(&msg).unwrap();
+        assert_eq!(msg_id, MESG_ACKNOWLEDGED_DATA);
+        assert_eq!(ch, 2);
+        assert_eq!(data.len(), 9);
+    }
+
+    #[test]
+    fn test_channel_id_with_device() {
+        let channel = AntChannel::with_device(1, 0x3039, 0x05);
+        let msg = channel.set_channel_id_hrm();
+
+        assert_eq!(&msg[3..8], &[1, 0x39, 0x30, HRM_DEVICE_TYPE, 0x05]);
+        assert_eq!(channel.device_number(), 12345);
+    }
+
+    #[test]
+    fn test_exclusion_list_messages() {
+        let channel = AntChannel::new(0);
+
+        let add = channel.add_to_id_list(12345, FEC_DEVICE_TYPE, 0x05, 2);
+        assert_eq!(add[2], MESG_ID_LIST_ADD);
+        assert_eq!(&add[3..9], &[0, 0x39, 0x30, FEC_DEVICE_TYPE, 0x05, 2]);
+
+        let config = channel.config_exclusion_list(3);
+        assert_eq!(config[2], MESG_ID_LIST_CONFIG);
+        assert_eq!(&config[3..6], &[0, 3, 0x01]);
+    }
+
+    #[test]
+    fn test_scan_mode_messages() {
+        let id = AntChannel::new(0).set_channel_id_any_type();
+        assert_eq!(&id[3..8], &[0, 0, 0, WILDCARD_DEVICE_TYPE, 0]);
+
+        let scan = AntChannel::open_rx_scan_mode();
+        assert_eq!(
+            scan,
+            vec![ANT_SYNC_BYTE, 0x01, MESG_OPEN_RX_SCAN_MODE, 0x00, 0xFE]
+        );
+    }
+
+    #[test]
+    fn test_parse_extended_data() {
+        let mut data = vec![0, 0x10, 0, 0, 0, 0, 0, 0, 0];
+        data.push(LIB_CONFIG_CHANNEL_ID | LIB_CONFIG_RSSI | LIB_CONFIG_RX_TIMESTAMP);
+        data.extend_from_slice(&[0x39, 0x30, FEC_DEVICE_TYPE, 0x05]);
+        data.extend_from_slice(&[0x20, (-62i8) as u8, 0x00]);
+        data.extend_from_slice(&[0x00, 0x40]);
+
+        let extended = ExtendedData::parse(&data).unwrap();
+
+        assert_eq!(extended.device_number, Some(12345));
+        assert_eq!(extended.device_type, Some(FEC_DEVICE_TYPE));
+        assert_eq!(extended.transmission_type, Some(0x05));
+        assert_eq!(extended.rssi, Some(-62));
+        assert_eq!(extended.rx_timestamp, Some(16384));
+    }
+
+    #[test]
+    fn test_parse_extended_data_timestamp_only() {
+        let mut data = vec![0, 0x10, 0, 0, 0, 0, 0, 0, 0];
+        data.extend_from_slice(&[LIB_CONFIG_RX_TIMESTAMP, 0x34, 0x12]);
+
+        let extended = ExtendedData::parse(&data).unwrap();
+
+        assert_eq!(extended.device_number, None);
+        assert_eq!(extended.rx_timestamp, Some(0x1234));
+    }
+
+    #[test]
+    fn test_parse_extended_data_absent_or_truncated() {
+        assert_eq!(ExtendedData::parse(&[0, 0x10, 0, 0, 0, 0, 0, 0, 0]), None);
+        assert_eq!(
+            ExtendedData::parse(&[0, 0x10, 0, 0, 0, 0, 0, 0, 0, LIB_CONFIG_CHANNEL_ID, 0x39]),
+            None
+        );
+    }
+
     #[test]
     fn test_init_sequence_length() {
         let channel = AntChannel::new(0);
         let sequence = channel.get_init_sequence();
         assert_eq!(sequence.len(), 7); // Reset, network key, assign, ID, freq, period, open
     }
+
+    #[test]
+    fn test_burst_data_sequence() {
+        let channel = AntChannel::new(1);
+        let payload: Vec<u8> = (0..40).collect();
+        let packets = channel.burst_data(&payload);
+
+        assert_eq!(packets.len(), 5);
+        let channel_bytes: Vec<u8> = packets.iter().map(|p| p[3]).collect();
+        // Sequence 0, 1, 2, 3, 1 in bits 5-6, last packet flagged in bit 7
+        assert_eq!(channel_bytes, vec![0x01, 0x21, 0x41, 0x61, 0xA1]);
+
+        for packet in &packets {
+            assert_eq!(packet[2], MESG_BURST_DATA);
+            assert!(AntChannel::parse_message(packet).is_some());
+        }
+        assert_eq!(&packets[4][4..12], &[32, 33, 34, 35, 36, 37, 38, 39]);
+    }
+
+    #[test]
+    fn test_burst_data_pads_last_packet() {
+        let channel = AntChannel::new(0);
+        let packets = channel.burst_data(&[1, 2, 3]);
+
+        assert_eq!(packets.len(), 1);
+        assert_eq!(packets[0][3], BURST_LAST_PACKET);
+        assert_eq!(&packets[0][4..12], &[1, 2, 3, 0, 0, 0, 0, 0]);
+    }
+
+    #[test]
+    fn test_parse_channel_event() {
+        let msg = AntChannel::build_message(
+            MESG_RESPONSE_EVENT,
+            &[0, MESG_EVENT_ID, EVENT_TRANSFER_TX_COMPLETED],
+        );
+        let (msg_id, _, data) = AntChannel::parse_message(&msg).unwrap();
+
+        assert_eq!(
+            ChannelEvent::parse(msg_id, &data),
+            Some(ChannelEvent::Event {
+                channel: 0,
+                code: EVENT_TRANSFER_TX_COMPLETED
+            })
+        );
+    }
+
+    #[test]
+    fn test_parse_channel_response() {
+        let data = [1, MESG_OPEN_CHANNEL, RESPONSE_NO_ERROR];
+        let event = ChannelEvent::parse(MESG_RESPONSE_EVENT, &data).unwrap();
+
+        assert_eq!(
+            event,
+            ChannelEvent::Response {
+                channel: 1,
+                msg_id: MESG_OPEN_CHANNEL,
+                code: RESPONSE_NO_ERROR
+            }
+        );
+        assert_eq!(event.channel(), 1);
+        assert_eq!(ChannelEvent::parse(MESG_BROADCAST_DATA, &data), None);
+    }
+
+    #[test]
+    fn test_parse_stick_capabilities() {
+        let capabilities = StickCapabilities::parse(&[8, 8, 0x00, 0xBA, 0x36, 0x00]).unwrap();
+        assert_eq!(capabilities.max_channels, 8);
+        assert_eq!(capabilities.max_networks, 8);
+        assert!(capabilities.receive_channels());
+        assert!(capabilities.burst_messages());
+        assert!(capabilities.serial_number());
+        assert!(capabilities.extended_messages());
+        assert!(capabilities.scan_mode());
+        assert!(capabilities.features().contains(&"search list"));
+        assert!(!capabilities.features().contains(&"LED"));
+
+        // Older sticks send only the first four bytes
+        let short = StickCapabilities::parse(&[4, 3, 0x30, 0x00]).unwrap();
+        assert!(!short.burst_messages());
+        assert!(!short.acknowledged_messages());
+        assert!(!short.scan_mode());
+        assert_eq!(StickCapabilities::parse(&[8]), None);
+    }
+
+    #[test]
+    fn test_parse_channel_status() {
+        // Channel 1, slave (0x00) on network 0, tracking
+        let status = ChannelStatus::parse(&[1, 0x03]).unwrap();
+        assert_eq!(status.channel, 1);
+        assert_eq!(status.state, RadioState::Tracking);
+        assert_eq!(status.network_number, 0);
+        assert_eq!(status.channel_type, 0);
+
+        // Master (0x10) on network 1, searching
+        let status = ChannelStatus::parse(&[0, 0x16]).unwrap();
+        assert_eq!(status.state, RadioState::Searching);
+        assert_eq!(status.network_number, 1);
+        assert_eq!(status.channel_type, 1);
+        assert_eq!(ChannelStatus::parse(&[0]), None);
+    }
+
+    #[test]
+    fn test_parse_channel_id() {
+        let id = ChannelId::parse(&[0, 0x39, 0x30, FEC_DEVICE_TYPE, 0x05]).unwrap();
+        assert_eq!(id.device_number, 12345);
+        assert_eq!(id.device_type, FEC_DEVICE_TYPE);
+        assert_eq!(id.transmission_type, 0x05);
+        assert_eq!(ChannelId::parse(&[0, 0x39]), None);
+    }
 }
@@ -0,0 +1,541 @@
+// ANT+ FE-C Trainer Control
+//
+// This module sends FE-C control pages (target power, simulation, ...) to a
+// trainer and waits for the trainer to confirm them via Page 71 (Command Status).
+
+use super::calibration::CalibrationKind;
+use super::channel::{AntChannel, MESG_ACKNOWLEDGED_DATA, MESG_BROADCAST_DATA};
+use super::fec::{
+    FecCommand, FecDataPage, FecParser, UserConfig, PAGE_COMMAND_STATUS, PAGE_FE_CAPABILITIES,
+    PAGE_MANUFACTURER_ID, PAGE_PRODUCT_INFO,
+};
+use super::manufacturer::{device_name, trainer_quirks, TrainerQuirks};
+use super::transport::AntTransport;
+use serde::Serialize;
+use std::thread;
+use std::time::{Duration, Instant};
+
+// How many times a command is sent before giving up
+const COMMAND_MAX_ATTEMPTS: u32 = 3;
+
+// How long to wait for the Command Status page after each attempt
+const COMMAND_STATUS_TIMEOUT: Duration = Duration::from_millis(1000);
+
+// Gap between the control page and the follow-up status request
+const COMMAND_REQUEST_DELAY: Duration = Duration::from_millis(50);
+
+// Basic resistance fallback: grade range mapped onto 0-100% resistance
+const FALLBACK_GRADE_MIN_PERCENT: f32 = -5.0;
+const FALLBACK_GRADE_MAX_PERCENT: f32 = 15.0;
+
+// Basic resistance fallback: wattage treated as 100% resistance
+const FALLBACK_FULL_RESISTANCE_WATTS: f32 = 400.0;
+
+// Page 70 requests for the trainer's identity: rounds and spacing
+const INFO_REQUEST_ATTEMPTS: u32 = 3;
+const INFO_REQUEST_INTERVAL: Duration = Duration::from_secs(2);
+
+// Page 81 serial number when the trainer has none
+const SERIAL_NUMBER_INVALID: u32 = 0xFFFF_FFFF;
+
+/// Control modes advertised by the trainer in Page 54 (FE Capabilities)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize)]
+pub struct FecCapabilities {
+    /// Maximum applicable resistance in Newtons
+    pub max_resistance: u16,
+    pub supports_basic_resistance: bool,
+    pub supports_target_power: bool,
+    pub supports_simulation: bool,
+}
+
+impl FecCapabilities {
+    /// Extract capabilities from a Page 54 data page
+    pub fn from_page(page: &FecDataPage) -> Option<Self> {
+        match page {
+            FecDataPage::FECapabilities {
+                max_resistance,
+                supports_basic_resistance,
+                supports_target_power,
+                supports_simulation,
+            } => Some(Self {
+                max_resistance: *max_resistance,
+                supports_basic_resistance: *supports_basic_resistance,
+                supports_target_power: *supports_target_power,
+                supports_simulation: *supports_simulation,
+            }),
+            _ => None,
+        }
+    }
+}
+
+/// Capabilities and identity of the connected trainer
+///
+/// Filled from Pages 54, 80 and 81, whether the trainer broadcasts them on
+/// its own or answers a Page 70 request for them.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct TrainerInfo {
+    /// Max resistance and supported control modes (Page 54)
+    pub capabilities: Option<FecCapabilities>,
+    pub manufacturer_id: Option<u16>,
+    pub model_number: Option<u16>,
+    /// Manufacturer and model name, when the manufacturer is known
+    pub name: Option<String>,
+    pub hw_revision: Option<u8>,
+    pub serial_number: Option<u32>,
+    /// Software revision, e.g. "2.5" (Page 81)
+    pub firmware: Option<String>,
+    #[serde(skip)]
+    sw_revision: Option<(u8, u8)>, // Main, supplemental
+    #[serde(skip)]
+    requests_sent: u32,
+    #[serde(skip)]
+    last_request: Option<Instant>,
+}
+
+impl TrainerInfo {
+    /// Record an info page; returns whether the page was one
+    pub fn update(&mut self, page: &FecDataPage) -> bool {
+        match *page {
+            FecDataPage::FECapabilities { .. } => {
+                self.capabilities = FecCapabilities::from_page(page);
+            }
+            FecDataPage::ManufacturerId {
+                hw_revision,
+                manufacturer_id,
+                model_number,
+            } => {
+                self.manufacturer_id = Some(manufacturer_id);
+                self.model_number = Some(model_number);
+                self.name = device_name(manufacturer_id, Some(model_number));
+                self.hw_revision = Some(hw_revision);
+            }
+            FecDataPage::ProductInfo {
+                sw_revision_supplemental,
+                sw_revision_main,
+                serial_number,
+            } => {
+                self.sw_revision = Some((sw_revision_main, sw_revision_supplemental));
+                self.serial_number =
+                    (serial_number != SERIAL_NUMBER_INVALID).then_some(serial_number);
+                self.firmware = Some(firmware_version(sw_revision_main, sw_revision_supplemental));
+            }
+            _ => return false,
+        }
+        true
+    }
+
+    /// Info pages not received yet
+    pub fn missing_pages(&self) -> Vec<u8> {
+        let mut pages = Vec::new();
+        if self.capabilities.is_none() {
+            pages.push(PAGE_FE_CAPABILITIES);
+        }
+        if self.manufacturer_id.is_none() {
+            pages.push(PAGE_MANUFACTURER_ID);
+        }
+        if self.sw_revision.is_none() {
+            pages.push(PAGE_PRODUCT_INFO);
+        }
+        pages
+    }
+
+    /// Deviations from the profile, once Page 80 has identified the trainer
+    pub fn quirks(&self) -> TrainerQuirks {
+        match (self.manufacturer_id, self.model_number) {
+            (Some(manufacturer_id), Some(model_number)) => {
+                trainer_quirks(manufacturer_id, model_number)
+            }
+            _ => TrainerQuirks::default(),
+        }
+    }
+
+    /// Software revision in hundredths, as recorded in FIT files
+    pub fn software_version(&self) -> Option<u16> {
+        self.sw_revision.map(|(main, supplemental)| {
+            if supplemental == 0xFF {
+                main as u16 * 10
+            } else {
+                (main as u16 * 100 + supplemental as u16) / 10
+            }
+        })
+    }
+
+    /// Pages to request now: the missing ones, at most every 2s and for
+    /// 3 rounds, so a trainer that ignores Page 70 isn't asked forever
+    pub fn due_requests(&mut self, now: Instant) -> Vec<u8> {
+        let due = self.requests_sent < INFO_REQUEST_ATTEMPTS
+            && self.last_request.map_or(true, |last| {
+                now.duration_since(last) >= INFO_REQUEST_INTERVAL
+            });
+        if !due {
+            return Vec::new();
+        }
+
+        let pages = self.missing_pages();
+        if !pages.is_empty() {
+            self.requests_sent += 1;
+            self.last_request = Some(now);
+        }
+        pages
+    }
+}
+
+/// Page 81 software revision: main / 10, or (main * 100 + supplemental)
+/// / 1000 when the supplemental revision is set
+fn firmware_version(main: u8, supplemental: u8) -> String {
+    if supplemental == 0xFF {
+        format!("{}.{}", main / 10, main % 10)
+    } else {
+        let revision = main as u32 * 100 + supplemental as u32;
+        format!("{}.{:03}", revision / 1000, revision % 1000)
+    }
+}
+
+/// Sends control pages on the FE-C channel
+pub struct FecController {
+    channel: AntChannel,
+    capabilities: Option<FecCapabilities>,
+    quirks: TrainerQuirks,
+}
+
+impl FecController {
+    pub fn new(channel_number: u8) -> Self {
+        Self {
+            channel: AntChannel::new(channel_number),
+            capabilities: None,
+            quirks: TrainerQuirks::default(),
+        }
+    }
+
+    /// Use the trainer's Page 54 capabilities to pick a control mode
+    ///
+    /// Without capabilities every request is sent as-is.
+    pub fn with_capabilities(mut self, capabilities: Option<FecCapabilities>) -> Self {
+        self.capabilities = capabilities;
+        self
+    }
+
+    /// Work around the trainer's known deviations from the profile
+    pub fn with_quirks(mut self, quirks: TrainerQuirks) -> Self {
+        self.quirks = quirks;
+        self
+    }
+
+    /// ERG mode: hold the trainer at a fixed wattage (Page 49)
+    ///
+    /// `on_message` receives every other ANT message read while waiting
+    /// for the confirmation, so no trainer or HR data is lost. Trainers
+    /// that only support basic resistance get an approximate resistance.
+    pub fn set_target_power(
+        &self,
+        ant: &mut dyn AntTransport,
+        watts: f32,
+        on_message: &mut dyn FnMut(u8, u8, &[u8]),
+    ) -> Result<(), String> {
+        let command = if self.use_resistance_fallback(|c| c.supports_target_power) {
+            FecCommand::basic_resistance(resistance_for_power(watts))
+        } else {
+            FecCommand::target_power(watts)
+        };
+        self.send_command(ant, &command, on_message)
+    }
+
+    /// Simulation mode: ride a road grade in percent (Page 51)
+    ///
+    /// Trainers that only support basic resistance get a resistance level
+    /// proportional to the grade.
+    pub fn set_grade(
+        &self,
+        ant: &mut dyn AntTransport,
+        grade_percent: f32,
+        on_message: &mut dyn FnMut(u8, u8, &[u8]),
+    ) -> Result<(), String> {
+        let command = if self.use_resistance_fallback(|c| c.supports_simulation) {
+            FecCommand::basic_resistance(resistance_for_grade(grade_percent))
+        } else {
+            FecCommand::track_resistance(grade_percent, None)
+        };
+        self.send_command(ant, &command, on_message)
+    }
+
+    /// Basic resistance: percentage of the trainer's maximum (Page 48)
+    pub fn set_resistance(
+        &self,
+        ant: &mut dyn AntTransport,
+        percent: f32,
+        on_message: &mut dyn FnMut(u8, u8, &[u8]),
+    ) -> Result<(), String> {
+        self.send_command(ant, &FecCommand::basic_resistance(percent), on_message)
+    }
+
+    /// Simulation mode: aerodynamic drag parameters (Page 50)
+    pub fn set_wind_resistance(
+        &self,
+        ant: &mut dyn AntTransport,
+        cda: Option<f32>,
+        wind_speed_kmh: Option<f32>,
+        drafting_factor: Option<f32>,
+        on_message: &mut dyn FnMut(u8, u8, &[u8]),
+    ) -> Result<(), String> {
+        let command = FecCommand::wind_resistance(cda, wind_speed_kmh, drafting_factor);
+        self.send_command(ant, &command, on_message)
+    }
+
+    /// Rider/bike weight and wheel size used in simulation mode (Page 55)
+    pub fn set_user_config(
+        &self,
+        ant: &mut dyn AntTransport,
+        config: &UserConfig,
+        on_message: &mut dyn FnMut(u8, u8, &[u8]),
+    ) -> Result<(), String> {
+        self.send_command(ant, &FecCommand::user_configuration(config), on_message)
+    }
+
+    /// Start a spin-down or zero-offset calibration (Page 1)
+    ///
+    /// Only the request is sent here; the trainer reports progress and the
+    /// result in Pages 2 and 1 on the regular broadcasts.
+    pub fn request_calibration(
+        &self,
+        ant: &mut dyn AntTransport,
+        kind: CalibrationKind,
+        on_message: &mut dyn FnMut(u8, u8, &[u8]),
+    ) -> Result<(), String> {
+        let command = FecCommand::calibration_request(
+            kind == CalibrationKind::SpinDown,
+            kind == CalibrationKind::ZeroOffset,
+        );
+        self.send_command(ant, &command, on_message)
+    }
+
+    /// Ask the trainer to broadcast each page once (Page 70)
+    ///
+    /// The pages arrive later with the regular broadcasts.
+    pub fn request_pages(
+        &self,
+        ant: &mut dyn AntTransport,
+        pages: &[u8],
+        on_message: &mut dyn FnMut(u8, u8, &[u8]),
+    ) -> Result<(), String> {
+        for &page in pages {
+            self.send_command(ant, &FecCommand::request_page(page), on_message)?;
+        }
+        Ok(())
+    }
+
+    /// Send a control page and retry until Page 71 confirms it
+    pub fn send_command(
+        &self,
+        ant: &mut dyn AntTransport,
+        command: &FecCommand,
+        on_message: &mut dyn FnMut(u8, u8, &[u8]),
+    ) -> Result<(), String> {
+        let channel_number = self.channel.channel_number();
+        let command_msg = self.channel.acknowledged_data(&command.encode());
+
+        if !command.expects_status() || self.quirks.no_command_status {
+            return ant.send_acknowledged(channel_number, &command_msg, on_message);
+        }
+
+        let status_request = FecCommand::request_page(PAGE_COMMAND_STATUS);
+        let request_msg = self.channel.acknowledged_data(&status_request.encode());
+
+        for attempt in 1..=COMMAND_MAX_ATTEMPTS {
+            ant.send_acknowledged(channel_number, &command_msg, on_message)?;
+            thread::sleep(COMMAND_REQUEST_DELAY);
+            ant.send_acknowledged(channel_number, &request_msg, on_message)?;
+
+            match self.await_command_status(ant, command, on_message)? {
+                Some(result) => return result,
+                None => println!(
+                    "FE-C page {} not confirmed (attempt {}/{})",
+                    command.page_number(),
+                    attempt,
+                    COMMAND_MAX_ATTEMPTS
+                ),
+            }
+        }
+
+        Err(format!(
+            "Trainer did not confirm page {} after {} attempts",
+            command.page_number(),
+            COMMAND_MAX_ATTEMPTS
+        ))
+    }
+
+    /// Whether a mode must be emulated with basic resistance
+    fn use_resistance_fallback(&self, supports_mode: impl Fn(&FecCapabilities) -> bool) -> bool {
+        self.capabilities
+            .map(|c| !supports_mode(&c) && c.supports_basic_resistance)
+            .unwrap_or(false)
+    }
+
+    /// Read messages until a Command Status page settles the command or the
+    /// timeout expires
+    fn await_command_status(
+        &self,
+        ant: &mut dyn AntTransport,
+        command: &FecCommand,
+        on_message: &mut dyn FnMut(u8, u8, &[u8]),
+    ) -> Result<Option<Result<(), String>>, String> {
+        let deadline = Instant::now() + COMMAND_STATUS_TIMEOUT;
+        let channel_number = self.channel.channel_number();
+
+        while Instant::now() < deadline {
+            let mut outcome = None;
+
+            for (msg_id, channel, data) in ant.read_messages()? {
+                let is_data = msg_id == MESG_BROADCAST_DATA || msg_id == MESG_ACKNOWLEDGED_DATA;
+                if is_data
+                    && channel == channel_number
+                    && data.len() >= 9
+                    && data[1] == PAGE_COMMAND_STATUS
+                {
+                    if let Some(page) = FecParser::parse_data_page(&data[1..9]) {
+                        if outcome.is_none() {
+                            outcome = command.check_status(&page);
+                        }
+                    }
+                    continue;
+                }
+
+                on_message(msg_id, channel, &data);
+            }
+
+            if outcome.is_some() {
+                return Ok(outcome);
+            }
+        }
+
+        Ok(None)
+    }
+}
+
+/// Map a road grade onto a basic resistance percentage
+///
+/// Descents below -5% ride at zero resistance, climbs above 15% at full.
+pub fn resistance_for_grade(grade_percent: f32) -> f32 {
+    let range = FALLBACK_GRADE_MAX_PERCENT - FALLBACK_GRADE_MIN_PERCENT;
+    ((grade_percent - FALLBACK_GRADE_MIN_PERCENT) / range * 100.0).clamp(0.0, 100.0)
+}
+
+/// Map a target wattage onto a basic resistance percentage
+///
+/// Without a power curve for the trainer this is a straight line up to
+/// 400W; the rider's cadence decides the actual power.
+pub fn resistance_for_power(watts: f32) -> f32 {
+    (watts / FALLBACK_FULL_RESISTANCE_WATTS * 100.0).clamp(0.0, 100.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resistance_for_grade() {
+        assert_eq!(resistance_for_grade(-10.0), 0.0);
+        assert_eq!(resistance_for_grade(-5.0), 0.0);
+        assert_eq!(resistance_for_grade(5.0), 50.0);
+        assert_eq!(resistance_for_grade(25.0), 100.0);
+    }
+
+    #[test]
+    fn test_resistance_for_power() {
+        assert_eq!(resistance_for_power(0.0), 0.0);
+        assert_eq!(resistance_for_power(200.0), 50.0);
+        assert_eq!(resistance_for_power(1000.0), 100.0);
+    }
+
+    #[test]
+    fn test_capabilities_from_page() {
+        let page = FecDataPage::FECapabilities {
+            max_resistance: 2000,
+            supports_basic_resistance: true,
+            supports_target_power: false,
+            supports_simulation: false,
+        };
+
+        let capabilities = FecCapabilities::from_page(&page).unwrap();
+        assert_eq!(capabilities.max_resistance, 2000);
+        assert!(capabilities.supports_basic_resistance);
+        assert!(!capabilities.supports_target_power);
+    }
+
+    #[test]
+    fn test_trainer_info_from_pages() {
+        let mut info = TrainerInfo::default();
+        assert_eq!(
+            info.missing_pages(),
+            vec![
+                PAGE_FE_CAPABILITIES,
+                PAGE_MANUFACTURER_ID,
+                PAGE_PRODUCT_INFO
+            ]
+        );
+
+        let pages = [
+            [0x36, 0xFF, 0xFF, 0xFF, 0xFF, 0xD0, 0x07, 0x07],
+            [0x50, 0xFF, 0xFF, 0x01, 0x20, 0x00, 0x3C, 0x0A],
+            [0x51, 0xFF, 0xFF, 25, 0x87, 0xD6, 0x12, 0x00],
+            [0x10, 25, 0, 0, 0x8D, 0x20, 0xFF, 0x30],
+        ];
+        let recorded: Vec<bool> = pages
+            .iter()
+            .map(|data| info.update(&FecParser::parse_data_page(data).unwrap()))
+            .collect();
+        assert_eq!(recorded, vec![true, true, true, false]);
+
+        assert_eq!(info.capabilities.unwrap().max_resistance, 2000);
+        assert!(info.capabilities.unwrap().supports_simulation);
+        assert_eq!(info.manufacturer_id, Some(32));
+        assert_eq!(info.model_number, Some(2620));
+        assert_eq!(info.serial_number, Some(1234567));
+        assert_eq!(info.firmware.as_deref(), Some("2.5"));
+        assert_eq!(info.software_version(), Some(250));
+        assert_eq!(info.name.as_deref(), Some("Wahoo"));
+        assert_eq!(info.quirks(), TrainerQuirks::default());
+        assert!(info.missing_pages().is_empty());
+    }
+
+    #[test]
+    fn test_firmware_version() {
+        assert_eq!(firmware_version(25, 0xFF), "2.5");
+        assert_eq!(firmware_version(25, 3), "2.503");
+    }
+
+    #[test]
+    fn test_info_requests_are_spaced_and_limited() {
+        let mut info = TrainerInfo::default();
+        let start = Instant::now();
+
+        assert_eq!(info.due_requests(start).len(), 3);
+        assert!(info.due_requests(start + Duration::from_secs(1)).is_empty());
+        assert_eq!(info.due_requests(start + Duration::from_secs(2)).len(), 3);
+        assert_eq!(info.due_requests(start + Duration::from_secs(4)).len(), 3);
+        assert!(info.due_requests(start + Duration::from_secs(6)).is_empty());
+    }
+
+    #[test]
+    fn test_fallback_only_for_basic_resistance_trainers() {
+        let basic = FecCapabilities {
+            supports_basic_resistance: true,
+            ..Default::default()
+        };
+        let smart = FecCapabilities {
+            supports_basic_resistance: true,
+            supports_target_power: true,
+            supports_simulation: true,
+            ..Default::default()
+        };
+
+        let controller = FecController::new(0).with_capabilities(Some(basic));
+        assert!(controller.use_resistance_fallback(|c| c.supports_target_power));
+
+        let controller = FecController::new(0).with_capabilities(Some(smart));
+        assert!(!controller.use_resistance_fallback(|c| c.supports_target_power));
+
+        // Unknown capabilities: send the requested mode as-is
+        let controller = FecController::new(0);
+        assert!(!controller.use_resistance_fallback(|c| c.supports_simulation));
+    }
+}
@@ -0,0 +1,494 @@
+// ANT+ Device Discovery
+//
+// Search phase before connecting. One wildcard channel per profile
+// (FE-C, HRM) with extended messages enabled, so every broadcast names the
+// device that sent it. Each device found is added to its channel's
+// exclusion list and the search restarts, until the search times out, the
+// list is full, or the overall deadline passes.
+
+use super::channel::{
+    AntChannel, ChannelEvent, ExtendedData, EVENT_CHANNEL_CLOSED, FEC_DEVICE_TYPE, HRM_DEVICE_TYPE,
+    ID_LIST_MAX_SIZE, LIB_CONFIG_CHANNEL_ID, LIB_CONFIG_RSSI, MESG_BROADCAST_DATA,
+};
+use super::fec::{FecCommand, FecDataPage, FecParser, PAGE_MANUFACTURER_ID};
+use super::hrm::HrmParser;
+use super::manufacturer::device_name;
+use super::transport::AntTransport;
+use serde::Serialize;
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Default length of a discovery search
+pub const DEFAULT_SEARCH_TIMEOUT: Duration = Duration::from_secs(10);
+
+// Time spent on each found device waiting for its manufacturer page
+const IDENTIFY_TIME: Duration = Duration::from_millis(2000);
+
+// Delay between configuration messages, as in connect
+const CONFIG_MESSAGE_DELAY: Duration = Duration::from_millis(50);
+
+/// A sensor heard during discovery
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct DiscoveredDevice {
+    pub device_number: u16,
+    pub device_type: u8,
+    pub transmission_type: u8,
+    /// ANT+ manufacturer ID (FE-C page 80, HRM page 2)
+    pub manufacturer_id: Option<u16>,
+    /// Model number (FE-C page 80 only)
+    pub model_number: Option<u16>,
+    /// Manufacturer and model name, when the manufacturer is known
+    pub name: Option<String>,
+    /// Last received signal strength in dBm
+    pub rssi: Option<i8>,
+    /// Broadcasts received from this device
+    pub message_count: u32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum SearchState {
+    /// Wildcard search open, waiting for any not-yet-excluded device
+    Searching,
+    /// Tracking a found device until its manufacturer page arrives
+    Identifying {
+        device: usize,
+        until: Instant,
+    },
+    /// Channel closing so the device can be added to the exclusion list
+    Excluding {
+        device: usize,
+    },
+    Done,
+}
+
+struct SearchChannel {
+    channel: AntChannel,
+    device_type: u8,
+    excluded: u8,
+    state: SearchState,
+}
+
+impl SearchChannel {
+    /// Channel configuration up to (not including) open
+    fn config_sequence(&self) -> Vec<Vec<u8>> {
+        let ch = &self.channel;
+        if self.device_type == HRM_DEVICE_TYPE {
+            vec![
+                ch.assign_channel(),
+                ch.set_channel_id_hrm(),
+                ch.set_channel_frequency(),
+                ch.set_channel_period_hrm(),
+            ]
+        } else {
+            vec![
+                ch.assign_channel(),
+                ch.set_channel_id(),
+                ch.set_channel_frequency(),
+                ch.set_channel_period(),
+            ]
+        }
+    }
+}
+
+/// Discovery search over FE-C (channel 0) and HRM (channel 1)
+///
+/// `handle_message` and `poll` return the messages to write next, so the
+/// search can be driven by `run` or stepped directly.
+pub struct DeviceSearch {
+    channels: Vec<SearchChannel>,
+    devices: Vec<DiscoveredDevice>,
+}
+
+impl DeviceSearch {
+    pub fn new() -> Self {
+        let channels = [(0, FEC_DEVICE_TYPE), (1, HRM_DEVICE_TYPE)]
+            .into_iter()
+            .map(|(number, device_type)| SearchChannel {
+                channel: AntChannel::new(number),
+                device_type,
+                excluded: 0,
+                state: SearchState::Searching,
+            })
+            .collect();
+
+        Self {
+            channels,
+            devices: Vec::new(),
+        }
+    }
+
+    /// Reset the stick, enable extended messages and open the search channels
+    pub fn start_sequence(&self) -> Vec<Vec<u8>> {
+        let mut sequence = vec![
+            AntChannel::reset_system(),
+            self.channels[0].channel.set_network_key(),
+            AntChannel::set_lib_config(LIB_CONFIG_CHANNEL_ID | LIB_CONFIG_RSSI),
+        ];
+
+        for sc in &self.channels {
+            sequence.extend(sc.config_sequence());
+            sequence.push(sc.channel.open_channel());
+        }
+        sequence
+    }
+
+    /// Close the search channels and turn extended messages off again
+    pub fn stop_sequence(&self) -> Vec<Vec<u8>> {
+        let mut sequence: Vec<Vec<u8>> = self
+            .channels
+            .iter()
+            .map(|sc| sc.channel.close_channel())
+            .collect();
+        sequence.push(AntChannel::set_lib_config(0));
+        sequence
+    }
+
+    /// Whether every channel has finished searching
+    pub fn is_done(&self) -> bool {
+        self.channels.iter().all(|sc| sc.state == SearchState::Done)
+    }
+
+    /// Devices found so far, in discovery order
+    pub fn devices(&self) -> &[DiscoveredDevice] {
+        &self.devices
+    }
+
+    /// Process one received message
+    pub fn handle_message(
+        &mut self,
+        msg_id: u8,
+        channel: u8,
+        data: &[u8],
+        now: Instant,
+    ) -> Vec<Vec<u8>> {
+        let Some(index) = self
+            .channels
+            .iter()
+            .position(|sc| sc.channel.channel_number() == channel)
+        else {
+            return Vec::new();
+        };
+
+        if let Some(ChannelEvent::Event { code, .. }) = ChannelEvent::parse(msg_id, data) {
+            if code == EVENT_CHANNEL_CLOSED {
+                return self.channel_closed(index);
+            }
+            return Vec::new();
+        }
+
+        if msg_id != MESG_BROADCAST_DATA || data.len() < 9 {
+            return Vec::new();
+        }
+
+        // Without the channel ID we can't tell devices apart
+        let Some(extended) = ExtendedData::parse(data) else {
+            return Vec::new();
+        };
+        let (Some(device_number), Some(transmission_type)) =
+            (extended.device_number, extended.transmission_type)
+        else {
+            return Vec::new();
+        };
+
+        let device_type = self.channels[index].device_type;
+        let device = self.record(device_number, device_type, transmission_type, extended.rssi);
+        self.record_page(device, &data[1..9]);
+
+        match self.channels[index].state {
+            SearchState::Searching => {
+                self.channels[index].state = SearchState::Identifying {
+                    device,
+                    until: now + IDENTIFY_TIME,
+                };
+
+                if self.devices[device].manufacturer_id.is_some() {
+                    return self.exclude(index, device);
+                }
+                if device_type == FEC_DEVICE_TYPE {
+                    // Trainers broadcast page 80 rarely; ask for it
+                    let request = FecCommand::request_page(PAGE_MANUFACTURER_ID).encode();
+                    return vec![self.channels[index].channel.acknowledged_data(&request)];
+                }
+                Vec::new()
+            }
+            SearchState::Identifying {
+                device: current, ..
+            } if current == device && self.devices[device].manufacturer_id.is_some() => {
+                self.exclude(index, device)
+            }
+            _ => Vec::new(),
+        }
+    }
+
+    /// Advance timers; call regularly while searching
+    pub fn poll(&mut self, now: Instant) -> Vec<Vec<u8>> {
+        let mut messages = Vec::new();
+
+        for index in 0..self.channels.len() {
+            if let SearchState::Identifying { device, until } = self.channels[index].state {
+                if now >= until {
+                    messages.extend(self.exclude(index, device));
+                }
+            }
+        }
+        messages
+    }
+
+    /// Run a complete search on an open transport
+    ///
+    /// Returns the devices found, strongest signal first per device type.
+    pub fn run(
+        mut self,
+        ant: &mut dyn AntTransport,
+        timeout: Duration,
+    ) -> Result<Vec<DiscoveredDevice>, String> {
+        for msg in self.start_sequence() {
+            ant.write(&msg)?;
+            thread::sleep(CONFIG_MESSAGE_DELAY);
+        }
+
+        let deadline = Instant::now() + timeout;
+        while Instant::now() < deadline && !self.is_done() {
+            for (msg_id, channel, data) in ant.read_messages()? {
+                for msg in self.handle_message(msg_id, channel, &data, Instant::now()) {
+                    ant.write(&msg)?;
+                }
+            }
+            for msg in self.poll(Instant::now()) {
+                ant.write(&msg)?;
+            }
+        }
+
+        for msg in self.stop_sequence() {
+            ant.write(&msg)?;
+        }
+
+        let mut devices = self.devices;
+        devices.sort_by_key(|d| (d.device_type, std::cmp::Reverse(d.rssi)));
+        Ok(devices)
+    }
+
+    /// Add or update a device; returns its index
+    fn record(
+        &mut self,
+        device_number: u16,
+        device_type: u8,
+        transmission_type: u8,
+        rssi: Option<i8>,
+    ) -> usize {
+        let index = match self
+            .devices
+            .iter()
+            .position(|d| d.device_number == device_number && d.device_type == device_type)
+        {
+            Some(index) => index,
+            None => {
+                self.devices.push(DiscoveredDevice {
+                    device_number,
+                    device_type,
+                    transmission_type,
+                    manufacturer_id: None,
+                    model_number: None,
+                    name: None,
+                    rssi: None,
+                    message_count: 0,
+                });
+                self.devices.len() - 1
+            }
+        };
+
+        let device = &mut self.devices[index];
+        device.message_count += 1;
+        if rssi.is_some() {
+            device.rssi = rssi;
+        }
+        index
+    }
+
+    /// Pick up the manufacturer from FE-C page 80 or HRM page 2
+    fn record_page(&mut self, index: usize, page: &[u8]) {
+        let device = &mut self.devices[index];
+
+        if device.device_type == FEC_DEVICE_TYPE {
+            if let Some(FecDataPage::ManufacturerId {
+                manufacturer_id,
+                model_number,
+                ..
+            }) = FecParser::parse_data_page(page)
+            {
+                device.manufacturer_id = Some(manufacturer_id);
+                device.model_number = Some(model_number);
+                device.name = device_name(manufacturer_id, Some(model_number));
+            }
+        } else if let Some((manufacturer_id, _)) = HrmParser::parse_manufacturer_info(page) {
+            device.manufacturer_id = Some(manufacturer_id as u16);
+            device.name = device_name(manufacturer_id as u16, None);
+        }
+    }
+
+    /// Done with a device: close the channel so it can be excluded
+    fn exclude(&mut self, index: usize, device: usize) -> Vec<Vec<u8>> {
+        let sc = &mut self.channels[index];
+        sc.state = if sc.excluded < ID_LIST_MAX_SIZE {
+            SearchState::Excluding { device }
+        } else {
+            SearchState::Done
+        };
+        vec![sc.channel.close_channel()]
+    }
+
+    /// Channel closed: either restart the search past the excluded device,
+    /// or the search timed out with nothing left to find
+    fn channel_closed(&mut self, index: usize) -> Vec<Vec<u8>> {
+        let SearchState::Excluding { device } = self.channels[index].state else {
+            self.channels[index].state = SearchState::Done;
+            return Vec::new();
+        };
+
+        let found = &self.devices[device];
+        let sc = &mut self.channels[index];
+        let messages = vec![
+            sc.channel.add_to_id_list(
+                found.device_number,
+                found.device_type,
+                found.transmission_type,
+                sc.excluded,
+            ),
+            sc.channel.config_exclusion_list(sc.excluded + 1),
+            sc.channel.open_channel(),
+        ];
+
+        sc.excluded += 1;
+        sc.state = SearchState::Searching;
+        messages
+    }
+}
+
+impl Default for DeviceSearch {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ant::channel::{MESG_CLOSE_CHANNEL, MESG_EVENT_ID, MESG_RESPONSE_EVENT};
+    use crate::ant::sim::{SimDevice, SimulatedStick};
+
+    fn broadcast(channel: u8, page: [u8; 8], device_number: u16, device_type: u8) -> Vec<u8> {
+        let mut data = vec![channel];
+        data.extend_from_slice(&page);
+        data.push(LIB_CONFIG_CHANNEL_ID | LIB_CONFIG_RSSI);
+        data.extend_from_slice(&device_number.to_le_bytes());
+        data.extend_from_slice(&[device_type, 0x05, 0x20, (-60i8) as u8, 0x00]);
+        data
+    }
+
+    fn msg_ids(messages: &[Vec<u8>]) -> Vec<u8> {
+        messages.iter().map(|m| m[2]).collect()
+    }
+
+    #[test]
+    fn test_found_device_is_excluded_after_identify_timeout() {
+        let mut search = DeviceSearch::new();
+        let start = Instant::now();
+        let page_16 = [0x10, 25, 0, 0, 0, 0, 0xFF, 0x30];
+
+        // First broadcast: record the device and ask for page 80
+        let out = search.handle_message(
+            MESG_BROADCAST_DATA,
+            0,
+            &broadcast(0, page_16, 12345, FEC_DEVICE_TYPE),
+            start,
+        );
+        assert_eq!(out.len(), 1);
+        assert_eq!(out[0][4], 0x46); // Page 70 request
+
+        // No page 80: give up on identifying and close
+        assert!(search.poll(start + IDENTIFY_TIME / 2).is_empty());
+        let out = search.poll(start + IDENTIFY_TIME);
+        assert_eq!(msg_ids(&out), vec![MESG_CLOSE_CHANNEL]);
+
+        // Closed: exclude the device and search again
+        let closed = [0, MESG_EVENT_ID, EVENT_CHANNEL_CLOSED];
+        let out = search.handle_message(MESG_RESPONSE_EVENT, 0, &closed, start);
+        assert_eq!(msg_ids(&out), vec![0x59, 0x5A, 0x4B]);
+        assert_eq!(&out[0][3..9], &[0, 0x39, 0x30, FEC_DEVICE_TYPE, 0x05, 0]);
+
+        assert_eq!(search.devices().len(), 1);
+        assert_eq!(search.devices()[0].rssi, Some(-60));
+        assert!(!search.is_done());
+    }
+
+    #[test]
+    fn test_manufacturer_page_finishes_identification() {
+        let mut search = DeviceSearch::new();
+        let now = Instant::now();
+        let page_80 = [0x50, 0xFF, 0xFF, 0x01, 0x20, 0x00, 0x3C, 0x0A];
+
+        search.handle_message(
+            MESG_BROADCAST_DATA,
+            0,
+            &broadcast(0, [0x10, 25, 0, 0, 0, 0, 0xFF, 0x30], 7, FEC_DEVICE_TYPE),
+            now,
+        );
+        let out = search.handle_message(
+            MESG_BROADCAST_DATA,
+            0,
+            &broadcast(0, page_80, 7, FEC_DEVICE_TYPE),
+            now,
+        );
+
+        assert_eq!(msg_ids(&out), vec![MESG_CLOSE_CHANNEL]);
+        assert_eq!(search.devices()[0].manufacturer_id, Some(32));
+        assert_eq!(search.devices()[0].model_number, Some(2620));
+        assert_eq!(search.devices()[0].name.as_deref(), Some("Wahoo"));
+        assert_eq!(search.devices()[0].message_count, 2);
+    }
+
+    #[test]
+    fn test_search_timeout_finishes_channel() {
+        let mut search = DeviceSearch::new();
+        let now = Instant::now();
+
+        for channel in 0..2 {
+            let closed = [channel, MESG_EVENT_ID, EVENT_CHANNEL_CLOSED];
+            assert!(search
+                .handle_message(MESG_RESPONSE_EVENT, channel, &closed, now)
+                .is_empty());
+        }
+
+        assert!(search.is_done());
+        assert!(search.devices().is_empty());
+    }
+
+    #[test]
+    fn test_run_lists_every_simulated_device() {
+        let mut stick = SimulatedStick::new().with_devices(vec![
+            SimDevice::new(FEC_DEVICE_TYPE, 12345, 0x05, -70),
+            SimDevice::new(FEC_DEVICE_TYPE, 222, 0x05, -50),
+            SimDevice::new(HRM_DEVICE_TYPE, 54321, 0x01, -65),
+        ]);
+        stick.open().unwrap();
+
+        let devices = DeviceSearch::new()
+            .run(&mut stick, DEFAULT_SEARCH_TIMEOUT)
+            .unwrap();
+
+        let found: Vec<(u8, u16)> = devices
+            .iter()
+            .map(|d| (d.device_type, d.device_number))
+            .collect();
+        // Sorted per device type, strongest signal first
+        assert_eq!(
+            found,
+            vec![
+                (FEC_DEVICE_TYPE, 222),
+                (FEC_DEVICE_TYPE, 12345),
+                (HRM_DEVICE_TYPE, 54321)
+            ]
+        );
+        assert!(devices.iter().all(|d| d.manufacturer_id.is_some()));
+    }
+}
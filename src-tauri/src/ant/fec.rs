@@ -6,13 +6,25 @@
 // Reference: ANT+ Fitness Equipment Device Profile
 
 use super::TrainerData;
+use serde::{Deserialize, Serialize};
 
 // FE-C Data Page Numbers
+pub const PAGE_CALIBRATION_REQUEST: u8 = 0x01; // 1
+pub const PAGE_CALIBRATION_PROGRESS: u8 = 0x02; // 2
 pub const PAGE_GENERAL_FE_DATA: u8 = 0x10; // 16
 pub const PAGE_GENERAL_SETTINGS: u8 = 0x11; // 17
 pub const PAGE_GENERAL_FE_METABOLIC: u8 = 0x12; // 18
+pub const PAGE_TREADMILL_DATA: u8 = 0x13; // 19
+pub const PAGE_ELLIPTICAL_DATA: u8 = 0x14; // 20
+pub const PAGE_ROWER_DATA: u8 = 0x16; // 22
+pub const PAGE_CLIMBER_DATA: u8 = 0x17; // 23
+pub const PAGE_NORDIC_SKIER_DATA: u8 = 0x18; // 24
 pub const PAGE_SPECIFIC_TRAINER_DATA: u8 = 0x19; // 25
 pub const PAGE_TRAINER_TORQUE: u8 = 0x1A; // 26
+pub const PAGE_BASIC_RESISTANCE: u8 = 0x30; // 48
+pub const PAGE_TARGET_POWER: u8 = 0x31; // 49
+pub const PAGE_WIND_RESISTANCE: u8 = 0x32; // 50
+pub const PAGE_TRACK_RESISTANCE: u8 = 0x33; // 51
 pub const PAGE_FE_CAPABILITIES: u8 = 0x36; // 54
 pub const PAGE_USER_CONFIG: u8 = 0x37; // 55
 pub const PAGE_REQUEST_DATA: u8 = 0x46; // 70
@@ -41,6 +53,65 @@ pub const FE_STATE_READY: u8 = 2;
 pub const FE_STATE_IN_USE: u8 = 3;
 pub const FE_STATE_FINISHED: u8 = 4;
 
+// Calibration modes (Page 1 request/response and Page 2 byte 1)
+pub const CALIBRATION_ZERO_OFFSET: u8 = 0x40;
+pub const CALIBRATION_SPIN_DOWN: u8 = 0x80;
+
+// Calibration conditions (Page 2 byte 2, two bits each)
+pub const CALIBRATION_CONDITION_NOT_APPLICABLE: u8 = 0;
+pub const CALIBRATION_CONDITION_TOO_LOW: u8 = 1;
+pub const CALIBRATION_CONDITION_OK: u8 = 2;
+pub const CALIBRATION_CONDITION_TOO_HIGH: u8 = 3;
+
+// Target power limits (Page 25 byte 7, bits 0-1)
+pub const TARGET_POWER_AT_TARGET: u8 = 0; // Or no target set
+pub const TARGET_POWER_SPEED_TOO_LOW: u8 = 1;
+pub const TARGET_POWER_SPEED_TOO_HIGH: u8 = 2;
+pub const TARGET_POWER_LIMIT_REACHED: u8 = 3; // Limit of unknown cause
+
+// Command Status (from Page 71 byte 3)
+pub const COMMAND_STATUS_PASS: u8 = 0;
+pub const COMMAND_STATUS_FAIL: u8 = 1;
+pub const COMMAND_STATUS_NOT_SUPPORTED: u8 = 2;
+pub const COMMAND_STATUS_REJECTED: u8 = 3;
+pub const COMMAND_STATUS_PENDING: u8 = 4;
+pub const COMMAND_STATUS_UNINITIALIZED: u8 = 0xFF;
+
+// Page 26 units: wheel period in 1/2048s, accumulated torque in 1/32 Nm
+const WHEEL_PERIOD_UNITS_PER_SECOND: f32 = 2048.0;
+const TORQUE_UNITS_PER_NM: f32 = 32.0;
+
+// 700x23c road wheel, used until the rider configures their own
+pub const DEFAULT_WHEEL_CIRCUMFERENCE_M: f32 = 2.096;
+
+// Page 26 power this far from Page 25 power marks the reading as suspect:
+// more than the ratio of the larger value and more than the watts
+const POWER_MISMATCH_RATIO: f32 = 0.25;
+const POWER_MISMATCH_MIN_WATTS: u16 = 50;
+
+// Request Data Page (Page 70) command type
+const REQUEST_COMMAND_TYPE_DATA_PAGE: u8 = 0x01;
+
+// Page 49 target power range (0.25W units)
+const TARGET_POWER_MAX_WATTS: f32 = 4000.0;
+
+// Page 51 grade range (0.01% units, offset by -200%)
+const GRADE_MIN_PERCENT: f32 = -200.0;
+const GRADE_MAX_PERCENT: f32 = 200.0;
+const CRR_UNIT: f32 = 0.00005;
+
+// Page 50 ranges
+const WIND_SPEED_OFFSET_KMH: f32 = 127.0;
+
+// Page 55 ranges
+const USER_WEIGHT_MAX_KG: f32 = 655.34; // 0.01kg units
+const BIKE_WEIGHT_MAX_KG: f32 = 50.0; // 0.05kg units
+const WHEEL_DIAMETER_MAX_M: f32 = 2.54; // 0.01m units
+const GEAR_RATIO_UNIT: f32 = 0.03;
+
+// "Use the trainer's default" marker for optional command fields
+const FIELD_INVALID: u8 = 0xFF;
+
 /// Parse ANT+ FE-C data pages
 pub struct FecParser {
     // Accumulated values for calculating deltas
@@ -48,6 +119,27 @@ pub struct FecParser {
     last_accumulated_power: u16,
     last_distance: u8,
     last_elapsed_time: u8,
+    seen_trainer_data: bool, // A Page 25 arrived, so the last values are real
+    seen_general_data: bool, // A Page 16 arrived
+    // Totals across Page 16 rollovers since the first page
+    total_distance: u32,                   // meters
+    total_elapsed_time: u32,               // 0.25s units
+    last_calories: Option<u8>,             // Page 18 accumulated calories, if reported
+    total_calories: u32,                   // kcal across Page 18 rollovers
+    last_instantaneous_power: Option<u16>, // Page 25, to cross-check Page 26
+    // Page 26 values for the deltas between torque events
+    last_torque_event_count: u8,
+    last_wheel_ticks: u8,
+    last_wheel_period: u16,
+    last_accumulated_torque: u16,
+    seen_torque_data: bool,
+    wheel_circumference: f32, // meters
+    // Totals across the rollovers of the equipment pages (19-24)
+    last_stride_count: Option<u8>,
+    total_strides: u32,
+    last_positive_vertical: Option<u8>,
+    last_negative_vertical: Option<u8>,
+    total_vertical_distance: i32, // 0.1m, climbed minus descended
 }
 
 impl FecParser {
@@ -57,6 +149,74 @@ impl FecParser {
             last_accumulated_power: 0,
             last_distance: 0,
             last_elapsed_time: 0,
+            seen_trainer_data: false,
+            seen_general_data: false,
+            total_distance: 0,
+            total_elapsed_time: 0,
+            last_calories: None,
+            total_calories: 0,
+            last_instantaneous_power: None,
+            last_torque_event_count: 0,
+            last_wheel_ticks: 0,
+            last_wheel_period: 0,
+            last_accumulated_torque: 0,
+            seen_torque_data: false,
+            wheel_circumference: DEFAULT_WHEEL_CIRCUMFERENCE_M,
+            last_stride_count: None,
+            total_strides: 0,
+            last_positive_vertical: None,
+            last_negative_vertical: None,
+            total_vertical_distance: 0,
+        }
+    }
+
+    /// Wheel circumference in meters, for the Page 26 wheel speed
+    pub fn set_wheel_circumference(&mut self, circumference_m: f32) {
+        self.wheel_circumference = circumference_m;
+    }
+
+    /// Distance covered since the first Page 16, in meters
+    pub fn total_distance(&self) -> u32 {
+        self.total_distance
+    }
+
+    /// Time elapsed since the first Page 16, in seconds
+    pub fn total_elapsed_time(&self) -> f32 {
+        self.total_elapsed_time as f32 * 0.25
+    }
+
+    /// Calories burned since the first Page 18, if the trainer counts them
+    pub fn total_calories(&self) -> Option<u32> {
+        self.last_calories.map(|_| self.total_calories)
+    }
+
+    /// Strides (or rower strokes) since the first equipment page that counts them
+    pub fn total_strides(&self) -> Option<u32> {
+        self.last_stride_count.map(|_| self.total_strides)
+    }
+
+    /// Net vertical distance since the first page reporting it, in meters
+    pub fn total_vertical_distance(&self) -> Option<f32> {
+        (self.last_positive_vertical.is_some() || self.last_negative_vertical.is_some())
+            .then(|| self.total_vertical_distance as f32 / 10.0)
+    }
+
+    /// Update TrainerData from a page parsed by `parse_broadcast`, plus the
+    /// distance, elapsed time, calorie, stride and vertical distance totals
+    pub fn apply(&self, data: &mut TrainerData, page: &FecDataPage) {
+        page.update_trainer_data(data);
+        if self.seen_general_data {
+            data.trainer_distance = self.total_distance;
+            data.trainer_elapsed_time = self.total_elapsed_time();
+        }
+        if let Some(calories) = self.total_calories() {
+            data.calories = Some(calories);
+        }
+        if let Some(strides) = self.total_strides() {
+            data.strides = Some(strides);
+        }
+        if let Some(vertical_distance) = self.total_vertical_distance() {
+            data.vertical_distance = Some(vertical_distance);
         }
     }
 
@@ -72,9 +232,36 @@ impl FecParser {
         let page_number = data[0];
 
         match page_number {
-            PAGE_GENERAL_FE_DATA => Self::parse_general_fe_data(data),
+            PAGE_CALIBRATION_REQUEST => Self::parse_calibration_response(data),
+            PAGE_CALIBRATION_PROGRESS => Self::parse_calibration_progress(data),
+            PAGE_GENERAL_FE_DATA => {
+                let page = Self::parse_general_fe_data(data);
+                if let Some(page) = &page {
+                    self.accumulate_general_fe_data(page);
+                }
+                page
+            }
+            PAGE_GENERAL_SETTINGS => Self::parse_general_settings(data),
+            PAGE_GENERAL_FE_METABOLIC => {
+                let page = Self::parse_general_metabolic(data);
+                if let Some(page) = &page {
+                    self.accumulate_calories(page);
+                }
+                page
+            }
+            PAGE_TREADMILL_DATA
+            | PAGE_ELLIPTICAL_DATA
+            | PAGE_ROWER_DATA
+            | PAGE_CLIMBER_DATA
+            | PAGE_NORDIC_SKIER_DATA => {
+                let page = Self::parse_equipment_data(data);
+                if let Some(page) = &page {
+                    self.accumulate_equipment_data(page);
+                }
+                page
+            }
             PAGE_SPECIFIC_TRAINER_DATA => self.parse_specific_trainer_data(data),
-            PAGE_TRAINER_TORQUE => Self::parse_trainer_torque(data),
+            PAGE_TRAINER_TORQUE => self.parse_trainer_torque_data(data),
             PAGE_MANUFACTURER_ID => Self::parse_manufacturer_id(data),
             PAGE_PRODUCT_INFO => Self::parse_product_info(data),
             PAGE_FE_CAPABILITIES => Self::parse_fe_capabilities(data),
@@ -125,6 +312,178 @@ impl FecParser {
         })
     }
 
+    /// Add the Page 16 elapsed time and distance since the previous page,
+    /// allowing for their 64s and 256m rollovers
+    fn accumulate_general_fe_data(&mut self, page: &FecDataPage) {
+        let FecDataPage::GeneralFE {
+            elapsed_time_quarter_sec,
+            distance_meters,
+            distance_enabled,
+            ..
+        } = *page
+        else {
+            return;
+        };
+
+        if self.seen_general_data {
+            let elapsed = elapsed_time_quarter_sec.wrapping_sub(self.last_elapsed_time);
+            self.total_elapsed_time += elapsed as u32;
+            if distance_enabled {
+                let distance = distance_meters.wrapping_sub(self.last_distance);
+                self.total_distance += distance as u32;
+            }
+        }
+
+        self.last_elapsed_time = elapsed_time_quarter_sec;
+        self.last_distance = distance_meters;
+        self.seen_general_data = true;
+    }
+
+    /// Page 17 (0x11): General Settings
+    ///
+    /// Cycle length, incline and resistance level as set on the equipment
+    fn parse_general_settings(data: &[u8]) -> Option<FecDataPage> {
+        // Bytes 1-2 are reserved
+        let cycle_length = data[3]; // 0.01m, 0xFF = invalid
+        let incline = i16::from_le_bytes([data[4], data[5]]); // 0.01%, 0x7FFF = invalid
+        let resistance_level = data[6]; // 0.5%, 0-200
+        let fe_state = (data[7] >> 4) & 0x07;
+
+        Some(FecDataPage::GeneralSettings {
+            cycle_length_cm: (cycle_length != 0xFF).then_some(cycle_length),
+            incline_hundredths: (incline != i16::MAX).then_some(incline),
+            resistance_level_half_percent: (resistance_level <= 200).then_some(resistance_level),
+            fe_state,
+        })
+    }
+
+    /// Page 18 (0x12): General FE Metabolic Data
+    ///
+    /// Trainer-computed METs, calorie burn rate and accumulated calories
+    fn parse_general_metabolic(data: &[u8]) -> Option<FecDataPage> {
+        // Byte 1 is reserved
+        let mets = u16::from_le_bytes([data[2], data[3]]); // 0.01 MET, 0xFFFF = invalid
+        let burn_rate = u16::from_le_bytes([data[4], data[5]]); // 0.1 kcal/h, 0xFFFF = invalid
+        let accumulated_calories = data[6]; // kcal, rolls over at 256
+        let calories_enabled = (data[7] & 0x01) != 0;
+        let fe_state = (data[7] >> 4) & 0x07;
+
+        Some(FecDataPage::GeneralMetabolic {
+            mets_hundredths: (mets != 0xFFFF).then_some(mets),
+            caloric_burn_rate_tenths: (burn_rate != 0xFFFF).then_some(burn_rate),
+            accumulated_calories: calories_enabled.then_some(accumulated_calories),
+            fe_state,
+        })
+    }
+
+    /// Add the Page 18 calories since the previous page, allowing for the
+    /// 256 kcal rollover
+    fn accumulate_calories(&mut self, page: &FecDataPage) {
+        let FecDataPage::GeneralMetabolic {
+            accumulated_calories: Some(calories),
+            ..
+        } = *page
+        else {
+            return;
+        };
+
+        if let Some(last) = self.last_calories {
+            self.total_calories += calories.wrapping_sub(last) as u32;
+        }
+        self.last_calories = Some(calories);
+    }
+
+    /// Pages 19-24: data specific to treadmills, ellipticals, rowers,
+    /// climbers and nordic skiers
+    ///
+    /// Byte 7 carries the page's capability bits (which accumulated fields
+    /// are transmitted) below the FE state.
+    fn parse_equipment_data(data: &[u8]) -> Option<FecDataPage> {
+        let capabilities = data[7] & 0x0F;
+        let fe_state = (data[7] >> 4) & 0x07;
+        let valid = |value: u8| (value != 0xFF).then_some(value);
+        let power = u16::from_le_bytes([data[5], data[6]]); // W, 0xFFFF = invalid
+        let instantaneous_power = (power != 0xFFFF).then_some(power);
+
+        match data[0] {
+            // Bytes 1-2 are reserved; vertical distances in 0.1m, rolling over at 25.6m
+            PAGE_TREADMILL_DATA => Some(FecDataPage::Treadmill {
+                cadence: valid(data[3]),
+                negative_vertical_distance: (capabilities & 0x01 != 0).then_some(data[4]),
+                positive_vertical_distance: (capabilities & 0x02 != 0).then_some(data[5]),
+                fe_state,
+            }),
+            // Byte 1 is reserved
+            PAGE_ELLIPTICAL_DATA => Some(FecDataPage::Elliptical {
+                stride_count: (capabilities & 0x02 != 0).then_some(data[2]),
+                positive_vertical_distance: (capabilities & 0x01 != 0).then_some(data[3]),
+                cadence: valid(data[4]),
+                instantaneous_power,
+                fe_state,
+            }),
+            // Bytes 1-2 are reserved
+            PAGE_ROWER_DATA => Some(FecDataPage::Rower {
+                stroke_count: (capabilities & 0x01 != 0).then_some(data[3]),
+                cadence: valid(data[4]),
+                instantaneous_power,
+                fe_state,
+            }),
+            PAGE_CLIMBER_DATA => Some(FecDataPage::Climber {
+                stride_cycles: (capabilities & 0x01 != 0).then_some(data[3]),
+                cadence: valid(data[4]),
+                instantaneous_power,
+                fe_state,
+            }),
+            PAGE_NORDIC_SKIER_DATA => Some(FecDataPage::NordicSkier {
+                stride_count: (capabilities & 0x01 != 0).then_some(data[3]),
+                cadence: valid(data[4]),
+                instantaneous_power,
+                fe_state,
+            }),
+            _ => None,
+        }
+    }
+
+    /// Add the strides and vertical distance since the previous equipment
+    /// page, allowing for their 256-count and 25.6m rollovers
+    fn accumulate_equipment_data(&mut self, page: &FecDataPage) {
+        let (strides, positive, negative) = match *page {
+            FecDataPage::Treadmill {
+                negative_vertical_distance,
+                positive_vertical_distance,
+                ..
+            } => (None, positive_vertical_distance, negative_vertical_distance),
+            FecDataPage::Elliptical {
+                stride_count,
+                positive_vertical_distance,
+                ..
+            } => (stride_count, positive_vertical_distance, None),
+            FecDataPage::Rower { stroke_count, .. } => (stroke_count, None, None),
+            FecDataPage::Climber { stride_cycles, .. } => (stride_cycles, None, None),
+            FecDataPage::NordicSkier { stride_count, .. } => (stride_count, None, None),
+            _ => return,
+        };
+
+        if let Some(strides) = strides {
+            if let Some(last) = self.last_stride_count {
+                self.total_strides += strides.wrapping_sub(last) as u32;
+            }
+            self.last_stride_count = Some(strides);
+        }
+        if let Some(positive) = positive {
+            if let Some(last) = self.last_positive_vertical {
+                self.total_vertical_distance += positive.wrapping_sub(last) as i32;
+            }
+            self.last_positive_vertical = Some(positive);
+        }
+        if let Some(negative) = negative {
+            if let Some(last) = self.last_negative_vertical {
+                self.total_vertical_distance -= negative.wrapping_sub(last) as i32;
+            }
+            self.last_negative_vertical = Some(negative);
+        }
+    }
+
     /// Page 25 (0x19): Specific Trainer/Stationary Bike Data
     ///
     /// Contains trainer-specific data:
@@ -150,9 +509,10 @@ impl FecParser {
         // Trainer status in upper 4 bits of byte 6
         let trainer_status_bits = (power_msb_and_status >> 4) & 0x0F;
 
-        // Flags byte 7
+        // Flags byte 7: target power limits, FE state in the upper nibble
         let flags = data[7];
-        let target_power_limits = flags & 0x07;
+        let target_power_limits = flags & 0x03;
+        let fe_state = (flags >> 4) & 0x07;
 
         // Calculate average power if we have previous values
         let avg_power = self.calculate_average_power(update_event_count, accumulated_power);
@@ -160,6 +520,8 @@ impl FecParser {
         // Update last values
         self.last_event_count = update_event_count;
         self.last_accumulated_power = accumulated_power;
+        self.last_instantaneous_power = Some(instantaneous_power);
+        self.seen_trainer_data = true;
 
         Some(FecDataPage::SpecificTrainer {
             event_count: update_event_count,
@@ -169,12 +531,13 @@ impl FecParser {
             average_power: avg_power,
             trainer_status: TrainerStatus::from_bits(trainer_status_bits),
             target_power_limits,
+            fe_state,
         })
     }
 
     /// Calculate average power from accumulated values
     fn calculate_average_power(&self, event_count: u8, accumulated_power: u16) -> Option<u16> {
-        if self.last_event_count == 0 && self.last_accumulated_power == 0 {
+        if !self.seen_trainer_data {
             return None;
         }
 
@@ -191,17 +554,87 @@ impl FecParser {
     }
 
     /// Page 26 (0x1A): Trainer Torque Data
-    fn parse_trainer_torque(data: &[u8]) -> Option<FecDataPage> {
+    ///
+    /// Contains accumulated values for the events since the previous page:
+    /// - Wheel ticks (revolutions)
+    /// - Wheel period (1/2048s)
+    /// - Torque (1/32 Nm)
+    fn parse_trainer_torque_data(&mut self, data: &[u8]) -> Option<FecDataPage> {
         let update_event_count = data[1];
         let wheel_ticks = data[2];
         let wheel_period = u16::from_le_bytes([data[3], data[4]]);
         let accumulated_torque = u16::from_le_bytes([data[5], data[6]]);
+        let fe_state = (data[7] >> 4) & 0x07;
+
+        let measurement = self.calculate_torque(
+            update_event_count,
+            wheel_ticks,
+            wheel_period,
+            accumulated_torque,
+        );
+
+        self.last_torque_event_count = update_event_count;
+        self.last_wheel_ticks = wheel_ticks;
+        self.last_wheel_period = wheel_period;
+        self.last_accumulated_torque = accumulated_torque;
+        self.seen_torque_data = true;
 
         Some(FecDataPage::TrainerTorque {
             event_count: update_event_count,
             wheel_ticks,
             wheel_period,
             accumulated_torque,
+            fe_state,
+            measurement,
+        })
+    }
+
+    /// Wheel speed, torque and power over the events since the last Page 26,
+    /// allowing for the rollover of every accumulated field
+    fn calculate_torque(
+        &self,
+        event_count: u8,
+        wheel_ticks: u8,
+        wheel_period: u16,
+        accumulated_torque: u16,
+    ) -> Option<TorqueMeasurement> {
+        if !self.seen_torque_data {
+            return None;
+        }
+
+        let event_diff = event_count.wrapping_sub(self.last_torque_event_count);
+        if event_diff == 0 {
+            return None;
+        }
+
+        let ticks_diff = wheel_ticks.wrapping_sub(self.last_wheel_ticks);
+        let period_diff = wheel_period.wrapping_sub(self.last_wheel_period);
+        let torque_diff = accumulated_torque.wrapping_sub(self.last_accumulated_torque);
+
+        let average_torque = torque_diff as f32 / TORQUE_UNITS_PER_NM / event_diff as f32;
+
+        // A stopped wheel keeps the period where it was
+        let (wheel_speed, power) = if period_diff == 0 {
+            (0.0, 0)
+        } else {
+            let seconds = period_diff as f32 / WHEEL_PERIOD_UNITS_PER_SECOND;
+            let revolutions_per_second = ticks_diff as f32 / seconds;
+            let angular_velocity = 2.0 * std::f32::consts::PI * revolutions_per_second;
+            (
+                self.wheel_circumference * revolutions_per_second * 3.6,
+                (average_torque * angular_velocity).round() as u16,
+            )
+        };
+
+        let power_mismatch = self
+            .last_instantaneous_power
+            .is_some_and(|reported| powers_disagree(power, reported));
+
+        Some(TorqueMeasurement {
+            wheel_speed,
+            average_torque,
+            power,
+            power_mismatch,
         })
     }
 
@@ -247,6 +680,48 @@ impl FecParser {
         })
     }
 
+    /// Page 1 (0x01): Calibration Response
+    ///
+    /// Sent by the trainer when a requested calibration has finished. The
+    /// success bits use the same layout as the request mode byte.
+    fn parse_calibration_response(data: &[u8]) -> Option<FecDataPage> {
+        let results = data[1];
+        let temperature = data[3]; // 0.5°C, offset by -25°C, 0xFF = invalid
+        let zero_offset = u16::from_le_bytes([data[4], data[5]]); // 0xFFFF = invalid
+        let spin_down_time = u16::from_le_bytes([data[6], data[7]]); // ms, 0xFFFF = invalid
+
+        Some(FecDataPage::CalibrationResponse {
+            zero_offset_success: (results & CALIBRATION_ZERO_OFFSET) != 0,
+            spin_down_success: (results & CALIBRATION_SPIN_DOWN) != 0,
+            temperature: (temperature != 0xFF).then_some(temperature),
+            zero_offset: (zero_offset != 0xFFFF).then_some(zero_offset),
+            spin_down_time_ms: (spin_down_time != 0xFFFF).then_some(spin_down_time),
+        })
+    }
+
+    /// Page 2 (0x02): Calibration in Progress
+    ///
+    /// Repeated while a calibration runs, telling the rider which speed and
+    /// temperature the trainer is waiting for
+    fn parse_calibration_progress(data: &[u8]) -> Option<FecDataPage> {
+        let pending = data[1];
+        let conditions = data[2];
+        let temperature = data[3]; // 0.5°C, offset by -25°C, 0xFF = invalid
+        let target_speed = u16::from_le_bytes([data[4], data[5]]); // 0.001 m/s, 0xFFFF = invalid
+        let target_spin_down_time = u16::from_le_bytes([data[6], data[7]]); // ms, 0xFFFF = invalid
+
+        Some(FecDataPage::CalibrationProgress {
+            zero_offset_pending: (pending & CALIBRATION_ZERO_OFFSET) != 0,
+            spin_down_pending: (pending & CALIBRATION_SPIN_DOWN) != 0,
+            temperature_condition: (conditions >> 4) & 0x03,
+            speed_condition: (conditions >> 6) & 0x03,
+            temperature: (temperature != 0xFF).then_some(temperature),
+            target_speed_mms: (target_speed != 0xFFFF).then_some(target_speed),
+            target_spin_down_time_ms: (target_spin_down_time != 0xFFFF)
+                .then_some(target_spin_down_time),
+        })
+    }
+
     /// Page 71 (0x47): Command Status
     fn parse_command_status(data: &[u8]) -> Option<FecDataPage> {
         let last_command = data[1];
@@ -280,9 +755,18 @@ impl FecParser {
         let page_number = data[0];
 
         match page_number {
+            PAGE_CALIBRATION_REQUEST => Self::parse_calibration_response(data),
+            PAGE_CALIBRATION_PROGRESS => Self::parse_calibration_progress(data),
             PAGE_GENERAL_FE_DATA => Self::parse_general_fe_data(data),
+            PAGE_GENERAL_SETTINGS => Self::parse_general_settings(data),
+            PAGE_GENERAL_FE_METABOLIC => Self::parse_general_metabolic(data),
+            PAGE_TREADMILL_DATA
+            | PAGE_ELLIPTICAL_DATA
+            | PAGE_ROWER_DATA
+            | PAGE_CLIMBER_DATA
+            | PAGE_NORDIC_SKIER_DATA => Self::parse_equipment_data(data),
             PAGE_SPECIFIC_TRAINER_DATA => Self::parse_specific_trainer_static(data),
-            PAGE_TRAINER_TORQUE => Self::parse_trainer_torque(data),
+            PAGE_TRAINER_TORQUE => Self::parse_trainer_torque_static(data),
             PAGE_MANUFACTURER_ID => Self::parse_manufacturer_id(data),
             PAGE_PRODUCT_INFO => Self::parse_product_info(data),
             PAGE_FE_CAPABILITIES => Self::parse_fe_capabilities(data),
@@ -310,7 +794,8 @@ impl FecParser {
 
         let trainer_status_bits = (power_msb_and_status >> 4) & 0x0F;
         let flags = data[7];
-        let target_power_limits = flags & 0x07;
+        let target_power_limits = flags & 0x03;
+        let fe_state = (flags >> 4) & 0x07;
 
         Some(FecDataPage::SpecificTrainer {
             event_count: update_event_count,
@@ -320,6 +805,19 @@ impl FecParser {
             average_power: None, // Not calculated in static version
             trainer_status: TrainerStatus::from_bits(trainer_status_bits),
             target_power_limits,
+            fe_state,
+        })
+    }
+
+    /// Static version of parse_trainer_torque_data (without the measurement)
+    fn parse_trainer_torque_static(data: &[u8]) -> Option<FecDataPage> {
+        Some(FecDataPage::TrainerTorque {
+            event_count: data[1],
+            wheel_ticks: data[2],
+            wheel_period: u16::from_le_bytes([data[3], data[4]]),
+            accumulated_torque: u16::from_le_bytes([data[5], data[6]]),
+            fe_state: (data[7] >> 4) & 0x07,
+            measurement: None, // Not calculated in static version
         })
     }
 
@@ -329,6 +827,65 @@ impl FecParser {
     }
 }
 
+/// Whether Page 26 power disagrees sharply with Page 25 power
+fn powers_disagree(torque_power: u16, reported_power: u16) -> bool {
+    let difference = torque_power.abs_diff(reported_power);
+    let larger = torque_power.max(reported_power) as f32;
+    difference > POWER_MISMATCH_MIN_WATTS && difference as f32 > larger * POWER_MISMATCH_RATIO
+}
+
+/// Wheel speed, torque and power computed from successive Page 26 events
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TorqueMeasurement {
+    /// Wheel speed in km/h, from the configured wheel circumference
+    pub wheel_speed: f32,
+    /// Average torque per event, in Nm
+    pub average_torque: f32,
+    /// Power from torque and wheel speed, in watts
+    pub power: u16,
+    /// Power disagrees sharply with the last Page 25 instantaneous power
+    pub power_mismatch: bool,
+}
+
+/// Kind of fitness equipment, from the equipment type in Page 16
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Equipment {
+    General,
+    Treadmill,
+    Elliptical,
+    StationaryBike,
+    Rower,
+    Climber,
+    NordicSkier,
+    Trainer,
+}
+
+impl Equipment {
+    /// `None` for the reserved values
+    pub fn from_type(equipment_type: u8) -> Option<Self> {
+        match equipment_type {
+            EQUIPMENT_TYPE_GENERAL => Some(Equipment::General),
+            EQUIPMENT_TYPE_TREADMILL => Some(Equipment::Treadmill),
+            EQUIPMENT_TYPE_ELLIPTICAL => Some(Equipment::Elliptical),
+            EQUIPMENT_TYPE_STATIONARY_BIKE => Some(Equipment::StationaryBike),
+            EQUIPMENT_TYPE_ROWER => Some(Equipment::Rower),
+            EQUIPMENT_TYPE_CLIMBER => Some(Equipment::Climber),
+            EQUIPMENT_TYPE_NORDIC_SKIER => Some(Equipment::NordicSkier),
+            EQUIPMENT_TYPE_TRAINER => Some(Equipment::Trainer),
+            _ => None,
+        }
+    }
+
+    /// Whether the rider pedals a bike on it, so speed reads better than pace
+    pub fn is_bike(self) -> bool {
+        matches!(
+            self,
+            Equipment::General | Equipment::StationaryBike | Equipment::Trainer
+        )
+    }
+}
+
 /// Trainer status decoded from Page 25
 #[derive(Debug, Clone, Default)]
 pub struct TrainerStatus {
@@ -347,9 +904,90 @@ impl TrainerStatus {
     }
 }
 
+/// FE state from byte 7 of Pages 16-18 and 25
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FeState {
+    Asleep,
+    Ready,
+    InUse,
+    Finished,
+}
+
+impl FeState {
+    /// `None` for the reserved values
+    pub fn from_bits(state: u8) -> Option<Self> {
+        match state {
+            FE_STATE_ASLEEP => Some(FeState::Asleep),
+            FE_STATE_READY => Some(FeState::Ready),
+            FE_STATE_IN_USE => Some(FeState::InUse),
+            FE_STATE_FINISHED => Some(FeState::Finished),
+            _ => None,
+        }
+    }
+}
+
+/// Trainer condition the rider should be told about
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TrainerAlert {
+    /// ERG target out of reach: pedal faster or shift to a harder gear
+    SpeedTooLowForTarget,
+    /// ERG target out of reach: pedal slower or shift to an easier gear
+    SpeedTooHighForTarget,
+    /// ERG target out of reach for a reason the trainer doesn't report
+    TargetPowerLimitReached,
+    BicyclePowerCalibrationRequired,
+    ResistanceCalibrationRequired,
+    UserConfigRequired,
+}
+
+impl TrainerAlert {
+    /// Alerts raised by a Page 25 trainer status and target power limits
+    pub fn from_trainer_status(status: &TrainerStatus, target_power_limits: u8) -> Vec<Self> {
+        let mut alerts = Vec::new();
+        match target_power_limits {
+            TARGET_POWER_SPEED_TOO_LOW => alerts.push(TrainerAlert::SpeedTooLowForTarget),
+            TARGET_POWER_SPEED_TOO_HIGH => alerts.push(TrainerAlert::SpeedTooHighForTarget),
+            TARGET_POWER_LIMIT_REACHED => alerts.push(TrainerAlert::TargetPowerLimitReached),
+            _ => {}
+        }
+        if status.bicycle_power_calibration_required {
+            alerts.push(TrainerAlert::BicyclePowerCalibrationRequired);
+        }
+        if status.resistance_calibration_required {
+            alerts.push(TrainerAlert::ResistanceCalibrationRequired);
+        }
+        if status.user_config_required {
+            alerts.push(TrainerAlert::UserConfigRequired);
+        }
+        alerts
+    }
+}
+
 /// Parsed FE-C data page
 #[derive(Debug, Clone)]
 pub enum FecDataPage {
+    /// Page 1: Calibration Response - results of a finished calibration
+    CalibrationResponse {
+        zero_offset_success: bool,
+        spin_down_success: bool,
+        temperature: Option<u8>, // 0.5°C, offset by -25°C
+        zero_offset: Option<u16>,
+        spin_down_time_ms: Option<u16>,
+    },
+
+    /// Page 2: Calibration in Progress - conditions the trainer waits for
+    CalibrationProgress {
+        zero_offset_pending: bool,
+        spin_down_pending: bool,
+        temperature_condition: u8,     // CALIBRATION_CONDITION_*
+        speed_condition: u8,           // CALIBRATION_CONDITION_* (no "too high")
+        temperature: Option<u8>,       // 0.5°C, offset by -25°C
+        target_speed_mms: Option<u16>, // 0.001 m/s
+        target_spin_down_time_ms: Option<u16>,
+    },
+
     /// Page 16: General FE Data - basic equipment information
     GeneralFE {
         equipment_type: u8,
@@ -362,6 +1000,63 @@ pub enum FecDataPage {
         distance_enabled: bool,
     },
 
+    /// Page 17: General Settings - equipment setup
+    GeneralSettings {
+        cycle_length_cm: Option<u8>,               // Wheel circumference on bikes
+        incline_hundredths: Option<i16>,           // 0.01%
+        resistance_level_half_percent: Option<u8>, // 0.5% of maximum
+        fe_state: u8,
+    },
+
+    /// Page 18: General Metabolic Data - trainer-computed energy
+    GeneralMetabolic {
+        mets_hundredths: Option<u16>,
+        caloric_burn_rate_tenths: Option<u16>, // 0.1 kcal/h
+        accumulated_calories: Option<u8>,      // kcal, rolls over at 256
+        fe_state: u8,
+    },
+
+    /// Page 19: Treadmill Data
+    Treadmill {
+        cadence: Option<u8>,                    // strides/min
+        negative_vertical_distance: Option<u8>, // 0.1m, rolls over at 25.6m
+        positive_vertical_distance: Option<u8>, // 0.1m, rolls over at 25.6m
+        fe_state: u8,
+    },
+
+    /// Page 20: Elliptical Data
+    Elliptical {
+        stride_count: Option<u8>,               // rolls over at 256
+        positive_vertical_distance: Option<u8>, // 0.1m, rolls over at 25.6m
+        cadence: Option<u8>,                    // strides/min
+        instantaneous_power: Option<u16>,
+        fe_state: u8,
+    },
+
+    /// Page 22: Rower Data
+    Rower {
+        stroke_count: Option<u8>, // rolls over at 256
+        cadence: Option<u8>,      // strokes/min
+        instantaneous_power: Option<u16>,
+        fe_state: u8,
+    },
+
+    /// Page 23: Climber Data
+    Climber {
+        stride_cycles: Option<u8>, // rolls over at 256
+        cadence: Option<u8>,       // stride cycles/min
+        instantaneous_power: Option<u16>,
+        fe_state: u8,
+    },
+
+    /// Page 24: Nordic Skier Data
+    NordicSkier {
+        stride_count: Option<u8>, // rolls over at 256
+        cadence: Option<u8>,      // strides/min
+        instantaneous_power: Option<u16>,
+        fe_state: u8,
+    },
+
     /// Page 25: Specific Trainer Data - power and cadence
     SpecificTrainer {
         event_count: u8,
@@ -370,15 +1065,18 @@ pub enum FecDataPage {
         instantaneous_power: u16,
         average_power: Option<u16>,
         trainer_status: TrainerStatus,
-        target_power_limits: u8,
+        target_power_limits: u8, // TARGET_POWER_*
+        fe_state: u8,
     },
 
     /// Page 26: Trainer Torque Data
     TrainerTorque {
         event_count: u8,
         wheel_ticks: u8,
-        wheel_period: u16,
-        accumulated_torque: u16,
+        wheel_period: u16,       // 1/2048s
+        accumulated_torque: u16, // 1/32 Nm
+        fe_state: u8,
+        measurement: Option<TorqueMeasurement>, // None for the first page
     },
 
     /// Page 54: FE Capabilities
@@ -421,8 +1119,13 @@ pub enum FecDataPage {
 impl FecDataPage {
     /// Update TrainerData from parsed page
     pub fn update_trainer_data(&self, data: &mut TrainerData) {
+        if let Some(fe_state) = self.fe_state() {
+            data.fe_state = FeState::from_bits(fe_state);
+        }
+
         match self {
             FecDataPage::GeneralFE {
+                equipment_type,
                 speed_mms,
                 heart_rate,
                 ..
@@ -434,21 +1137,119 @@ impl FecDataPage {
                 if let Some(hr) = heart_rate {
                     data.heart_rate = *hr;
                 }
+
+                // Seconds per km: 1000m / (speed_mms / 1000 m/s)
+                data.equipment = Equipment::from_type(*equipment_type);
+                data.pace = match data.equipment {
+                    Some(equipment) if !equipment.is_bike() && *speed_mms > 0 => {
+                        Some(1_000_000.0 / *speed_mms as f32)
+                    }
+                    _ => None,
+                };
+            }
+            FecDataPage::Treadmill {
+                cadence: Some(cad), ..
+            } => {
+                data.cadence = *cad;
+            }
+            FecDataPage::Rower {
+                cadence,
+                instantaneous_power,
+                ..
+            } => {
+                data.stroke_rate = *cadence;
+                if let Some(cad) = cadence {
+                    data.cadence = *cad;
+                }
+                if let Some(power) = instantaneous_power {
+                    data.power = *power;
+                }
+            }
+            FecDataPage::Elliptical {
+                cadence,
+                instantaneous_power,
+                ..
+            }
+            | FecDataPage::Climber {
+                cadence,
+                instantaneous_power,
+                ..
+            }
+            | FecDataPage::NordicSkier {
+                cadence,
+                instantaneous_power,
+                ..
+            } => {
+                if let Some(cad) = cadence {
+                    data.cadence = *cad;
+                }
+                if let Some(power) = instantaneous_power {
+                    data.power = *power;
+                }
             }
             FecDataPage::SpecificTrainer {
                 cadence,
                 instantaneous_power,
+                average_power,
+                trainer_status,
+                target_power_limits,
                 ..
             } => {
                 if let Some(cad) = cadence {
                     data.cadence = *cad;
                 }
                 data.power = *instantaneous_power;
+                if let Some(avg) = average_power {
+                    data.average_power = *avg;
+                }
+                data.alerts =
+                    TrainerAlert::from_trainer_status(trainer_status, *target_power_limits);
+            }
+            FecDataPage::GeneralSettings {
+                cycle_length_cm,
+                incline_hundredths,
+                resistance_level_half_percent,
+                ..
+            } => {
+                data.cycle_length = cycle_length_cm.map(|cm| cm as f32 / 100.0);
+                data.incline = incline_hundredths.map(|i| i as f32 / 100.0);
+                data.resistance_level = resistance_level_half_percent.map(|r| r as f32 / 2.0);
+            }
+            FecDataPage::GeneralMetabolic {
+                mets_hundredths, ..
+            } => {
+                data.mets = mets_hundredths.map(|m| m as f32 / 100.0);
+            }
+            FecDataPage::TrainerTorque {
+                measurement: Some(measurement),
+                ..
+            } => {
+                data.wheel_speed = Some(measurement.wheel_speed);
+                data.torque = Some(measurement.average_torque);
+                data.torque_power = Some(measurement.power);
+                data.power_mismatch = measurement.power_mismatch;
             }
             _ => {}
         }
     }
 
+    /// FE state carried in byte 7 of the general and trainer pages
+    pub fn fe_state(&self) -> Option<u8> {
+        match self {
+            FecDataPage::GeneralFE { fe_state, .. }
+            | FecDataPage::GeneralSettings { fe_state, .. }
+            | FecDataPage::GeneralMetabolic { fe_state, .. }
+            | FecDataPage::Treadmill { fe_state, .. }
+            | FecDataPage::Elliptical { fe_state, .. }
+            | FecDataPage::Rower { fe_state, .. }
+            | FecDataPage::Climber { fe_state, .. }
+            | FecDataPage::NordicSkier { fe_state, .. }
+            | FecDataPage::SpecificTrainer { fe_state, .. }
+            | FecDataPage::TrainerTorque { fe_state, .. } => Some(*fe_state),
+            _ => None,
+        }
+    }
+
     /// Get human-readable equipment type name
     pub fn equipment_type_name(equipment_type: u8) -> &'static str {
         match equipment_type {
@@ -476,6 +1277,340 @@ impl FecDataPage {
     }
 }
 
+/// Rider and bike parameters used by the trainer in simulation mode
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UserConfig {
+    /// Rider weight in kg
+    pub user_weight_kg: f32,
+    /// Bike weight in kg
+    pub bike_weight_kg: f32,
+    /// Wheel diameter in meters (0.7m for a 700c road wheel)
+    pub wheel_diameter_m: f32,
+    /// Front/rear gear ratio, if known
+    pub gear_ratio: Option<f32>,
+}
+
+impl Default for UserConfig {
+    fn default() -> Self {
+        Self {
+            user_weight_kg: 75.0,
+            bike_weight_kg: 10.0,
+            wheel_diameter_m: 0.7,
+            gear_ratio: None,
+        }
+    }
+}
+
+/// Control page sent from the display to the trainer
+///
+/// Control pages are transmitted as ANT acknowledged messages on the FE-C
+/// channel. The trainer reports the outcome in Page 71 (Command Status).
+#[derive(Debug, Clone, PartialEq)]
+pub enum FecCommand {
+    /// Page 1: Calibration Request
+    CalibrationRequest { mode: u8 }, // CALIBRATION_ZERO_OFFSET | CALIBRATION_SPIN_DOWN
+
+    /// Page 48: Basic Resistance (percentage of maximum resistance)
+    BasicResistance { total_resistance: u8 }, // 0.5%
+
+    /// Page 49: Target Power (ERG mode)
+    TargetPower { target_quarter_watts: u16 },
+
+    /// Page 50: Wind Resistance (simulation mode)
+    WindResistance {
+        wind_coefficient: u8, // 0.01 kg/m
+        wind_speed: u8,       // km/h, offset by -127
+        drafting_factor: u8,  // 0.01
+    },
+
+    /// Page 51: Track Resistance (simulation mode)
+    TrackResistance {
+        grade: u16, // 0.01%, offset by -200%
+        crr: u8,    // 5x10^-5
+    },
+
+    /// Page 55: User Configuration
+    UserConfiguration {
+        user_weight: u16,   // 0.01 kg
+        bike_weight: u16,   // 0.05 kg, 12 bits
+        wheel_diameter: u8, // 0.01 m
+        gear_ratio: u8,     // 0.03
+    },
+
+    /// Page 70: Request Data Page - asks the trainer to transmit a page
+    RequestDataPage { page_number: u8, transmissions: u8 },
+}
+
+impl FecCommand {
+    /// Page 48 resistance as a percentage of the trainer's maximum
+    pub fn basic_resistance(percent: f32) -> Self {
+        FecCommand::BasicResistance {
+            total_resistance: (percent.clamp(0.0, 100.0) * 2.0).round() as u8,
+        }
+    }
+
+    /// Page 49 target power, clamped to the 0-4000W range of the profile
+    pub fn target_power(watts: f32) -> Self {
+        let watts = watts.clamp(0.0, TARGET_POWER_MAX_WATTS);
+        FecCommand::TargetPower {
+            target_quarter_watts: (watts * 4.0).round() as u16,
+        }
+    }
+
+    /// Page 51 track resistance for a road grade in percent
+    ///
+    /// `crr` is the rolling resistance coefficient; `None` leaves it at the
+    /// trainer's default (0.004).
+    pub fn track_resistance(grade_percent: f32, crr: Option<f32>) -> Self {
+        let grade_percent = grade_percent.clamp(GRADE_MIN_PERCENT, GRADE_MAX_PERCENT);
+        FecCommand::TrackResistance {
+            grade: ((grade_percent - GRADE_MIN_PERCENT) * 100.0).round() as u16,
+            crr: crr.map_or(FIELD_INVALID, |crr| {
+                (crr / CRR_UNIT).round().clamp(0.0, 254.0) as u8
+            }),
+        }
+    }
+
+    /// Page 50 wind resistance
+    ///
+    /// `cda` is the wind resistance coefficient in kg/m; any `None` leaves
+    /// the trainer's default (0.51 kg/m, no wind, no drafting).
+    pub fn wind_resistance(
+        cda: Option<f32>,
+        wind_speed_kmh: Option<f32>,
+        drafting_factor: Option<f32>,
+    ) -> Self {
+        FecCommand::WindResistance {
+            wind_coefficient: cda.map_or(FIELD_INVALID, |cda| {
+                (cda * 100.0).round().clamp(0.0, 254.0) as u8
+            }),
+            wind_speed: wind_speed_kmh.map_or(FIELD_INVALID, |speed| {
+                (speed + WIND_SPEED_OFFSET_KMH).round().clamp(0.0, 254.0) as u8
+            }),
+            drafting_factor: drafting_factor.map_or(FIELD_INVALID, |factor| {
+                (factor * 100.0).round().clamp(0.0, 100.0) as u8
+            }),
+        }
+    }
+
+    /// Page 55 user configuration
+    pub fn user_configuration(config: &UserConfig) -> Self {
+        let user_weight = config.user_weight_kg.clamp(0.0, USER_WEIGHT_MAX_KG);
+        let bike_weight = config.bike_weight_kg.clamp(0.0, BIKE_WEIGHT_MAX_KG);
+        let wheel_diameter = config.wheel_diameter_m.clamp(0.0, WHEEL_DIAMETER_MAX_M);
+
+        FecCommand::UserConfiguration {
+            user_weight: (user_weight * 100.0).round() as u16,
+            bike_weight: (bike_weight * 20.0).round() as u16,
+            wheel_diameter: (wheel_diameter * 100.0).round() as u8,
+            gear_ratio: config.gear_ratio.map_or(0, |ratio| {
+                (ratio / GEAR_RATIO_UNIT).round().clamp(1.0, 255.0) as u8
+            }),
+        }
+    }
+
+    /// Page 1 calibration request for a spin-down, a zero offset or both
+    pub fn calibration_request(spin_down: bool, zero_offset: bool) -> Self {
+        let mut mode = 0;
+        if spin_down {
+            mode |= CALIBRATION_SPIN_DOWN;
+        }
+        if zero_offset {
+            mode |= CALIBRATION_ZERO_OFFSET;
+        }
+        FecCommand::CalibrationRequest { mode }
+    }
+
+    /// Page 70 request for a single data page
+    pub fn request_page(page_number: u8) -> Self {
+        FecCommand::RequestDataPage {
+            page_number,
+            transmissions: 1,
+        }
+    }
+
+    /// Data page number of this command
+    pub fn page_number(&self) -> u8 {
+        match self {
+            FecCommand::CalibrationRequest { .. } => PAGE_CALIBRATION_REQUEST,
+            FecCommand::BasicResistance { .. } => PAGE_BASIC_RESISTANCE,
+            FecCommand::TargetPower { .. } => PAGE_TARGET_POWER,
+            FecCommand::WindResistance { .. } => PAGE_WIND_RESISTANCE,
+            FecCommand::TrackResistance { .. } => PAGE_TRACK_RESISTANCE,
+            FecCommand::UserConfiguration { .. } => PAGE_USER_CONFIG,
+            FecCommand::RequestDataPage { .. } => PAGE_REQUEST_DATA,
+        }
+    }
+
+    /// Whether the trainer reports this command in Page 71
+    ///
+    /// Only the resistance and power control pages are echoed in Command
+    /// Status; configuration pages and requests are fire-and-forget, and a
+    /// calibration reports back through Pages 1 and 2.
+    pub fn expects_status(&self) -> bool {
+        !matches!(
+            self,
+            FecCommand::CalibrationRequest { .. }
+                | FecCommand::UserConfiguration { .. }
+                | FecCommand::RequestDataPage { .. }
+        )
+    }
+
+    /// Encode the command into an 8-byte ANT+ payload
+    pub fn encode(&self) -> [u8; 8] {
+        match self {
+            FecCommand::CalibrationRequest { mode } => [
+                PAGE_CALIBRATION_REQUEST,
+                *mode,
+                0x00, // Reserved
+                0xFF,
+                0xFF,
+                0xFF,
+                0xFF,
+                0xFF,
+            ],
+            FecCommand::BasicResistance { total_resistance } => [
+                PAGE_BASIC_RESISTANCE,
+                0xFF,
+                0xFF,
+                0xFF,
+                0xFF,
+                0xFF,
+                0xFF,
+                *total_resistance,
+            ],
+            FecCommand::TargetPower {
+                target_quarter_watts,
+            } => {
+                let power = target_quarter_watts.to_le_bytes();
+                [
+                    PAGE_TARGET_POWER,
+                    0xFF,
+                    0xFF,
+                    0xFF,
+                    0xFF,
+                    0xFF,
+                    power[0],
+                    power[1],
+                ]
+            }
+            FecCommand::WindResistance {
+                wind_coefficient,
+                wind_speed,
+                drafting_factor,
+            } => [
+                PAGE_WIND_RESISTANCE,
+                0xFF,
+                0xFF,
+                0xFF,
+                0xFF,
+                *wind_coefficient,
+                *wind_speed,
+                *drafting_factor,
+            ],
+            FecCommand::TrackResistance { grade, crr } => {
+                let grade = grade.to_le_bytes();
+                [
+                    PAGE_TRACK_RESISTANCE,
+                    0xFF,
+                    0xFF,
+                    0xFF,
+                    0xFF,
+                    grade[0],
+                    grade[1],
+                    *crr,
+                ]
+            }
+            FecCommand::UserConfiguration {
+                user_weight,
+                bike_weight,
+                wheel_diameter,
+                gear_ratio,
+            } => {
+                let user_weight = user_weight.to_le_bytes();
+                [
+                    PAGE_USER_CONFIG,
+                    user_weight[0],
+                    user_weight[1],
+                    0xFF,
+                    // Wheel diameter offset (not used) + bike weight low nibble
+                    0x0F | (((bike_weight & 0x0F) as u8) << 4),
+                    (bike_weight >> 4) as u8,
+                    *wheel_diameter,
+                    *gear_ratio,
+                ]
+            }
+            FecCommand::RequestDataPage {
+                page_number,
+                transmissions,
+            } => [
+                PAGE_REQUEST_DATA,
+                0xFF,
+                0xFF, // Slave serial number (none)
+                0xFF,
+                0xFF,                 // Descriptor bytes (none)
+                transmissions & 0x7F, // Transmit as broadcast, N times
+                *page_number,
+                REQUEST_COMMAND_TYPE_DATA_PAGE,
+            ],
+        }
+    }
+
+    /// Check a Page 71 Command Status reply against this command
+    ///
+    /// Returns `None` when the reply does not confirm this command yet
+    /// (different command, pending, or a stale setpoint), `Some(Ok)` when the
+    /// trainer applied it, and `Some(Err)` when it will never be applied.
+    pub fn check_status(&self, page: &FecDataPage) -> Option<Result<(), String>> {
+        let FecDataPage::CommandStatus {
+            last_command,
+            status,
+            data,
+            ..
+        } = page
+        else {
+            return None;
+        };
+
+        if *last_command != self.page_number() {
+            return None;
+        }
+
+        match *status {
+            COMMAND_STATUS_PASS if self.echo_matches(data) => Some(Ok(())),
+            COMMAND_STATUS_NOT_SUPPORTED => Some(Err(format!(
+                "Trainer does not support page {}",
+                self.page_number()
+            ))),
+            COMMAND_STATUS_REJECTED => {
+                Some(Err(format!("Trainer rejected page {}", self.page_number())))
+            }
+            _ => None,
+        }
+    }
+
+    /// Compare the setpoint echoed in Page 71 bytes 4-7 with this command
+    fn echo_matches(&self, data: &[u8; 4]) -> bool {
+        match self {
+            FecCommand::BasicResistance { total_resistance } => data[3] == *total_resistance,
+            FecCommand::TargetPower {
+                target_quarter_watts,
+            } => u16::from_le_bytes([data[2], data[3]]) == *target_quarter_watts,
+            FecCommand::WindResistance {
+                wind_coefficient,
+                wind_speed,
+                drafting_factor,
+            } => data[1..4] == [*wind_coefficient, *wind_speed, *drafting_factor],
+            FecCommand::TrackResistance { grade, crr } => {
+                u16::from_le_bytes([data[1], data[2]]) == *grade && data[3] == *crr
+            }
+            FecCommand::CalibrationRequest { .. }
+            | FecCommand::UserConfiguration { .. }
+            | FecCommand::RequestDataPage { .. } => true,
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -581,6 +1716,394 @@ mod tests {
         }
     }
 
+    fn page_16(elapsed_quarter_sec: u8, distance: u8) -> [u8; 8] {
+        let distance_enabled = 0x04;
+        [
+            PAGE_GENERAL_FE_DATA,
+            EQUIPMENT_TYPE_TRAINER,
+            elapsed_quarter_sec,
+            distance,
+            0xE8,
+            0x03,
+            0xFF,
+            0x30 | distance_enabled,
+        ]
+    }
+
+    fn page_25(event_count: u8, accumulated_power: u16, power: u16) -> [u8; 8] {
+        let accumulated = accumulated_power.to_le_bytes();
+        let power = power.to_le_bytes();
+        [
+            PAGE_SPECIFIC_TRAINER_DATA,
+            event_count,
+            90,
+            accumulated[0],
+            accumulated[1],
+            power[0],
+            power[1],
+            0x00,
+        ]
+    }
+
+    #[test]
+    fn test_distance_and_elapsed_time_survive_rollover() {
+        let mut parser = FecParser::new();
+        let mut trainer_data = TrainerData::default();
+
+        // 60s and 250m in, then past the 64s / 256m rollover
+        for page in [page_16(240, 250), page_16(8, 10), page_16(20, 30)] {
+            let page = parser.parse_broadcast(&page).unwrap();
+            parser.apply(&mut trainer_data, &page);
+        }
+
+        assert_eq!(parser.total_distance(), 36);
+        assert_eq!(trainer_data.trainer_distance, 36);
+        assert_eq!(trainer_data.trainer_elapsed_time, 9.0);
+    }
+
+    #[test]
+    fn test_average_power_from_accumulated_power() {
+        let mut parser = FecParser::new();
+        let mut trainer_data = TrainerData::default();
+
+        // The first page has nothing to average against
+        let first = parser.parse_broadcast(&page_25(0, 0, 200)).unwrap();
+        parser.apply(&mut trainer_data, &first);
+        assert_eq!(trainer_data.average_power, 0);
+
+        // Two events at 200W and 220W: 420W accumulated
+        let second = parser.parse_broadcast(&page_25(2, 420, 220)).unwrap();
+        parser.apply(&mut trainer_data, &second);
+        assert_eq!(trainer_data.average_power, 210);
+
+        // Accumulated power rolls over at 65536W
+        let mut parser = FecParser::new();
+        parser.parse_broadcast(&page_25(254, 65_436, 200));
+        let page = parser.parse_broadcast(&page_25(0, 64, 200)).unwrap();
+        assert!(matches!(
+            page,
+            FecDataPage::SpecificTrainer {
+                average_power: Some(82),
+                ..
+            }
+        ));
+    }
+
+    fn page_26(event_count: u8, wheel_ticks: u8, wheel_period: u16, torque: u16) -> [u8; 8] {
+        let period = wheel_period.to_le_bytes();
+        let torque = torque.to_le_bytes();
+        [
+            PAGE_TRAINER_TORQUE,
+            event_count,
+            wheel_ticks,
+            period[0],
+            period[1],
+            torque[0],
+            torque[1],
+            0x30, // In use
+        ]
+    }
+
+    #[test]
+    fn test_trainer_torque_gives_wheel_speed_torque_and_power() {
+        let mut parser = FecParser::new();
+        let mut trainer_data = TrainerData::default();
+
+        let first = parser.parse_broadcast(&page_26(1, 10, 0, 0)).unwrap();
+        parser.apply(&mut trainer_data, &first);
+        assert_eq!(trainer_data.torque_power, None);
+        assert_eq!(trainer_data.fe_state, Some(FeState::InUse));
+
+        // One wheel revolution in 0.5s at 10 Nm: 2 rev/s, 10 * 4π W
+        let second = parser.parse_broadcast(&page_26(2, 11, 1024, 320)).unwrap();
+        parser.apply(&mut trainer_data, &second);
+        assert!((trainer_data.wheel_speed.unwrap() - 15.09).abs() < 0.01);
+        assert_eq!(trainer_data.torque, Some(10.0));
+        assert_eq!(trainer_data.torque_power, Some(126));
+        assert!(!trainer_data.power_mismatch);
+
+        // Same event again: nothing to measure
+        let repeat = parser.parse_broadcast(&page_26(2, 11, 1024, 320)).unwrap();
+        assert!(matches!(
+            repeat,
+            FecDataPage::TrainerTorque {
+                measurement: None,
+                ..
+            }
+        ));
+
+        // Two events and revolutions in 0.5s on a configured 2.2m wheel
+        parser.set_wheel_circumference(2.2);
+        let page = parser.parse_broadcast(&page_26(4, 13, 2048, 960)).unwrap();
+        let FecDataPage::TrainerTorque {
+            measurement: Some(measurement),
+            ..
+        } = page
+        else {
+            panic!("expected a torque measurement");
+        };
+        assert!((measurement.wheel_speed - 31.68).abs() < 0.01);
+        assert_eq!(measurement.average_torque, 10.0);
+
+        // A stopped wheel
+        let page = parser.parse_broadcast(&page_26(5, 13, 2048, 960)).unwrap();
+        assert!(matches!(
+            page,
+            FecDataPage::TrainerTorque {
+                measurement: Some(TorqueMeasurement { power: 0, .. }),
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn test_trainer_torque_survives_rollover() {
+        let mut parser = FecParser::new();
+        parser.parse_broadcast(&page_26(255, 255, 65_000, 65_300));
+
+        // Two revolutions in 1s at 10 Nm, with every field rolling over
+        let page = parser.parse_broadcast(&page_26(0, 1, 1_512, 84)).unwrap();
+        let FecDataPage::TrainerTorque {
+            measurement: Some(measurement),
+            ..
+        } = page
+        else {
+            panic!("expected a torque measurement");
+        };
+        assert!((measurement.wheel_speed - 15.09).abs() < 0.01);
+        assert_eq!(measurement.average_torque, 10.0);
+        assert_eq!(measurement.power, 126);
+    }
+
+    #[test]
+    fn test_torque_power_is_cross_checked_against_page_25() {
+        let mut parser = FecParser::new();
+        let mut trainer_data = TrainerData::default();
+        parser.parse_broadcast(&page_26(1, 10, 0, 0));
+
+        // The trainer reports 300W while its torque says 126W
+        parser.parse_broadcast(&page_25(1, 300, 300));
+        let page = parser.parse_broadcast(&page_26(2, 11, 1024, 320)).unwrap();
+        parser.apply(&mut trainer_data, &page);
+        assert!(trainer_data.power_mismatch);
+
+        // Within the tolerance again
+        parser.parse_broadcast(&page_25(2, 430, 130));
+        let page = parser.parse_broadcast(&page_26(3, 12, 2048, 640)).unwrap();
+        parser.apply(&mut trainer_data, &page);
+        assert!(!trainer_data.power_mismatch);
+    }
+
+    #[test]
+    fn test_rower_strokes_pace_and_power() {
+        let mut parser = FecParser::new();
+        let mut trainer_data = TrainerData::default();
+
+        // Rower at 4 m/s: 250 s/km
+        let general = [
+            PAGE_GENERAL_FE_DATA,
+            EQUIPMENT_TYPE_ROWER,
+            0,
+            0,
+            0xA0,
+            0x0F,
+            0xFF,
+            0x30,
+        ];
+        let page = parser.parse_broadcast(&general).unwrap();
+        parser.apply(&mut trainer_data, &page);
+        assert_eq!(trainer_data.equipment, Some(Equipment::Rower));
+        assert_eq!(trainer_data.pace, Some(250.0));
+
+        // Stroke count rolls over from 250 to 4
+        for strokes in [250, 4] {
+            let rower = [PAGE_ROWER_DATA, 0xFF, 0xFF, strokes, 28, 0xC8, 0x00, 0x31];
+            let page = parser.parse_broadcast(&rower).unwrap();
+            parser.apply(&mut trainer_data, &page);
+        }
+        assert_eq!(trainer_data.strides, Some(10));
+        assert_eq!(trainer_data.stroke_rate, Some(28));
+        assert_eq!(trainer_data.cadence, 28);
+        assert_eq!(trainer_data.power, 200);
+        assert_eq!(trainer_data.fe_state, Some(FeState::InUse));
+    }
+
+    #[test]
+    fn test_treadmill_vertical_distance_and_bike_speed() {
+        let mut parser = FecParser::new();
+        let mut trainer_data = TrainerData::default();
+
+        // Climbed 1.0m (250 -> 4, rolling over) and descended 0.2m
+        for (negative, positive) in [(0, 250), (2, 4)] {
+            let treadmill = [
+                PAGE_TREADMILL_DATA,
+                0xFF,
+                0xFF,
+                160,
+                negative,
+                positive,
+                0xFF,
+                0x33,
+            ];
+            let page = parser.parse_broadcast(&treadmill).unwrap();
+            parser.apply(&mut trainer_data, &page);
+        }
+        assert_eq!(trainer_data.cadence, 160);
+        assert_eq!(trainer_data.vertical_distance, Some(0.8));
+        assert_eq!(trainer_data.strides, None);
+
+        // Bikes keep speed rather than pace
+        let general = [
+            PAGE_GENERAL_FE_DATA,
+            EQUIPMENT_TYPE_TRAINER,
+            0,
+            0,
+            0xA0,
+            0x0F,
+            0xFF,
+            0x30,
+        ];
+        FecParser::parse_data_page(&general)
+            .unwrap()
+            .update_trainer_data(&mut trainer_data);
+        assert_eq!(trainer_data.equipment, Some(Equipment::Trainer));
+        assert_eq!(trainer_data.pace, None);
+    }
+
+    #[test]
+    fn test_parse_general_settings() {
+        let mut trainer_data = TrainerData::default();
+        // 2.10m wheel, -1.50% incline, 30% resistance
+        let data = [PAGE_GENERAL_SETTINGS, 0xFF, 0xFF, 210, 0x6A, 0xFF, 60, 0x30];
+
+        let page = FecParser::parse_data_page(&data).unwrap();
+        page.update_trainer_data(&mut trainer_data);
+
+        assert_eq!(trainer_data.cycle_length, Some(2.1));
+        assert_eq!(trainer_data.incline, Some(-1.5));
+        assert_eq!(trainer_data.resistance_level, Some(30.0));
+
+        // Invalid fields stay unknown
+        let data = [
+            PAGE_GENERAL_SETTINGS,
+            0xFF,
+            0xFF,
+            0xFF,
+            0xFF,
+            0x7F,
+            0xFF,
+            0x30,
+        ];
+        FecParser::parse_data_page(&data)
+            .unwrap()
+            .update_trainer_data(&mut trainer_data);
+        assert_eq!(trainer_data.incline, None);
+        assert_eq!(trainer_data.resistance_level, None);
+    }
+
+    #[test]
+    fn test_parse_calibration_pages() {
+        // Spin-down waiting for the rider: speed too low, temperature ok,
+        // 20.5°C, target 8.333 m/s and 5000ms
+        let data = [
+            PAGE_CALIBRATION_PROGRESS,
+            0x80,
+            0x60,
+            91,
+            0x8D,
+            0x20,
+            0x88,
+            0x13,
+        ];
+        match FecParser::parse_data_page(&data).unwrap() {
+            FecDataPage::CalibrationProgress {
+                spin_down_pending,
+                zero_offset_pending,
+                temperature_condition,
+                speed_condition,
+                temperature,
+                target_speed_mms,
+                target_spin_down_time_ms,
+            } => {
+                assert!(spin_down_pending);
+                assert!(!zero_offset_pending);
+                assert_eq!(temperature_condition, CALIBRATION_CONDITION_OK);
+                assert_eq!(speed_condition, CALIBRATION_CONDITION_TOO_LOW);
+                assert_eq!(temperature, Some(91));
+                assert_eq!(target_speed_mms, Some(8333));
+                assert_eq!(target_spin_down_time_ms, Some(5000));
+            }
+            other => panic!("unexpected page {:?}", other),
+        }
+
+        // Spin-down done in 4800ms, no zero offset
+        let data = [
+            PAGE_CALIBRATION_REQUEST,
+            0x80,
+            0x00,
+            91,
+            0xFF,
+            0xFF,
+            0xC0,
+            0x12,
+        ];
+        match FecParser::parse_data_page(&data).unwrap() {
+            FecDataPage::CalibrationResponse {
+                spin_down_success,
+                zero_offset_success,
+                zero_offset,
+                spin_down_time_ms,
+                ..
+            } => {
+                assert!(spin_down_success);
+                assert!(!zero_offset_success);
+                assert_eq!(zero_offset, None);
+                assert_eq!(spin_down_time_ms, Some(4800));
+            }
+            other => panic!("unexpected page {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_metabolic_calories_survive_rollover() {
+        let mut parser = FecParser::new();
+        let mut trainer_data = TrainerData::default();
+
+        // 8.25 METs, 720 kcal/h, 250 kcal then past the 256 kcal rollover
+        for calories in [250, 4] {
+            let data = [
+                PAGE_GENERAL_FE_METABOLIC,
+                0xFF,
+                0x39,
+                0x03,
+                0x20,
+                0x1C,
+                calories,
+                0x31,
+            ];
+            let page = parser.parse_broadcast(&data).unwrap();
+            parser.apply(&mut trainer_data, &page);
+        }
+
+        assert_eq!(trainer_data.mets, Some(8.25));
+        assert_eq!(trainer_data.calories, Some(10));
+
+        // Trainers that don't count calories leave them unknown
+        let mut parser = FecParser::new();
+        let data = [
+            PAGE_GENERAL_FE_METABOLIC,
+            0xFF,
+            0x39,
+            0x03,
+            0x20,
+            0x1C,
+            0,
+            0x30,
+        ];
+        parser.parse_broadcast(&data);
+        assert_eq!(parser.total_calories(), None);
+    }
+
     #[test]
     fn test_update_trainer_data() {
         let mut trainer_data = TrainerData::default();
@@ -593,12 +2116,51 @@ mod tests {
             average_power: None,
             trainer_status: TrainerStatus::default(),
             target_power_limits: 0,
+            fe_state: FE_STATE_IN_USE,
         };
 
         page.update_trainer_data(&mut trainer_data);
 
         assert_eq!(trainer_data.cadence, 90);
         assert_eq!(trainer_data.power, 250);
+        assert_eq!(trainer_data.fe_state, Some(FeState::InUse));
+        assert!(trainer_data.alerts.is_empty());
+    }
+
+    #[test]
+    fn test_trainer_status_and_power_limits_raise_alerts() {
+        let mut trainer_data = TrainerData::default();
+
+        // 150W at too low a speed, spin-down and user config required, in use
+        let data = [PAGE_SPECIFIC_TRAINER_DATA, 1, 60, 0, 0, 0x96, 0x60, 0x31];
+        FecParser::parse_data_page(&data)
+            .unwrap()
+            .update_trainer_data(&mut trainer_data);
+
+        assert_eq!(trainer_data.power, 150);
+        assert_eq!(trainer_data.fe_state, Some(FeState::InUse));
+        assert_eq!(
+            trainer_data.alerts,
+            vec![
+                TrainerAlert::SpeedTooLowForTarget,
+                TrainerAlert::ResistanceCalibrationRequired,
+                TrainerAlert::UserConfigRequired,
+            ]
+        );
+
+        // Back on target: the alerts clear with the next page
+        let data = [PAGE_SPECIFIC_TRAINER_DATA, 2, 90, 0, 0, 0x96, 0x00, 0x30];
+        FecParser::parse_data_page(&data)
+            .unwrap()
+            .update_trainer_data(&mut trainer_data);
+        assert!(trainer_data.alerts.is_empty());
+
+        // Page 16 reports the FE state too
+        let data = [PAGE_GENERAL_FE_DATA, 25, 0, 0, 0, 0, 0xFF, 0x40];
+        FecParser::parse_data_page(&data)
+            .unwrap()
+            .update_trainer_data(&mut trainer_data);
+        assert_eq!(trainer_data.fe_state, Some(FeState::Finished));
     }
 
     #[test]
@@ -621,4 +2183,180 @@ mod tests {
 
         assert!((trainer_data.speed - 36.0).abs() < 0.01);
     }
+
+    #[test]
+    fn test_encode_target_power() {
+        let page = FecCommand::target_power(250.0).encode();
+
+        assert_eq!(page[0], PAGE_TARGET_POWER);
+        assert_eq!(&page[1..6], &[0xFF; 5]);
+        // 250W = 1000 quarter watts = 0x03E8
+        assert_eq!(page[6], 0xE8);
+        assert_eq!(page[7], 0x03);
+    }
+
+    #[test]
+    fn test_target_power_is_clamped() {
+        assert_eq!(
+            FecCommand::target_power(5000.0),
+            FecCommand::TargetPower {
+                target_quarter_watts: 16000
+            }
+        );
+        assert_eq!(
+            FecCommand::target_power(-10.0),
+            FecCommand::TargetPower {
+                target_quarter_watts: 0
+            }
+        );
+    }
+
+    #[test]
+    fn test_encode_request_data_page() {
+        let page = FecCommand::request_page(PAGE_COMMAND_STATUS).encode();
+
+        assert_eq!(page[0], PAGE_REQUEST_DATA);
+        assert_eq!(page[5], 1); // Transmit once
+        assert_eq!(page[6], PAGE_COMMAND_STATUS);
+        assert_eq!(page[7], REQUEST_COMMAND_TYPE_DATA_PAGE);
+    }
+
+    #[test]
+    fn test_command_status_confirms_target_power() {
+        let command = FecCommand::target_power(250.0);
+        let status = |status, setpoint: u16| {
+            let setpoint = setpoint.to_le_bytes();
+            FecDataPage::CommandStatus {
+                last_command: PAGE_TARGET_POWER,
+                sequence_number: 1,
+                status,
+                data: [0xFF, 0xFF, setpoint[0], setpoint[1]],
+            }
+        };
+
+        let pass = status(COMMAND_STATUS_PASS, 1000);
+        assert_eq!(command.check_status(&pass), Some(Ok(())));
+
+        // Stale setpoint from a previous command
+        let stale = status(COMMAND_STATUS_PASS, 800);
+        assert_eq!(command.check_status(&stale), None);
+
+        let pending = status(COMMAND_STATUS_PENDING, 1000);
+        assert_eq!(command.check_status(&pending), None);
+
+        assert!(matches!(
+            command.check_status(&status(COMMAND_STATUS_NOT_SUPPORTED, 1000)),
+            Some(Err(_))
+        ));
+    }
+
+    #[test]
+    fn test_command_status_ignores_other_commands() {
+        let command = FecCommand::target_power(250.0);
+        let page = FecDataPage::CommandStatus {
+            last_command: 0xFF,
+            sequence_number: 0,
+            status: COMMAND_STATUS_UNINITIALIZED,
+            data: [0xFF; 4],
+        };
+
+        assert_eq!(command.check_status(&page), None);
+    }
+
+    #[test]
+    fn test_encode_track_resistance() {
+        let page = FecCommand::track_resistance(5.0, Some(0.004)).encode();
+
+        assert_eq!(page[0], PAGE_TRACK_RESISTANCE);
+        // 5% = (5 + 200) / 0.01 = 20500 = 0x5014
+        assert_eq!(u16::from_le_bytes([page[5], page[6]]), 20500);
+        // Crr 0.004 / 0.00005 = 80
+        assert_eq!(page[7], 80);
+    }
+
+    #[test]
+    fn test_track_resistance_negative_grade_and_default_crr() {
+        let page = FecCommand::track_resistance(-3.5, None).encode();
+
+        assert_eq!(u16::from_le_bytes([page[5], page[6]]), 19650);
+        assert_eq!(page[7], 0xFF);
+    }
+
+    #[test]
+    fn test_encode_wind_resistance() {
+        let page = FecCommand::wind_resistance(Some(0.51), Some(-10.0), Some(1.0)).encode();
+
+        assert_eq!(page[0], PAGE_WIND_RESISTANCE);
+        assert_eq!(page[5], 51);
+        assert_eq!(page[6], 117); // -10 km/h headwind
+        assert_eq!(page[7], 100);
+
+        let defaults = FecCommand::wind_resistance(None, None, None).encode();
+        assert_eq!(&defaults[5..8], &[0xFF, 0xFF, 0xFF]);
+    }
+
+    #[test]
+    fn test_encode_user_configuration() {
+        let config = UserConfig {
+            user_weight_kg: 75.0,
+            bike_weight_kg: 9.5,
+            wheel_diameter_m: 0.7,
+            gear_ratio: None,
+        };
+        let page = FecCommand::user_configuration(&config).encode();
+
+        assert_eq!(page[0], PAGE_USER_CONFIG);
+        assert_eq!(u16::from_le_bytes([page[1], page[2]]), 7500);
+        // Bike weight 9.5kg / 0.05 = 190 = 0x0BE, split across bytes 4-5
+        assert_eq!(page[4], 0xEF);
+        assert_eq!(page[5], 0x0B);
+        assert_eq!(page[6], 70);
+        assert_eq!(page[7], 0); // Gear ratio invalid
+        assert!(!FecCommand::user_configuration(&config).expects_status());
+    }
+
+    #[test]
+    fn test_command_status_confirms_track_resistance() {
+        let command = FecCommand::track_resistance(2.0, None);
+        let page = FecDataPage::CommandStatus {
+            last_command: PAGE_TRACK_RESISTANCE,
+            sequence_number: 3,
+            status: COMMAND_STATUS_PASS,
+            data: [0xFF, 0xE8, 0x4E, 0xFF], // 20200 = 2%
+        };
+
+        assert_eq!(command.check_status(&page), Some(Ok(())));
+    }
+
+    #[test]
+    fn test_encode_calibration_request() {
+        let command = FecCommand::calibration_request(true, false);
+        let page = command.encode();
+
+        assert_eq!(page[0], PAGE_CALIBRATION_REQUEST);
+        assert_eq!(page[1], CALIBRATION_SPIN_DOWN);
+        assert_eq!(&page[3..8], &[0xFF; 5]);
+        assert!(!command.expects_status());
+
+        assert_eq!(
+            FecCommand::calibration_request(true, true).encode()[1],
+            CALIBRATION_SPIN_DOWN | CALIBRATION_ZERO_OFFSET
+        );
+    }
+
+    #[test]
+    fn test_encode_basic_resistance() {
+        let page = FecCommand::basic_resistance(37.5).encode();
+
+        assert_eq!(page[0], PAGE_BASIC_RESISTANCE);
+        assert_eq!(&page[1..7], &[0xFF; 6]);
+        assert_eq!(page[7], 75); // 0.5% units
+
+        assert_eq!(
+            FecCommand::basic_resistance(150.0),
+            FecCommand::BasicResistance {
+                total_resistance: 200
+            }
+        );
+    }
 }
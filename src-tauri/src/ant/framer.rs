@@ -0,0 +1,200 @@
+// ANT+ Stream Framer
+//
+// USB bulk reads are not aligned to ANT messages: one read can carry
+// several messages, and a message can be split across two reads. This
+// module buffers the raw byte stream and yields every complete message.
+
+use super::channel::ANT_SYNC_BYTE;
+
+// Sync + length + message ID + checksum
+const FRAME_OVERHEAD: usize = 4;
+
+// Largest data length we accept before treating the length byte as garbage
+const MAX_DATA_LENGTH: usize = 60;
+
+/// Stateful ANT message framer
+pub struct AntFramer {
+    buffer: Vec<u8>,
+    framing_errors: u64,
+}
+
+impl AntFramer {
+    pub fn new() -> Self {
+        Self {
+            buffer: Vec::with_capacity(128),
+            framing_errors: 0,
+        }
+    }
+
+    /// Append raw bytes from a read and return every complete message
+    ///
+    /// Each message is (message_id, channel, data) like
+    /// `AntChannel::parse_message`. Incomplete trailing bytes are kept
+    /// for the next call.
+    pub fn push(&mut self, bytes: &[u8]) -> Vec<(u8, u8, Vec<u8>)> {
+        self.buffer.extend_from_slice(bytes);
+
+        let mut messages = Vec::new();
+        while let Some(message) = self.next_message() {
+            messages.push(message);
+        }
+        messages
+    }
+
+    /// Number of discarded bytes runs and checksum failures so far
+    pub fn framing_errors(&self) -> u64 {
+        self.framing_errors
+    }
+
+    /// Drop any buffered partial frame (e.g. after the stick is reset)
+    pub fn clear(&mut self) {
+        self.buffer.clear();
+    }
+
+    fn next_message(&mut self) -> Option<(u8, u8, Vec<u8>)> {
+        loop {
+            // Discard anything before the next sync byte
+            match self.buffer.iter().position(|&b| b == ANT_SYNC_BYTE) {
+                Some(0) => {}
+                Some(pos) => {
+                    self.framing_errors += 1;
+                    self.buffer.drain(..pos);
+                }
+                None => {
+                    if !self.buffer.is_empty() {
+                        self.framing_errors += 1;
+                        self.buffer.clear();
+                    }
+                    return None;
+                }
+            }
+
+            if self.buffer.len() < 2 {
+                return None;
+            }
+
+            let length = self.buffer[1] as usize;
+            if length > MAX_DATA_LENGTH {
+                self.resync();
+                continue;
+            }
+
+            let frame_len = length + FRAME_OVERHEAD;
+            if self.buffer.len() < frame_len {
+                // Wait for the rest of the frame
+                return None;
+            }
+
+            let checksum_pos = frame_len - 1;
+            let checksum = self.buffer[..checksum_pos]
+                .iter()
+                .fold(0u8, |acc, &b| acc ^ b);
+            if checksum != self.buffer[checksum_pos] {
+                self.resync();
+                continue;
+            }
+
+            let frame: Vec<u8> = self.buffer.drain(..frame_len).collect();
+            let msg_id = frame[2];
+            let channel = if length > 0 { frame[3] } else { 0 };
+            let data = frame[3..3 + length].to_vec();
+
+            return Some((msg_id, channel, data));
+        }
+    }
+
+    /// Bad frame: drop it up to the next sync byte (one error per frame)
+    fn resync(&mut self) {
+        self.framing_errors += 1;
+        let next_sync = self.buffer[1..]
+            .iter()
+            .position(|&b| b == ANT_SYNC_BYTE)
+            .map_or(self.buffer.len(), |pos| pos + 1);
+        self.buffer.drain(..next_sync);
+    }
+}
+
+impl Default for AntFramer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ant::channel::AntChannel;
+
+    fn broadcast(channel: u8, page: u8) -> Vec<u8> {
+        AntChannel::build_message(0x4E, &[channel, page, 1, 2, 3, 4, 5, 6, 7])
+    }
+
+    #[test]
+    fn test_multiple_messages_in_one_read() {
+        let mut framer = AntFramer::new();
+        let mut bytes = broadcast(0, 0x10);
+        bytes.extend(broadcast(1, 0x04));
+        bytes.extend(broadcast(0, 0x19));
+
+        let messages = framer.push(&bytes);
+
+        assert_eq!(messages.len(), 3);
+        assert_eq!(messages[1].1, 1);
+        assert_eq!(messages[2].2[1], 0x19);
+        assert_eq!(framer.framing_errors(), 0);
+    }
+
+    #[test]
+    fn test_message_split_across_reads() {
+        let mut framer = AntFramer::new();
+        let bytes = broadcast(0, 0x10);
+
+        assert!(framer.push(&bytes[..5]).is_empty());
+        let messages = framer.push(&bytes[5..]);
+
+        assert_eq!(messages.len(), 1);
+        assert_eq!(messages[0].0, 0x4E);
+        assert_eq!(messages[0].2.len(), 9);
+    }
+
+    #[test]
+    fn test_resync_after_checksum_error() {
+        let mut framer = AntFramer::new();
+        let mut corrupted = broadcast(0, 0x10);
+        let last = corrupted.len() - 1;
+        corrupted[last] ^= 0xFF;
+
+        let mut bytes = corrupted;
+        bytes.extend(broadcast(1, 0x04));
+
+        let messages = framer.push(&bytes);
+
+        assert_eq!(messages.len(), 1);
+        assert_eq!(messages[0].1, 1);
+        assert!(framer.framing_errors() >= 1);
+    }
+
+    #[test]
+    fn test_garbage_before_sync_is_counted() {
+        let mut framer = AntFramer::new();
+        let mut bytes = vec![0x00, 0x13, 0x37];
+        bytes.extend(broadcast(0, 0x10));
+
+        let messages = framer.push(&bytes);
+
+        assert_eq!(messages.len(), 1);
+        assert_eq!(framer.framing_errors(), 1);
+    }
+
+    #[test]
+    fn test_oversized_length_resyncs() {
+        let mut framer = AntFramer::new();
+        let mut bytes = vec![ANT_SYNC_BYTE, 0xF0];
+        bytes.extend(broadcast(0, 0x10));
+
+        let messages = framer.push(&bytes);
+
+        assert_eq!(messages.len(), 1);
+        assert_eq!(framer.framing_errors(), 1);
+    }
+}
@@ -0,0 +1,117 @@
+// ANT USB Stick Hot-Plug Monitoring
+//
+// Reports the stick being plugged in or pulled out. Uses libusb hot-plug
+// callbacks where the platform supports them and falls back to rescanning
+// the bus periodically.
+
+use super::usb::{is_ant_stick, stick_present, ANT_USB_VID};
+use rusb::{Context, Device, Hotplug, HotplugBuilder, UsbContext};
+use std::thread;
+use std::time::Duration;
+
+// Bus rescan interval when libusb has no hot-plug support
+const RESCAN_INTERVAL: Duration = Duration::from_millis(2000);
+
+/// Stick arrival or removal
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StickEvent {
+    Arrived,
+    Left,
+}
+
+struct StickCallback<F> {
+    on_event: F,
+}
+
+impl<F: Fn(StickEvent) + Send> StickCallback<F> {
+    fn notify(&self, device: &Device<Context>, event: StickEvent) {
+        // Only the blocking-safe cached descriptor is read in a callback
+        if let Ok(desc) = device.device_descriptor() {
+            if is_ant_stick(desc.vendor_id(), desc.product_id()) {
+                (self.on_event)(event);
+            }
+        }
+    }
+}
+
+impl<F: Fn(StickEvent) + Send> Hotplug<Context> for StickCallback<F> {
+    fn device_arrived(&mut self, device: Device<Context>) {
+        self.notify(&device, StickEvent::Arrived);
+    }
+
+    fn device_left(&mut self, device: Device<Context>) {
+        self.notify(&device, StickEvent::Left);
+    }
+}
+
+/// Watch for the ANT stick on a background thread
+///
+/// `on_event` runs on the watcher thread and should only record the
+/// event; re-opening the stick is left to the caller's own thread.
+pub fn spawn_stick_watcher<F>(on_event: F)
+where
+    F: Fn(StickEvent) + Send + Clone + 'static,
+{
+    thread::spawn(move || {
+        if rusb::has_hotplug() {
+            match watch_hotplug(on_event) {
+                Ok(()) => {}
+                Err((e, on_event)) => {
+                    eprintln!("USB hot-plug unavailable ({}), rescanning instead", e);
+                    watch_rescan(on_event);
+                }
+            }
+        } else {
+            watch_rescan(on_event);
+        }
+    });
+}
+
+/// Block on libusb hot-plug events; hands the callback back on setup failure
+fn watch_hotplug<F>(on_event: F) -> Result<(), (String, F)>
+where
+    F: Fn(StickEvent) + Send + Clone + 'static,
+{
+    let context = match Context::new() {
+        Ok(context) => context,
+        Err(e) => return Err((e.to_string(), on_event)),
+    };
+
+    let callback = Box::new(StickCallback {
+        on_event: on_event.clone(),
+    });
+    let _registration = match HotplugBuilder::new()
+        .vendor_id(ANT_USB_VID)
+        .register(&context, callback)
+    {
+        Ok(registration) => registration,
+        Err(e) => return Err((e.to_string(), on_event)),
+    };
+
+    println!("Watching for ANT+ stick hot-plug events");
+    loop {
+        if let Err(e) = context.handle_events(None) {
+            eprintln!("USB hot-plug event handling failed: {}", e);
+            thread::sleep(RESCAN_INTERVAL);
+        }
+    }
+}
+
+/// Poll the bus and report presence changes
+fn watch_rescan<F: Fn(StickEvent)>(on_event: F) {
+    let mut present = stick_present();
+
+    loop {
+        thread::sleep(RESCAN_INTERVAL);
+
+        let now_present = stick_present();
+        if now_present != present {
+            present = now_present;
+            on_event(if present {
+                StickEvent::Arrived
+            } else {
+                StickEvent::Left
+            });
+        }
+    }
+}
@@ -31,6 +31,16 @@ impl HrmParser {
         Some(hr)
     }
 
+    /// Parse Page 2 (Manufacturer Information)
+    /// Returns (manufacturer ID, upper 16 bits of the serial number)
+    pub fn parse_manufacturer_info(data: &[u8]) -> Option<(u8, u16)> {
+        if data.len() < 8 || Self::get_page_number(data)? != PAGE_2_MANUFACTURER {
+            return None;
+        }
+
+        Some((data[1], u16::from_le_bytes([data[2], data[3]])))
+    }
+
     /// Get the page number from HR data
     pub fn get_page_number(data: &[u8]) -> Option<u8> {
         if data.is_empty() {
@@ -58,6 +68,16 @@ mod tests {
         assert_eq!(HrmParser::parse_heart_rate(&data), None);
     }
 
+    #[test]
+    fn test_parse_manufacturer_info() {
+        // Page 2 with the page toggle bit set: Garmin (1), serial 0x1234
+        let data = [0x82, 0x01, 0x34, 0x12, 0x00, 0x00, 0x00, 142];
+        assert_eq!(HrmParser::parse_manufacturer_info(&data), Some((1, 0x1234)));
+
+        let page_0 = [0x00, 0xFF, 0xFF, 0xFF, 0xFF, 0x00, 0x00, 142];
+        assert_eq!(HrmParser::parse_manufacturer_info(&page_0), None);
+    }
+
     #[test]
     fn test_short_data() {
         let data = [0x00, 0xFF, 0xFF];
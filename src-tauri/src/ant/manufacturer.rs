@@ -0,0 +1,186 @@
+// ANT+ Manufacturer and Product Names
+//
+// Names for the manufacturer IDs and model numbers reported in FE-C Page 80
+// and HRM Page 2. ANT+ uses the manufacturer IDs of the FIT profile, so the
+// same ID goes straight into recorded FIT files. Also holds the known
+// deviations of particular trainers from the FE-C profile.
+
+use serde::Serialize;
+
+pub const MANUFACTURER_GARMIN: u16 = 1;
+pub const MANUFACTURER_SRM: u16 = 6;
+pub const MANUFACTURER_QUARQ: u16 = 7;
+pub const MANUFACTURER_SARIS: u16 = 9; // CycleOps / PowerTap
+pub const MANUFACTURER_DYNASTREAM_OEM: u16 = 13;
+pub const MANUFACTURER_DYNASTREAM: u16 = 15;
+pub const MANUFACTURER_SUUNTO: u16 = 23;
+pub const MANUFACTURER_WAHOO: u16 = 32;
+pub const MANUFACTURER_CONCEPT2: u16 = 40;
+pub const MANUFACTURER_PIONEER: u16 = 48;
+pub const MANUFACTURER_4IIII: u16 = 51;
+pub const MANUFACTURER_STAGES: u16 = 69;
+pub const MANUFACTURER_SIGMASPORT: u16 = 70;
+pub const MANUFACTURER_ELITE: u16 = 86;
+pub const MANUFACTURER_TACX: u16 = 89;
+pub const MANUFACTURER_STRYD: u16 = 95;
+pub const MANUFACTURER_POLAR: u16 = 123;
+pub const MANUFACTURER_DEVELOPMENT: u16 = 255;
+
+const MANUFACTURERS: &[(u16, &str)] = &[
+    (MANUFACTURER_GARMIN, "Garmin"),
+    (MANUFACTURER_SRM, "SRM"),
+    (MANUFACTURER_QUARQ, "Quarq"),
+    (MANUFACTURER_SARIS, "Saris (CycleOps)"),
+    (MANUFACTURER_DYNASTREAM_OEM, "Dynastream OEM"),
+    (MANUFACTURER_DYNASTREAM, "Dynastream"),
+    (MANUFACTURER_SUUNTO, "Suunto"),
+    (MANUFACTURER_WAHOO, "Wahoo"),
+    (MANUFACTURER_CONCEPT2, "Concept2"),
+    (MANUFACTURER_PIONEER, "Pioneer"),
+    (MANUFACTURER_4IIII, "4iiii"),
+    (MANUFACTURER_STAGES, "Stages"),
+    (MANUFACTURER_SIGMASPORT, "Sigma Sport"),
+    (MANUFACTURER_ELITE, "Elite"),
+    (MANUFACTURER_TACX, "Tacx"),
+    (MANUFACTURER_STRYD, "Stryd"),
+    (MANUFACTURER_POLAR, "Polar"),
+    (MANUFACTURER_DEVELOPMENT, "Development"),
+];
+
+// Tacx trainers report their article number (T2875 -> 2875). Wahoo, Elite
+// and Saris don't publish their FE-C model numbers, so their trainers are
+// named by brand alone until a model's number is confirmed from a capture.
+const MODELS: &[(u16, u16, &str)] = &[
+    (MANUFACTURER_TACX, 2080, "Genius Smart"),
+    (MANUFACTURER_TACX, 2180, "Vortex Smart"),
+    (MANUFACTURER_TACX, 2240, "Flow Smart"),
+    (MANUFACTURER_TACX, 2780, "Bushido Smart"),
+    (MANUFACTURER_TACX, 2800, "Neo Smart"),
+    (MANUFACTURER_TACX, 2850, "Neo 2 Smart"),
+    (MANUFACTURER_TACX, 2875, "Neo 2T Smart"),
+    (MANUFACTURER_TACX, 2900, "Flux Smart"),
+    (MANUFACTURER_TACX, 2980, "Flux 2 Smart"),
+];
+
+/// Manufacturer name for an ANT+ manufacturer ID
+pub fn manufacturer_name(manufacturer_id: u16) -> Option<&'static str> {
+    MANUFACTURERS
+        .iter()
+        .find(|(id, _)| *id == manufacturer_id)
+        .map(|(_, name)| *name)
+}
+
+/// Model name for a manufacturer's model number
+pub fn model_name(manufacturer_id: u16, model_number: u16) -> Option<&'static str> {
+    MODELS
+        .iter()
+        .find(|(id, model, _)| *id == manufacturer_id && *model == model_number)
+        .map(|(_, _, name)| *name)
+}
+
+/// Display name such as "Tacx Neo 2T Smart" or "Wahoo"
+///
+/// `None` when the manufacturer is unknown; an unknown model of a known
+/// manufacturer gets the manufacturer name alone.
+pub fn device_name(manufacturer_id: u16, model_number: Option<u16>) -> Option<String> {
+    let manufacturer = manufacturer_name(manufacturer_id)?;
+    match model_number.and_then(|model| model_name(manufacturer_id, model)) {
+        Some(model) => Some(format!("{} {}", manufacturer, model)),
+        None => Some(manufacturer.to_string()),
+    }
+}
+
+/// Known deviations of a trainer from the FE-C profile
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct TrainerQuirks {
+    /// Doesn't answer Page 70 requests for Command Status (Page 71);
+    /// control pages are sent once, unconfirmed
+    pub no_command_status: bool,
+}
+
+// Quirks by manufacturer and, when only some models are affected, model.
+// Add an entry only with the capture or vendor note that shows the deviation.
+const QUIRKS: &[(u16, Option<u16>, TrainerQuirks)] = &[];
+
+/// Quirks of a trainer, from its Page 80 identity
+pub fn trainer_quirks(manufacturer_id: u16, model_number: u16) -> TrainerQuirks {
+    QUIRKS
+        .iter()
+        .find(|(id, model, _)| {
+            *id == manufacturer_id && model.map_or(true, |model| model == model_number)
+        })
+        .map(|(_, _, quirks)| *quirks)
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_device_names() {
+        assert_eq!(
+            device_name(MANUFACTURER_TACX, Some(2875)).as_deref(),
+            Some("Tacx Neo 2T Smart")
+        );
+        assert_eq!(
+            device_name(MANUFACTURER_WAHOO, Some(2620)).as_deref(),
+            Some("Wahoo")
+        );
+        assert_eq!(
+            device_name(MANUFACTURER_ELITE, Some(5)).as_deref(),
+            Some("Elite")
+        );
+        assert_eq!(
+            device_name(MANUFACTURER_SARIS, Some(3)).as_deref(),
+            Some("Saris (CycleOps)")
+        );
+        assert_eq!(
+            device_name(MANUFACTURER_GARMIN, None).as_deref(),
+            Some("Garmin")
+        );
+        assert_eq!(device_name(4242, Some(1)), None);
+    }
+
+    #[test]
+    fn test_manufacturer_ids_match_fit_profile() {
+        // `manufacturer` type of the FIT SDK profile
+        let fit_profile = [
+            (MANUFACTURER_GARMIN, 1),
+            (MANUFACTURER_SRM, 6),
+            (MANUFACTURER_QUARQ, 7),
+            (MANUFACTURER_SARIS, 9),
+            (MANUFACTURER_DYNASTREAM_OEM, 13),
+            (MANUFACTURER_DYNASTREAM, 15),
+            (MANUFACTURER_SUUNTO, 23),
+            (MANUFACTURER_WAHOO, 32),
+            (MANUFACTURER_CONCEPT2, 40),
+            (MANUFACTURER_PIONEER, 48),
+            (MANUFACTURER_4IIII, 51),
+            (MANUFACTURER_STAGES, 69),
+            (MANUFACTURER_SIGMASPORT, 70),
+            (MANUFACTURER_ELITE, 86),
+            (MANUFACTURER_TACX, 89),
+            (MANUFACTURER_STRYD, 95),
+            (MANUFACTURER_POLAR, 123),
+            (MANUFACTURER_DEVELOPMENT, 255),
+        ];
+        for (id, fit_id) in fit_profile {
+            assert_eq!(id, fit_id);
+        }
+        assert_eq!(manufacturer_name(51), Some("4iiii"));
+        assert_eq!(manufacturer_name(70), Some("Sigma Sport"));
+    }
+
+    #[test]
+    fn test_trainer_quirks() {
+        assert_eq!(
+            trainer_quirks(MANUFACTURER_SARIS, 1),
+            TrainerQuirks::default()
+        );
+        assert_eq!(
+            trainer_quirks(MANUFACTURER_TACX, 2875),
+            TrainerQuirks::default()
+        );
+    }
+}
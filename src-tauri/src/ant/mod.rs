@@ -3,11 +3,28 @@
 // This module provides ANT+ FE-C (Fitness Equipment Control) protocol
 // support for communicating with smart trainers.
 
+pub mod allocator;
+pub mod calibration;
+pub mod capture;
 pub mod channel;
+pub mod control;
+pub mod discovery;
 pub mod fec;
+pub mod framer;
+pub mod hotplug;
 pub mod hrm;
+pub mod manufacturer;
+pub mod monitor;
+pub mod pairing;
+pub mod profile;
+pub mod scan;
+pub mod serial;
+#[cfg(any(test, feature = "simulated-ant"))]
+pub mod sim;
+pub mod transport;
 pub mod usb;
 
+use fec::{Equipment, FeState, TrainerAlert};
 use serde::{Deserialize, Serialize};
 
 /// Real-time data from a connected trainer
@@ -21,6 +38,50 @@ pub struct TrainerData {
     pub cadence: u8,
     /// Heart rate in BPM (from ANT+ HR strap if paired)
     pub heart_rate: u8,
+    /// Power averaged over the trainer's update events since its last page
+    pub average_power: u16,
+    /// Distance reported by the trainer since the connect, in meters
+    pub trainer_distance: u32,
+    /// Time reported by the trainer since the connect, in seconds
+    pub trainer_elapsed_time: f32,
+    /// Incline set on the equipment in percent (FE-C Page 17)
+    pub incline: Option<f32>,
+    /// Resistance level in percent of the trainer's maximum (FE-C Page 17)
+    pub resistance_level: Option<f32>,
+    /// Cycle length (wheel circumference) in meters (FE-C Page 17)
+    pub cycle_length: Option<f32>,
+    /// Trainer-computed metabolic equivalents (FE-C Page 18)
+    pub mets: Option<f32>,
+    /// Trainer-computed calories since the connect, in kcal (FE-C Page 18)
+    pub calories: Option<u32>,
+    /// Equipment state from the trainer's pages (asleep, ready, in use, finished)
+    pub fe_state: Option<FeState>,
+    /// ERG limits and calibration/configuration requests from FE-C Page 25
+    pub alerts: Vec<TrainerAlert>,
+    /// Wheel speed in km/h (FE-C Page 26)
+    pub wheel_speed: Option<f32>,
+    /// Average torque in Nm (FE-C Page 26)
+    pub torque: Option<f32>,
+    /// Power derived from torque and wheel speed, in watts (FE-C Page 26)
+    pub torque_power: Option<u16>,
+    /// Torque-derived power disagrees sharply with the reported power
+    pub power_mismatch: bool,
+    /// Kind of fitness equipment (FE-C Page 16)
+    pub equipment: Option<Equipment>,
+    /// Pace in seconds per km, for equipment other than bikes
+    pub pace: Option<f32>,
+    /// Rowing stroke rate in strokes/min (FE-C Page 22)
+    pub stroke_rate: Option<u8>,
+    /// Strides, or rowing strokes, counted since the connect (FE-C Pages 20-24)
+    pub strides: Option<u32>,
+    /// Climbed minus descended since the connect, in meters (FE-C Pages 19-20)
+    pub vertical_distance: Option<f32>,
+    /// Device number of the trainer sending the data (from extended messages)
+    pub trainer_device: Option<u16>,
+    /// Device number of the heart rate strap
+    pub heart_rate_device: Option<u16>,
+    /// Manufacturer ID of the heart rate strap (HRM Page 2)
+    pub heart_rate_manufacturer: Option<u16>,
 }
 
 impl Default for TrainerData {
@@ -30,6 +91,28 @@ impl Default for TrainerData {
             speed: 0.0,
             cadence: 0,
             heart_rate: 0,
+            average_power: 0,
+            trainer_distance: 0,
+            trainer_elapsed_time: 0.0,
+            incline: None,
+            resistance_level: None,
+            cycle_length: None,
+            mets: None,
+            calories: None,
+            fe_state: None,
+            alerts: Vec::new(),
+            wheel_speed: None,
+            torque: None,
+            torque_power: None,
+            power_mismatch: false,
+            equipment: None,
+            pace: None,
+            stroke_rate: None,
+            strides: None,
+            vertical_distance: None,
+            trainer_device: None,
+            heart_rate_device: None,
+            heart_rate_manufacturer: None,
         }
     }
 }
@@ -42,6 +125,28 @@ impl TrainerData {
             speed,
             cadence,
             heart_rate,
+            average_power: 0,
+            trainer_distance: 0,
+            trainer_elapsed_time: 0.0,
+            incline: None,
+            resistance_level: None,
+            cycle_length: None,
+            mets: None,
+            calories: None,
+            fe_state: None,
+            alerts: Vec::new(),
+            wheel_speed: None,
+            torque: None,
+            torque_power: None,
+            power_mismatch: false,
+            equipment: None,
+            pace: None,
+            stroke_rate: None,
+            strides: None,
+            vertical_distance: None,
+            trainer_device: None,
+            heart_rate_device: None,
+            heart_rate_manufacturer: None,
         }
     }
 
@@ -0,0 +1,224 @@
+// ANT+ Channel State Tracking
+//
+// Follows a slave channel through searching, tracking and loss using the
+// data and RF events the stick sends, and decides when a channel that the
+// stick closed on its own (search timeout) should be reopened.
+
+use super::channel::{
+    ChannelEvent, EVENT_CHANNEL_CLOSED, EVENT_RX_FAIL_GO_TO_SEARCH, MESG_ACKNOWLEDGED_DATA,
+    MESG_BROADCAST_DATA, MESG_BURST_DATA,
+};
+use serde::Serialize;
+use std::time::{Duration, Instant};
+
+// Wait before reopening a channel the stick closed after a search timeout
+const REOPEN_DELAY: Duration = Duration::from_millis(1000);
+
+/// Connection state of one sensor channel
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ChannelState {
+    /// Not opened, or closed by the app
+    Closed,
+    /// Open and looking for the device
+    Searching,
+    /// Receiving data from the device
+    Tracking,
+    /// Device dropped out; the channel is searching again or will reopen
+    Lost,
+}
+
+/// Connection state of each sensor, pushed to the webview as `sensor-status`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct SensorStatus {
+    pub trainer: ChannelState,
+    pub heart_rate: ChannelState,
+    /// False while the stick is unplugged and being re-opened
+    pub stick_present: bool,
+}
+
+/// Per-channel state machine
+#[derive(Debug)]
+pub struct ChannelMonitor {
+    state: ChannelState,
+    reopen_at: Option<Instant>,
+    reopen_count: u32,
+}
+
+impl ChannelMonitor {
+    pub fn new() -> Self {
+        Self {
+            state: ChannelState::Closed,
+            reopen_at: None,
+            reopen_count: 0,
+        }
+    }
+
+    pub fn state(&self) -> ChannelState {
+        self.state
+    }
+
+    /// Times the channel has been reopened after the stick closed it
+    pub fn reopen_count(&self) -> u32 {
+        self.reopen_count
+    }
+
+    /// The app opened the channel
+    pub fn opened(&mut self) {
+        self.state = ChannelState::Searching;
+        self.reopen_at = None;
+    }
+
+    /// The app closed the channel; no reopen
+    pub fn closed(&mut self) {
+        self.state = ChannelState::Closed;
+        self.reopen_at = None;
+    }
+
+    /// The stick went away; the channel comes back with the stick
+    pub fn lost(&mut self) {
+        self.state = ChannelState::Lost;
+        self.reopen_at = None;
+    }
+
+    /// Update from a message received on this channel
+    ///
+    /// Returns the new state when it changed.
+    pub fn handle_message(
+        &mut self,
+        msg_id: u8,
+        data: &[u8],
+        now: Instant,
+    ) -> Option<ChannelState> {
+        let previous = self.state;
+
+        match msg_id {
+            MESG_BROADCAST_DATA | MESG_ACKNOWLEDGED_DATA | MESG_BURST_DATA => {
+                if self.state != ChannelState::Closed {
+                    self.state = ChannelState::Tracking;
+                }
+            }
+            _ => match ChannelEvent::parse(msg_id, data) {
+                // Missed too many messages: the stick is searching again
+                Some(ChannelEvent::Event {
+                    code: EVENT_RX_FAIL_GO_TO_SEARCH,
+                    ..
+                }) if self.state == ChannelState::Tracking => {
+                    self.state = ChannelState::Lost;
+                }
+                // Closed by the stick (search timed out) rather than by us
+                Some(ChannelEvent::Event {
+                    code: EVENT_CHANNEL_CLOSED,
+                    ..
+                }) if self.state != ChannelState::Closed => {
+                    self.state = ChannelState::Lost;
+                    self.reopen_at = Some(now + REOPEN_DELAY);
+                }
+                _ => {}
+            },
+        }
+
+        (self.state != previous).then_some(self.state)
+    }
+
+    /// Whether the channel should be reopened now; marks it searching
+    pub fn take_reopen(&mut self, now: Instant) -> bool {
+        match self.reopen_at {
+            Some(at) if now >= at => {
+                self.reopen_at = None;
+                self.reopen_count += 1;
+                self.state = ChannelState::Searching;
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
+impl Default for ChannelMonitor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ant::channel::{EVENT_RX_SEARCH_TIMEOUT, MESG_EVENT_ID, MESG_RESPONSE_EVENT};
+
+    const PAGE: [u8; 9] = [0, 0x10, 25, 0, 0, 0, 0, 0xFF, 0x30];
+
+    fn event(code: u8) -> [u8; 3] {
+        [0, MESG_EVENT_ID, code]
+    }
+
+    #[test]
+    fn test_data_moves_searching_to_tracking() {
+        let mut monitor = ChannelMonitor::new();
+        let now = Instant::now();
+        monitor.opened();
+
+        assert_eq!(
+            monitor.handle_message(MESG_BROADCAST_DATA, &PAGE, now),
+            Some(ChannelState::Tracking)
+        );
+        assert_eq!(
+            monitor.handle_message(MESG_BROADCAST_DATA, &PAGE, now),
+            None
+        );
+    }
+
+    #[test]
+    fn test_rx_fail_go_to_search_marks_lost_until_data_returns() {
+        let mut monitor = ChannelMonitor::new();
+        let now = Instant::now();
+        monitor.opened();
+        monitor.handle_message(MESG_BROADCAST_DATA, &PAGE, now);
+
+        let lost = event(EVENT_RX_FAIL_GO_TO_SEARCH);
+        assert_eq!(
+            monitor.handle_message(MESG_RESPONSE_EVENT, &lost, now),
+            Some(ChannelState::Lost)
+        );
+        // Still open: no reopen needed, data simply resumes
+        assert!(!monitor.take_reopen(now + REOPEN_DELAY));
+        assert_eq!(
+            monitor.handle_message(MESG_BROADCAST_DATA, &PAGE, now),
+            Some(ChannelState::Tracking)
+        );
+    }
+
+    #[test]
+    fn test_search_timeout_close_schedules_reopen() {
+        let mut monitor = ChannelMonitor::new();
+        let now = Instant::now();
+        monitor.opened();
+
+        monitor.handle_message(MESG_RESPONSE_EVENT, &event(EVENT_RX_SEARCH_TIMEOUT), now);
+        assert_eq!(monitor.state(), ChannelState::Searching);
+
+        monitor.handle_message(MESG_RESPONSE_EVENT, &event(EVENT_CHANNEL_CLOSED), now);
+        assert_eq!(monitor.state(), ChannelState::Lost);
+
+        assert!(!monitor.take_reopen(now));
+        assert!(monitor.take_reopen(now + REOPEN_DELAY));
+        assert_eq!(monitor.state(), ChannelState::Searching);
+        assert_eq!(monitor.reopen_count(), 1);
+        assert!(!monitor.take_reopen(now + REOPEN_DELAY * 2));
+    }
+
+    #[test]
+    fn test_closed_by_app_is_not_reopened() {
+        let mut monitor = ChannelMonitor::new();
+        let now = Instant::now();
+        monitor.opened();
+        monitor.closed();
+
+        assert_eq!(
+            monitor.handle_message(MESG_RESPONSE_EVENT, &event(EVENT_CHANNEL_CLOSED), now),
+            None
+        );
+        assert!(!monitor.take_reopen(now + REOPEN_DELAY));
+        assert_eq!(monitor.state(), ChannelState::Closed);
+    }
+}
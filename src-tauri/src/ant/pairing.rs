@@ -0,0 +1,146 @@
+// ANT+ Device Pairing
+//
+// Sensors the user picked from a discovery search, saved so the next
+// connect opens channels for those exact devices instead of wildcards.
+
+use super::channel::{AntChannel, FEC_DEVICE_TYPE, HRM_DEVICE_TYPE};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+
+/// Name of the pairing file in the app's data directory
+pub const PAIRING_FILE_NAME: &str = "devices.json";
+
+/// Channel ID of a paired sensor
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct PairedDevice {
+    pub device_number: u16,
+    pub transmission_type: u8,
+}
+
+/// Paired trainer and heart rate strap; None means wildcard search
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct PairedDevices {
+    pub fec: Option<PairedDevice>,
+    pub hrm: Option<PairedDevice>,
+}
+
+impl PairedDevices {
+    /// Load the saved pairings, falling back to wildcards
+    pub fn load(path: &Path) -> Self {
+        let loaded = if path.exists() {
+            Self::load_from(path)
+        } else {
+            Ok(Self::default())
+        };
+
+        loaded.unwrap_or_else(|e| {
+            eprintln!("Ignoring saved ANT+ pairings: {}", e);
+            Self::default()
+        })
+    }
+
+    pub fn load_from(path: &Path) -> Result<Self, String> {
+        let json = fs::read_to_string(path)
+            .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+        serde_json::from_str(&json).map_err(|e| format!("Invalid pairing file: {}", e))
+    }
+
+    pub fn save_to(&self, path: &Path) -> Result<(), String> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir).map_err(|e| format!("Failed to create directory: {}", e))?;
+        }
+
+        let json = serde_json::to_string_pretty(self).map_err(|e| e.to_string())?;
+        fs::write(path, json).map_err(|e| format!("Failed to write pairing file: {}", e))
+    }
+
+    /// Pair (Some) or forget (None) the sensor for an ANT+ device type
+    pub fn set(&mut self, device_type: u8, device: Option<PairedDevice>) -> Result<(), String> {
+        match device_type {
+            FEC_DEVICE_TYPE => self.fec = device,
+            HRM_DEVICE_TYPE => self.hrm = device,
+            _ => return Err(format!("Unsupported ANT+ device type {}", device_type)),
+        }
+        Ok(())
+    }
+
+    /// Paired sensor for an ANT+ device type; None for wildcard search
+    pub fn get(&self, device_type: u8) -> Option<PairedDevice> {
+        match device_type {
+            FEC_DEVICE_TYPE => self.fec,
+            HRM_DEVICE_TYPE => self.hrm,
+            _ => None,
+        }
+    }
+
+    /// FE-C channel for the paired trainer, or a wildcard channel
+    pub fn fec_channel(&self, channel_number: u8) -> AntChannel {
+        Self::channel_for(self.fec, channel_number)
+    }
+
+    /// HRM channel for the paired strap, or a wildcard channel
+    pub fn hrm_channel(&self, channel_number: u8) -> AntChannel {
+        Self::channel_for(self.hrm, channel_number)
+    }
+
+    fn channel_for(device: Option<PairedDevice>, channel_number: u8) -> AntChannel {
+        match device {
+            Some(d) => {
+                AntChannel::with_device(channel_number, d.device_number, d.transmission_type)
+            }
+            None => AntChannel::new(channel_number),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_save_and_load_round_trip() {
+        let path = std::env::temp_dir()
+            .join(format!("notchrider-pairing-{}", std::process::id()))
+            .join("devices.json");
+
+        let mut devices = PairedDevices::default();
+        devices
+            .set(
+                FEC_DEVICE_TYPE,
+                Some(PairedDevice {
+                    device_number: 12345,
+                    transmission_type: 0x05,
+                }),
+            )
+            .unwrap();
+
+        devices.save_to(&path).unwrap();
+        let loaded = PairedDevices::load_from(&path).unwrap();
+        let _ = fs::remove_dir_all(path.parent().unwrap());
+
+        assert_eq!(loaded, devices);
+        assert_eq!(loaded.get(FEC_DEVICE_TYPE).unwrap().device_number, 12345);
+        assert_eq!(loaded.get(HRM_DEVICE_TYPE), None);
+    }
+
+    #[test]
+    fn test_channels_use_paired_device_numbers() {
+        let devices = PairedDevices {
+            fec: None,
+            hrm: Some(PairedDevice {
+                device_number: 54321,
+                transmission_type: 0x01,
+            }),
+        };
+
+        assert_eq!(devices.fec_channel(0).device_number(), 0);
+        assert_eq!(devices.hrm_channel(1).device_number(), 54321);
+    }
+
+    #[test]
+    fn test_set_rejects_unknown_device_type() {
+        let mut devices = PairedDevices::default();
+        assert!(devices.set(11, None).is_err());
+    }
+}
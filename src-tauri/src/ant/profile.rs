@@ -0,0 +1,168 @@
+// ANT+ Sensor Profiles
+//
+// Everything that differs between the sensors the app listens to: the
+// channel configuration, and how their pages update `TrainerData`. The
+// channel allocator and the reader thread work purely in terms of these
+// profiles, so a new sensor type is added here rather than in `lib.rs`.
+
+use super::channel::{AntChannel, FEC_DEVICE_TYPE, HRM_DEVICE_TYPE};
+use super::fec::FecParser;
+use super::hrm::HrmParser;
+use super::pairing::PairedDevices;
+use super::TrainerData;
+use serde::Serialize;
+
+/// A sensor type the app opens a channel for
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SensorProfile {
+    /// Fitness Equipment Control (smart trainer)
+    Fec,
+    /// Heart rate monitor
+    Hrm,
+}
+
+impl SensorProfile {
+    /// Profiles opened on connect, in channel allocation order
+    pub const ALL: [SensorProfile; 2] = [SensorProfile::Fec, SensorProfile::Hrm];
+
+    pub fn device_type(self) -> u8 {
+        match self {
+            SensorProfile::Fec => FEC_DEVICE_TYPE,
+            SensorProfile::Hrm => HRM_DEVICE_TYPE,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            SensorProfile::Fec => "FE-C",
+            SensorProfile::Hrm => "HRM",
+        }
+    }
+
+    /// Channel for the paired device of this profile, or a wildcard channel
+    pub fn channel(self, channel_number: u8, paired: &PairedDevices) -> AntChannel {
+        match self {
+            SensorProfile::Fec => paired.fec_channel(channel_number),
+            SensorProfile::Hrm => paired.hrm_channel(channel_number),
+        }
+    }
+
+    /// Messages that assign, configure and open the channel
+    ///
+    /// Assumes the stick was reset and the network key set beforehand.
+    pub fn init_sequence(self, channel: &AntChannel) -> Vec<Vec<u8>> {
+        match self {
+            SensorProfile::Fec => vec![
+                channel.assign_channel(),
+                channel.set_channel_id(),
+                channel.set_channel_frequency(),
+                channel.set_channel_period(),
+                channel.open_channel(),
+            ],
+            SensorProfile::Hrm => channel.get_hrm_init_sequence(),
+        }
+    }
+
+    /// Apply an 8-byte data page from this sensor
+    pub fn update_trainer_data(self, data: &mut TrainerData, page: &[u8]) {
+        match self {
+            SensorProfile::Fec => {
+                if let Some(page) = FecParser::parse_data_page(page) {
+                    FecParser::update_trainer_data(data, &page);
+                }
+            }
+            SensorProfile::Hrm => {
+                if let Some(hr) = HrmParser::parse_heart_rate(page) {
+                    data.heart_rate = hr;
+                }
+                if let Some((manufacturer_id, _)) = HrmParser::parse_manufacturer_info(page) {
+                    data.heart_rate_manufacturer = Some(manufacturer_id as u16);
+                }
+            }
+        }
+    }
+
+    /// Record which device sent this sensor's data
+    pub fn set_device_number(self, data: &mut TrainerData, device_number: Option<u16>) {
+        match self {
+            SensorProfile::Fec => data.trainer_device = device_number,
+            SensorProfile::Hrm => data.heart_rate_device = device_number,
+        }
+    }
+
+    /// Clear this sensor's readings so the UI doesn't show stale values
+    pub fn clear_trainer_data(self, data: &mut TrainerData) {
+        match self {
+            SensorProfile::Fec => {
+                data.power = 0;
+                data.speed = 0.0;
+                data.cadence = 0;
+                data.average_power = 0;
+                data.fe_state = None;
+                data.alerts.clear();
+                data.wheel_speed = None;
+                data.torque = None;
+                data.torque_power = None;
+                data.power_mismatch = false;
+                data.equipment = None;
+                data.pace = None;
+                data.stroke_rate = None;
+                data.strides = None;
+                data.vertical_distance = None;
+                data.trainer_device = None;
+            }
+            SensorProfile::Hrm => {
+                data.heart_rate = 0;
+                data.heart_rate_device = None;
+                data.heart_rate_manufacturer = None;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ant::channel::MESG_OPEN_CHANNEL;
+    use crate::ant::fec::Equipment;
+
+    #[test]
+    fn test_init_sequence_ends_with_open() {
+        let paired = PairedDevices::default();
+        for profile in SensorProfile::ALL {
+            let channel = profile.channel(3, &paired);
+            let sequence = profile.init_sequence(&channel);
+
+            let open = sequence.last().unwrap();
+            assert_eq!(open[2], MESG_OPEN_CHANNEL);
+            assert_eq!(open[3], 3);
+            // Channel ID carries the profile's device type
+            assert_eq!(sequence[1][6], profile.device_type());
+        }
+    }
+
+    #[test]
+    fn test_pages_update_only_their_own_fields() {
+        let mut data = TrainerData::new(200, 30.0, 90, 0);
+
+        SensorProfile::Hrm.update_trainer_data(&mut data, &[0x00, 0, 0, 0, 0, 0, 0, 150]);
+        assert_eq!(data.heart_rate, 150);
+        assert_eq!(data.power, 200);
+
+        SensorProfile::Hrm.set_device_number(&mut data, Some(54321));
+        assert_eq!(data.heart_rate_device, Some(54321));
+        assert_eq!(data.trainer_device, None);
+
+        data.equipment = Some(Equipment::Rower);
+        data.strides = Some(120);
+        data.vertical_distance = Some(4.5);
+        SensorProfile::Fec.clear_trainer_data(&mut data);
+        assert_eq!(data.power, 0);
+        assert_eq!(data.equipment, None);
+        assert_eq!(data.strides, None);
+        assert_eq!(data.vertical_distance, None);
+        assert_eq!(data.heart_rate, 150);
+        assert_eq!(data.heart_rate_device, Some(54321));
+    }
+}
@@ -0,0 +1,399 @@
+// ANT+ Continuous Scan Mode
+//
+// For rooms with several bikes: instead of one slave channel per sensor,
+// channel 0 is opened in continuous scan mode with a fully wildcarded
+// channel ID, so one stick hears every FE-C, HRM and power device in range.
+// Extended messages name the device behind each broadcast; frames are
+// demultiplexed by that channel ID into a live table of devices.
+
+use super::channel::{
+    AntChannel, ChannelEvent, ExtendedData, LIB_CONFIG_CHANNEL_ID, LIB_CONFIG_RSSI,
+    LIB_CONFIG_RX_TIMESTAMP, MESG_ACKNOWLEDGED_DATA, MESG_BROADCAST_DATA, MESG_OPEN_RX_SCAN_MODE,
+    RESPONSE_NO_ERROR,
+};
+use super::transport::AntTransport;
+use serde::{Serialize, Serializer};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Default length of a scan started from the UI
+pub const DEFAULT_SCAN_DURATION: Duration = Duration::from_secs(10);
+
+/// How often `run` reports the live device table
+pub const SCAN_UPDATE_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Devices silent this long drop out of the table
+pub const SCAN_DEVICE_TIMEOUT: Duration = Duration::from_secs(5);
+
+// Delay between configuration messages, as in connect
+const CONFIG_MESSAGE_DELAY: Duration = Duration::from_millis(50);
+
+/// A sensor heard while scanning
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ScannedDevice {
+    pub device_number: u16,
+    pub device_type: u8,
+    pub transmission_type: u8,
+    /// Last received signal strength in dBm
+    pub rssi: Option<i8>,
+    /// Broadcasts received from this device
+    pub message_count: u32,
+    /// Most recent data page
+    pub last_page: [u8; 8],
+    /// Sent to the UI as `age_secs`, seconds since the last broadcast
+    #[serde(rename = "age_secs", serialize_with = "serialize_age")]
+    pub last_seen: Instant,
+}
+
+fn serialize_age<S: Serializer>(last_seen: &Instant, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_f64(last_seen.elapsed().as_secs_f64())
+}
+
+/// One data page, attributed to the device that sent it
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ScanFrame {
+    pub device_number: u16,
+    pub device_type: u8,
+    pub transmission_type: u8,
+    pub page: [u8; 8],
+}
+
+/// Continuous scan over channel 0
+///
+/// `handle_message` demultiplexes received frames, so the session can be
+/// driven by `run` or fed from a reader thread.
+pub struct ScanSession {
+    channel: AntChannel,
+    devices: Vec<ScannedDevice>,
+    rejected: Option<u8>, // Response code if the stick refused scan mode
+}
+
+impl ScanSession {
+    pub fn new() -> Self {
+        Self {
+            channel: AntChannel::new(0),
+            devices: Vec::new(),
+            rejected: None,
+        }
+    }
+
+    /// Reset the stick, enable extended messages and open scan mode
+    pub fn start_sequence(&self) -> Vec<Vec<u8>> {
+        let ch = &self.channel;
+        vec![
+            AntChannel::reset_system(),
+            ch.set_network_key(),
+            AntChannel::set_lib_config(
+                LIB_CONFIG_CHANNEL_ID | LIB_CONFIG_RSSI | LIB_CONFIG_RX_TIMESTAMP,
+            ),
+            ch.assign_channel(),
+            ch.set_channel_id_any_type(),
+            ch.set_channel_frequency(),
+            AntChannel::open_rx_scan_mode(),
+        ]
+    }
+
+    /// Leave scan mode and turn extended messages off again
+    pub fn stop_sequence(&self) -> Vec<Vec<u8>> {
+        vec![self.channel.close_channel(), AntChannel::set_lib_config(0)]
+    }
+
+    /// Devices heard so far, in order of first appearance
+    pub fn devices(&self) -> &[ScannedDevice] {
+        &self.devices
+    }
+
+    pub fn device(&self, device_type: u8, device_number: u16) -> Option<&ScannedDevice> {
+        self.devices
+            .iter()
+            .find(|d| d.device_type == device_type && d.device_number == device_number)
+    }
+
+    /// Process one received message
+    ///
+    /// Returns the data page and its sender for broadcast and acknowledged
+    /// data that carries the channel ID.
+    pub fn handle_message(&mut self, msg_id: u8, data: &[u8], now: Instant) -> Option<ScanFrame> {
+        if let Some(ChannelEvent::Response {
+            msg_id: MESG_OPEN_RX_SCAN_MODE,
+            code,
+            ..
+        }) = ChannelEvent::parse(msg_id, data)
+        {
+            if code != RESPONSE_NO_ERROR {
+                self.rejected = Some(code);
+            }
+            return None;
+        }
+
+        if !matches!(msg_id, MESG_BROADCAST_DATA | MESG_ACKNOWLEDGED_DATA) || data.len() < 9 {
+            return None;
+        }
+
+        // Without the channel ID we can't tell devices apart
+        let extended = ExtendedData::parse(data)?;
+        let frame = ScanFrame {
+            device_number: extended.device_number?,
+            device_type: extended.device_type?,
+            transmission_type: extended.transmission_type?,
+            page: data[1..9].try_into().ok()?,
+        };
+
+        self.record(&frame, extended.rssi, now);
+        Some(frame)
+    }
+
+    /// Drop devices not heard from for `max_age`; returns them
+    pub fn expire(&mut self, now: Instant, max_age: Duration) -> Vec<ScannedDevice> {
+        let (stale, live) = std::mem::take(&mut self.devices)
+            .into_iter()
+            .partition(|d| now.duration_since(d.last_seen) >= max_age);
+        self.devices = live;
+        stale
+    }
+
+    /// Devices still in range, strongest signal first per device type
+    pub fn table(&self) -> Vec<ScannedDevice> {
+        let mut devices = self.devices.clone();
+        devices.sort_by_key(|d| (d.device_type, std::cmp::Reverse(d.rssi)));
+        devices
+    }
+
+    /// Scan for `duration` on an open transport
+    ///
+    /// `on_update` gets the live table every `SCAN_UPDATE_INTERVAL`, with
+    /// devices gone quiet dropped. Returns the final table.
+    pub fn run(
+        mut self,
+        ant: &mut dyn AntTransport,
+        duration: Duration,
+        mut on_update: impl FnMut(&[ScannedDevice]),
+    ) -> Result<Vec<ScannedDevice>, String> {
+        for msg in self.start_sequence() {
+            ant.write(&msg)?;
+            thread::sleep(CONFIG_MESSAGE_DELAY);
+        }
+
+        let deadline = Instant::now() + duration;
+        let mut next_update = Instant::now() + SCAN_UPDATE_INTERVAL;
+        while Instant::now() < deadline && self.rejected.is_none() {
+            for (msg_id, _, data) in ant.read_messages()? {
+                self.handle_message(msg_id, &data, Instant::now());
+            }
+
+            let now = Instant::now();
+            if now >= next_update {
+                self.expire(now, SCAN_DEVICE_TIMEOUT);
+                on_update(&self.table());
+                next_update = now + SCAN_UPDATE_INTERVAL;
+            }
+        }
+
+        for msg in self.stop_sequence() {
+            ant.write(&msg)?;
+        }
+
+        if let Some(code) = self.rejected {
+            return Err(format!(
+                "ANT+ stick refused continuous scan mode (code 0x{:02X})",
+                code
+            ));
+        }
+
+        self.expire(Instant::now(), SCAN_DEVICE_TIMEOUT);
+        Ok(self.table())
+    }
+
+    fn record(&mut self, frame: &ScanFrame, rssi: Option<i8>, now: Instant) {
+        let index = match self.devices.iter().position(|d| {
+            d.device_number == frame.device_number && d.device_type == frame.device_type
+        }) {
+            Some(index) => index,
+            None => {
+                self.devices.push(ScannedDevice {
+                    device_number: frame.device_number,
+                    device_type: frame.device_type,
+                    transmission_type: frame.transmission_type,
+                    rssi: None,
+                    message_count: 0,
+                    last_page: frame.page,
+                    last_seen: now,
+                });
+                self.devices.len() - 1
+            }
+        };
+
+        let device = &mut self.devices[index];
+        device.message_count += 1;
+        device.last_page = frame.page;
+        device.last_seen = now;
+        if rssi.is_some() {
+            device.rssi = rssi;
+        }
+    }
+}
+
+impl Default for ScanSession {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ant::channel::{
+        FEC_DEVICE_TYPE, HRM_DEVICE_TYPE, MESG_RESPONSE_EVENT, POWER_DEVICE_TYPE,
+    };
+    use crate::ant::sim::{SimDevice, SimulatedStick};
+
+    fn broadcast(page: [u8; 8], device_number: u16, device_type: u8, rssi: i8) -> Vec<u8> {
+        let mut data = vec![0];
+        data.extend_from_slice(&page);
+        data.push(LIB_CONFIG_CHANNEL_ID | LIB_CONFIG_RSSI);
+        data.extend_from_slice(&device_number.to_le_bytes());
+        data.extend_from_slice(&[device_type, 0x05, 0x20, rssi as u8, 0x00]);
+        data
+    }
+
+    #[test]
+    fn test_frames_are_demultiplexed_by_device() {
+        let mut scan = ScanSession::new();
+        let now = Instant::now();
+        let page_16 = [0x10, 25, 0, 0, 0, 0, 0xFF, 0x30];
+        let page_hr = [0x00, 0xFF, 0xFF, 0xFF, 0xFF, 0x00, 0x00, 140];
+
+        let frame = scan
+            .handle_message(
+                MESG_BROADCAST_DATA,
+                &broadcast(page_16, 111, FEC_DEVICE_TYPE, -50),
+                now,
+            )
+            .unwrap();
+        assert_eq!(frame.device_number, 111);
+        assert_eq!(frame.page, page_16);
+
+        scan.handle_message(
+            MESG_BROADCAST_DATA,
+            &broadcast(page_hr, 54321, HRM_DEVICE_TYPE, -60),
+            now,
+        );
+        scan.handle_message(
+            MESG_BROADCAST_DATA,
+            &broadcast(page_16, 111, FEC_DEVICE_TYPE, -45),
+            now + Duration::from_secs(1),
+        );
+
+        assert_eq!(scan.devices().len(), 2);
+        let trainer = scan.device(FEC_DEVICE_TYPE, 111).unwrap();
+        assert_eq!(trainer.message_count, 2);
+        assert_eq!(trainer.rssi, Some(-45));
+        assert_eq!(trainer.last_seen, now + Duration::from_secs(1));
+        assert_eq!(
+            scan.device(HRM_DEVICE_TYPE, 54321).unwrap().last_page,
+            page_hr
+        );
+
+        // Without extended data the sender is unknown
+        let plain = &broadcast(page_16, 111, FEC_DEVICE_TYPE, -50)[..9];
+        assert_eq!(scan.handle_message(MESG_BROADCAST_DATA, plain, now), None);
+    }
+
+    #[test]
+    fn test_expire_drops_devices_gone_quiet() {
+        let mut scan = ScanSession::new();
+        let now = Instant::now();
+        let page = [0x10, 25, 0, 0, 0, 0, 0xFF, 0x30];
+
+        scan.handle_message(
+            MESG_BROADCAST_DATA,
+            &broadcast(page, 1, FEC_DEVICE_TYPE, -50),
+            now,
+        );
+        scan.handle_message(
+            MESG_BROADCAST_DATA,
+            &broadcast(page, 2, FEC_DEVICE_TYPE, -50),
+            now + Duration::from_secs(5),
+        );
+
+        let stale = scan.expire(now + Duration::from_secs(6), Duration::from_secs(3));
+        assert_eq!(stale.len(), 1);
+        assert_eq!(stale[0].device_number, 1);
+        assert_eq!(scan.devices()[0].device_number, 2);
+    }
+
+    #[test]
+    fn test_run_reports_the_live_table() {
+        let mut stick = SimulatedStick::new().with_devices(vec![SimDevice::new(
+            HRM_DEVICE_TYPE,
+            54321,
+            0x01,
+            -65,
+        )]);
+        stick.open().unwrap();
+
+        let mut updates = Vec::new();
+        ScanSession::new()
+            .run(&mut stick, Duration::from_millis(1500), |devices| {
+                updates.push(devices.to_vec())
+            })
+            .unwrap();
+
+        assert_eq!(updates.len(), 1);
+        assert_eq!(updates[0][0].device_number, 54321);
+    }
+
+    #[test]
+    fn test_last_seen_is_sent_as_age() {
+        let mut scan = ScanSession::new();
+        let page = [0x10, 25, 0, 0, 0, 0, 0xFF, 0x30];
+        scan.handle_message(
+            MESG_BROADCAST_DATA,
+            &broadcast(page, 1, FEC_DEVICE_TYPE, -50),
+            Instant::now() - Duration::from_secs(2),
+        );
+
+        let json = serde_json::to_value(&scan.devices()[0]).unwrap();
+        let age = json["age_secs"].as_f64().unwrap();
+        assert!((2.0..3.0).contains(&age));
+    }
+
+    #[test]
+    fn test_refused_scan_mode_is_recorded() {
+        let mut scan = ScanSession::new();
+        let refused = [0, MESG_OPEN_RX_SCAN_MODE, 0x15]; // CHANNEL_IN_WRONG_STATE
+        scan.handle_message(MESG_RESPONSE_EVENT, &refused, Instant::now());
+        assert_eq!(scan.rejected, Some(0x15));
+    }
+
+    #[test]
+    fn test_run_hears_every_simulated_device() {
+        let mut stick = SimulatedStick::new().with_devices(vec![
+            SimDevice::new(FEC_DEVICE_TYPE, 111, 0x05, -70),
+            SimDevice::new(FEC_DEVICE_TYPE, 222, 0x05, -50),
+            SimDevice::new(HRM_DEVICE_TYPE, 54321, 0x01, -65),
+            SimDevice::new(POWER_DEVICE_TYPE, 777, 0x05, -60),
+        ]);
+        stick.open().unwrap();
+
+        let devices = ScanSession::new()
+            .run(&mut stick, Duration::from_millis(200), |_| {})
+            .unwrap();
+
+        let heard: Vec<(u8, u16)> = devices
+            .iter()
+            .map(|d| (d.device_type, d.device_number))
+            .collect();
+        assert_eq!(
+            heard,
+            vec![
+                (POWER_DEVICE_TYPE, 777),
+                (FEC_DEVICE_TYPE, 222),
+                (FEC_DEVICE_TYPE, 111),
+                (HRM_DEVICE_TYPE, 54321),
+            ]
+        );
+        assert!(devices.iter().all(|d| d.message_count > 0));
+        assert!(!stick.is_channel_open(0));
+    }
+}
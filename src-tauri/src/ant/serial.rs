@@ -0,0 +1,177 @@
+// ANT+ Serial Transport
+//
+// USB1 sticks bound to the host's USB-serial driver and UART ANT modules
+// (nRF52 with the ANT SoftDevice, D52/AP2 modules) show up as a tty rather
+// than a bulk USB interface. The byte stream is the same framed ANT
+// messages, so only the link differs from `AntUsb`.
+
+use super::framer::AntFramer;
+use super::transport::AntTransport;
+use serialport::SerialPort;
+use std::io::{ErrorKind, Read, Write};
+use std::time::Duration;
+
+// USB1 sticks run at 115200; UART modules are strapped to a fixed rate
+pub const DEFAULT_BAUD_RATE: u32 = 115_200;
+
+// Short timeout for non-blocking reads, as for the USB stick
+const SERIAL_READ_TIMEOUT: Duration = Duration::from_millis(50);
+
+pub struct AntSerial {
+    path: Option<String>,
+    baud_rate: u32,
+    port: Option<Box<dyn SerialPort>>,
+    framer: AntFramer,
+}
+
+impl AntSerial {
+    pub fn new(path: impl Into<String>, baud_rate: u32) -> Self {
+        Self {
+            path: Some(path.into()),
+            baud_rate,
+            port: None,
+            framer: AntFramer::new(),
+        }
+    }
+
+    /// Use a port that is already open, e.g. one end of a pseudo-terminal
+    ///
+    /// The transport can't reopen it once closed.
+    pub fn from_port(port: Box<dyn SerialPort>) -> Self {
+        Self {
+            path: None,
+            baud_rate: DEFAULT_BAUD_RATE,
+            port: Some(port),
+            framer: AntFramer::new(),
+        }
+    }
+
+    /// Serial ports on this machine, for picking the one the stick is on
+    pub fn list_ports() -> Result<Vec<String>, String> {
+        let ports = serialport::available_ports()
+            .map_err(|e| format!("Failed to list serial ports: {}", e))?;
+        Ok(ports.into_iter().map(|port| port.port_name).collect())
+    }
+
+    fn port(&mut self) -> Result<&mut Box<dyn SerialPort>, String> {
+        self.port
+            .as_mut()
+            .ok_or_else(|| "Serial port not open. Call open() first.".to_string())
+    }
+}
+
+impl AntTransport for AntSerial {
+    fn open(&mut self) -> Result<(), String> {
+        if self.port.is_none() {
+            let path = self.path.as_deref().ok_or("No serial port configured")?;
+            let port = serialport::new(path, self.baud_rate)
+                .timeout(SERIAL_READ_TIMEOUT)
+                .open()
+                .map_err(|e| format!("Failed to open {}: {}", path, e))?;
+            println!(
+                "ANT+ serial port {} opened at {} baud",
+                path, self.baud_rate
+            );
+            self.port = Some(port);
+        }
+
+        // Drop anything left over from before the open
+        let port = self.port()?;
+        let _ = port.clear(serialport::ClearBuffer::All);
+        self.framer.clear();
+        Ok(())
+    }
+
+    fn close(&mut self) {
+        if self.path.is_none() {
+            return; // Borrowed port: keep it so the transport stays usable
+        }
+        if self.port.take().is_some() {
+            println!("ANT+ serial port closed");
+        }
+    }
+
+    fn write(&mut self, data: &[u8]) -> Result<usize, String> {
+        let port = self.port()?;
+        port.write_all(data)
+            .and_then(|_| port.flush())
+            .map_err(|e| format!("Serial write failed: {}", e))?;
+        Ok(data.len())
+    }
+
+    fn read(&mut self, buffer: &mut [u8]) -> Result<usize, String> {
+        match self.port()?.read(buffer) {
+            Ok(bytes) => Ok(bytes),
+            Err(e) if e.kind() == ErrorKind::TimedOut => Ok(0), // No data available
+            Err(e) => Err(format!("Serial read failed: {}", e)),
+        }
+    }
+
+    fn framer(&mut self) -> &mut AntFramer {
+        &mut self.framer
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use crate::ant::channel::{AntChannel, MESG_BROADCAST_DATA, MESG_SYSTEM_RESET};
+    use serialport::TTYPort;
+
+    fn pty_transport() -> (AntSerial, TTYPort) {
+        let (mut host, stick) = TTYPort::pair().expect("pseudo-terminal");
+        host.set_timeout(Duration::from_millis(500)).unwrap();
+
+        let mut ant = AntSerial::from_port(Box::new(stick));
+        ant.open().unwrap();
+        (ant, host)
+    }
+
+    #[test]
+    fn test_writes_framed_messages_to_the_tty() {
+        let (mut ant, mut stick) = pty_transport();
+
+        let reset = AntChannel::reset_system();
+        ant.write(&reset).unwrap();
+
+        let mut received = vec![0u8; reset.len()];
+        stick.read_exact(&mut received).unwrap();
+        assert_eq!(received, reset);
+        assert_eq!(received[2], MESG_SYSTEM_RESET);
+    }
+
+    #[test]
+    fn test_reads_messages_split_across_reads() {
+        let (mut ant, mut stick) = pty_transport();
+
+        let page = [0x10, 25, 0, 0, 0, 0, 0xFF, 0x30];
+        let mut data = vec![0];
+        data.extend_from_slice(&page);
+        let message = AntChannel::build_message(MESG_BROADCAST_DATA, &data);
+
+        // Half a frame first: nothing complete yet
+        let (first, rest) = message.split_at(5);
+        stick.write_all(first).unwrap();
+        let mut messages = Vec::new();
+        for _ in 0..10 {
+            messages.extend(ant.read_messages().unwrap());
+        }
+        assert!(messages.is_empty());
+
+        stick.write_all(rest).unwrap();
+        for _ in 0..10 {
+            messages.extend(ant.read_messages().unwrap());
+            if !messages.is_empty() {
+                break;
+            }
+        }
+        assert_eq!(messages, vec![(MESG_BROADCAST_DATA, 0, data)]);
+    }
+
+    #[test]
+    fn test_missing_port_cannot_open() {
+        let mut ant = AntSerial::new("/dev/notch-rider-missing-tty", DEFAULT_BAUD_RATE);
+        assert!(ant.open().is_err());
+        assert!(ant.write(&AntChannel::reset_system()).is_err());
+    }
+}
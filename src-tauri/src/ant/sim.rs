@@ -0,0 +1,844 @@
+// Simulated ANT+ USB Stick
+//
+// An in-process stand-in for a Garmin/Dynastream stick. It answers
+// configuration messages with RESPONSE_NO_ERROR, confirms acknowledged
+// and burst transfers, and broadcasts scripted FE-C and HRM pages from
+// simulated devices on every open channel, so the whole stack can be
+// tested without hardware.
+
+use super::channel::{
+    AntChannel, EVENT_CHANNEL_CLOSED, EVENT_RX_SEARCH_TIMEOUT, EVENT_TRANSFER_TX_COMPLETED,
+    EVENT_TRANSFER_TX_FAILED, FEC_DEVICE_TYPE, HRM_DEVICE_TYPE, LIB_CONFIG_CHANNEL_ID,
+    LIB_CONFIG_RSSI, LIB_CONFIG_RX_TIMESTAMP, MESG_ACKNOWLEDGED_DATA, MESG_ASSIGN_CHANNEL,
+    MESG_BROADCAST_DATA, MESG_BURST_DATA, MESG_CAPABILITIES, MESG_CHANNEL_ID, MESG_CHANNEL_STATUS,
+    MESG_CLOSE_CHANNEL, MESG_EVENT_ID, MESG_ID_LIST_ADD, MESG_ID_LIST_CONFIG, MESG_LIB_CONFIG,
+    MESG_OPEN_CHANNEL, MESG_OPEN_RX_SCAN_MODE, MESG_REQUEST_MESSAGE, MESG_RESPONSE_EVENT,
+    MESG_STARTUP, MESG_SYSTEM_RESET, POWER_DEVICE_TYPE, RESPONSE_NO_ERROR, WILDCARD_DEVICE_TYPE,
+};
+use super::fec::{
+    CALIBRATION_CONDITION_OK, CALIBRATION_SPIN_DOWN, CALIBRATION_ZERO_OFFSET, COMMAND_STATUS_PASS,
+    COMMAND_STATUS_UNINITIALIZED, PAGE_CALIBRATION_PROGRESS, PAGE_CALIBRATION_REQUEST,
+    PAGE_COMMAND_STATUS, PAGE_REQUEST_DATA,
+};
+use super::framer::AntFramer;
+use super::transport::AntTransport;
+use std::collections::{HashMap, VecDeque};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+// Burst packets: bit 7 of the channel byte marks the last packet
+const BURST_LAST_PACKET: u8 = 0x80;
+const BURST_CHANNEL_MASK: u8 = 0x1F;
+
+// Channels and networks reported in the capabilities reply (as an ANT USB-m)
+const SIM_MAX_CHANNELS: u8 = 8;
+const SIM_MAX_NETWORKS: u8 = 8;
+const SIM_ADVANCED_OPTIONS: u8 = 0xBA; // Networks, serial number, search list, ...
+const SIM_ADVANCED_OPTIONS_2: u8 = 0x36; // Extended messages, scan mode, ...
+
+// RSSI measurement type in flagged extended data
+const RSSI_MEASUREMENT_DBM: u8 = 0x20;
+
+// Stick clock ticks (1/32768 s) between simulated broadcasts: the 4 Hz
+// FE-C channel period
+const BROADCAST_TICKS: u16 = 8192;
+
+/// Default FE-C script: Page 16 at 30 km/h, Page 25 at 200W / 90 RPM,
+/// Page 80 from manufacturer 32 (Wahoo) model 2620
+const DEFAULT_FEC_PAGES: [[u8; 8]; 3] = [
+    [0x10, 25, 0, 0, 0x8D, 0x20, 0xFF, 0x30],
+    [0x19, 1, 90, 0, 0, 0xC8, 0x00, 0x00],
+    [0x50, 0xFF, 0xFF, 0x01, 0x20, 0x00, 0x3C, 0x0A],
+];
+
+/// FE-C pages only sent on request (Page 70): Page 54 with 2000 N max
+/// resistance and every control mode, Page 81 with software 2.5 and
+/// serial 1234567
+const REQUESTED_FEC_PAGES: [[u8; 8]; 2] = [
+    [0x36, 0xFF, 0xFF, 0xFF, 0xFF, 0xD0, 0x07, 0x07],
+    [0x51, 0xFF, 0xFF, 25, 0x87, 0xD6, 0x12, 0x00],
+];
+
+/// Default HRM script: Page 0 at 140 BPM, Page 2 from manufacturer 1 (Garmin)
+const DEFAULT_HRM_PAGES: [[u8; 8]; 2] = [
+    [0x00, 0xFF, 0xFF, 0xFF, 0xFF, 0x00, 0x00, 140],
+    [0x02, 0x01, 0x34, 0x12, 0x00, 0x00, 0x00, 140],
+];
+
+/// Power meter script (scan mode only): Page 16 at 200W / 90 RPM
+const DEFAULT_POWER_PAGES: [[u8; 8]; 1] = [[0x10, 1, 0xFF, 90, 0xC8, 0x00, 0xC8, 0x00]];
+
+// Calibration answers: 20.5°C, 30 km/h target, 5s expected / 4.8s measured
+// spin-down, zero offset 1024
+const SIM_TEMPERATURE: u8 = 91;
+const SIM_CALIBRATION_SPEED_MMS: u16 = 8333;
+const SIM_TARGET_SPIN_DOWN_MS: u16 = 5000;
+const SIM_SPIN_DOWN_MS: u16 = 4800;
+const SIM_ZERO_OFFSET: u16 = 1024;
+
+/// A sensor within range of the simulated stick
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SimDevice {
+    pub device_type: u8,
+    pub device_number: u16,
+    pub transmission_type: u8,
+    /// Signal strength reported in extended data (dBm)
+    pub rssi: i8,
+}
+
+impl SimDevice {
+    pub fn new(device_type: u8, device_number: u16, transmission_type: u8, rssi: i8) -> Self {
+        Self {
+            device_type,
+            device_number,
+            transmission_type,
+            rssi,
+        }
+    }
+
+    /// Whether a channel ID (0 = wildcard) matches this device
+    fn matches(&self, device_type: u8, device_number: u16, transmission_type: u8) -> bool {
+        (device_type == WILDCARD_DEVICE_TYPE || device_type == self.device_type)
+            && (device_number == 0 || device_number == self.device_number)
+            && (transmission_type == 0 || transmission_type == self.transmission_type)
+    }
+}
+
+#[derive(Default)]
+struct SimChannel {
+    device_type: u8,
+    device_number: u16,
+    transmission_type: u8,
+    open: bool,
+    next_page: usize,
+    /// Inclusion/exclusion list entries: (device number, type, transmission type)
+    id_list: HashMap<u8, (u16, u8, u8)>,
+    exclusion_size: u8,
+    /// Index of the device the channel found
+    paired: Option<usize>,
+}
+
+/// In-process simulated ANT stick
+pub struct SimulatedStick {
+    open: bool,
+    /// Cleared to simulate the stick being unplugged
+    plugged: Arc<AtomicBool>,
+    channels: HashMap<u8, SimChannel>,
+    devices: Vec<SimDevice>,
+    /// Lib Config flags for extended data
+    lib_config: u8,
+    /// Stick clock reported as the RX timestamp
+    rx_clock: u16,
+    /// Channel 0 is in continuous scan mode
+    scanning: bool,
+    /// Broadcast rounds sent while scanning, for cycling each device's pages
+    scan_round: usize,
+    max_channels: u8,
+    fec_pages: Vec<[u8; 8]>,
+    hrm_pages: Vec<[u8; 8]>,
+    /// Last FE-C control page received, echoed in Page 71
+    last_command: Option<[u8; 8]>,
+    command_sequence: u8,
+    /// Remaining transfers to answer with EVENT_TRANSFER_TX_FAILED
+    failing_transfers: u32,
+    /// Pages requested via Page 70, sent in place of the next broadcast
+    requested_pages: VecDeque<(u8, [u8; 8])>,
+    /// Every message written by the host, for test assertions
+    sent: Vec<(u8, Vec<u8>)>,
+    outbox: VecDeque<u8>,
+    host_framer: AntFramer,
+    framer: AntFramer,
+}
+
+impl SimulatedStick {
+    pub fn new() -> Self {
+        Self {
+            open: false,
+            plugged: Arc::new(AtomicBool::new(true)),
+            channels: HashMap::new(),
+            devices: vec![
+                SimDevice::new(FEC_DEVICE_TYPE, 12345, 0x05, -55),
+                SimDevice::new(HRM_DEVICE_TYPE, 54321, 0x01, -60),
+            ],
+            lib_config: 0,
+            rx_clock: 0,
+            scanning: false,
+            scan_round: 0,
+            max_channels: SIM_MAX_CHANNELS,
+            fec_pages: DEFAULT_FEC_PAGES.to_vec(),
+            hrm_pages: DEFAULT_HRM_PAGES.to_vec(),
+            last_command: None,
+            command_sequence: 0,
+            failing_transfers: 0,
+            requested_pages: VecDeque::new(),
+            sent: Vec::new(),
+            outbox: VecDeque::new(),
+            host_framer: AntFramer::new(),
+            framer: AntFramer::new(),
+        }
+    }
+
+    /// Switch shared with tests: store false to unplug the stick, true to
+    /// plug it back in
+    pub fn plug_switch(&self) -> Arc<AtomicBool> {
+        Arc::clone(&self.plugged)
+    }
+
+    fn check_plugged(&self) -> Result<(), String> {
+        if self.plugged.load(Ordering::SeqCst) {
+            Ok(())
+        } else {
+            Err("ANT+ stick disconnected".to_string())
+        }
+    }
+
+    /// Replace the devices in range (by default one trainer and one strap)
+    pub fn with_devices(mut self, devices: Vec<SimDevice>) -> Self {
+        self.devices = devices;
+        self
+    }
+
+    /// Report a different channel count in the capabilities reply
+    pub fn with_max_channels(mut self, max_channels: u8) -> Self {
+        self.max_channels = max_channels;
+        self
+    }
+
+    /// Replace the pages broadcast on FE-C channels (cycled in order)
+    pub fn with_fec_pages(mut self, pages: Vec<[u8; 8]>) -> Self {
+        self.fec_pages = pages;
+        self
+    }
+
+    /// Replace the pages broadcast on HRM channels (cycled in order)
+    pub fn with_hrm_pages(mut self, pages: Vec<[u8; 8]>) -> Self {
+        self.hrm_pages = pages;
+        self
+    }
+
+    /// Answer the next `count` acknowledged/burst transfers with a failure
+    pub fn fail_transfers(&mut self, count: u32) {
+        self.failing_transfers = count;
+    }
+
+    /// Messages written by the host as (message_id, data)
+    pub fn sent_messages(&self) -> &[(u8, Vec<u8>)] {
+        &self.sent
+    }
+
+    /// Whether a channel has been opened by the host
+    pub fn is_channel_open(&self, channel: u8) -> bool {
+        self.channels.get(&channel).is_some_and(|ch| ch.open)
+    }
+
+    fn queue(&mut self, msg_id: u8, data: &[u8]) {
+        self.outbox.extend(AntChannel::build_message(msg_id, data));
+    }
+
+    fn queue_response(&mut self, channel: u8, msg_id: u8) {
+        self.queue(MESG_RESPONSE_EVENT, &[channel, msg_id, RESPONSE_NO_ERROR]);
+    }
+
+    fn queue_event(&mut self, channel: u8, code: u8) {
+        self.queue(MESG_RESPONSE_EVENT, &[channel, MESG_EVENT_ID, code]);
+    }
+
+    /// Broadcast a page from the channel's device, with extended data if enabled
+    fn queue_page(&mut self, channel: u8, page: &[u8; 8]) {
+        let device = self
+            .channels
+            .get(&channel)
+            .and_then(|ch| ch.paired)
+            .map(|index| self.devices[index]);
+        self.queue_device_page(channel, device, page);
+    }
+
+    fn queue_device_page(&mut self, channel: u8, device: Option<SimDevice>, page: &[u8; 8]) {
+        let mut data = vec![channel];
+        data.extend_from_slice(page);
+
+        let flags =
+            self.lib_config & (LIB_CONFIG_CHANNEL_ID | LIB_CONFIG_RSSI | LIB_CONFIG_RX_TIMESTAMP);
+        self.rx_clock = self.rx_clock.wrapping_add(BROADCAST_TICKS);
+
+        if let (Some(device), true) = (device, flags != 0) {
+            data.push(flags);
+            if flags & LIB_CONFIG_CHANNEL_ID != 0 {
+                data.extend_from_slice(&device.device_number.to_le_bytes());
+                data.extend_from_slice(&[device.device_type, device.transmission_type]);
+            }
+            if flags & LIB_CONFIG_RSSI != 0 {
+                data.extend_from_slice(&[RSSI_MEASUREMENT_DBM, device.rssi as u8, 0x00]);
+            }
+            if flags & LIB_CONFIG_RX_TIMESTAMP != 0 {
+                data.extend_from_slice(&self.rx_clock.to_le_bytes());
+            }
+        }
+
+        self.queue(MESG_BROADCAST_DATA, &data);
+    }
+
+    /// Channel opened: pair with the first matching device that isn't
+    /// excluded, or time out the search and close the channel
+    fn search(&mut self, channel: u8) {
+        let Some(ch) = self.channels.get_mut(&channel) else {
+            return;
+        };
+
+        let excluded: Vec<(u16, u8, u8)> = (0..ch.exclusion_size)
+            .filter_map(|index| ch.id_list.get(&index).copied())
+            .collect();
+
+        let found = self.devices.iter().position(|d| {
+            d.matches(ch.device_type, ch.device_number, ch.transmission_type)
+                && !excluded
+                    .iter()
+                    .any(|&(number, _, _)| number == d.device_number)
+        });
+
+        ch.paired = found;
+        if found.is_none() {
+            ch.open = false;
+            self.queue_event(channel, EVENT_RX_SEARCH_TIMEOUT);
+            self.queue_event(channel, EVENT_CHANNEL_CLOSED);
+        }
+    }
+
+    /// Handle one complete message written by the host
+    fn handle_host_message(&mut self, msg_id: u8, channel: u8, data: &[u8]) {
+        self.sent.push((msg_id, data.to_vec()));
+
+        match msg_id {
+            MESG_SYSTEM_RESET => {
+                self.channels.clear();
+                self.lib_config = 0;
+                self.scanning = false;
+                self.queue(MESG_STARTUP, &[0x00]);
+            }
+            MESG_ASSIGN_CHANNEL => {
+                self.channels.insert(channel, SimChannel::default());
+                self.queue_response(channel, msg_id);
+            }
+            MESG_CHANNEL_ID => {
+                if let (Some(ch), true) = (self.channels.get_mut(&channel), data.len() >= 5) {
+                    ch.device_number = u16::from_le_bytes([data[1], data[2]]);
+                    ch.device_type = data[3];
+                    ch.transmission_type = data[4];
+                }
+                self.queue_response(channel, msg_id);
+            }
+            MESG_ID_LIST_ADD => {
+                if let (Some(ch), true) = (self.channels.get_mut(&channel), data.len() >= 6) {
+                    let id = (u16::from_le_bytes([data[1], data[2]]), data[3], data[4]);
+                    ch.id_list.insert(data[5], id);
+                }
+                self.queue_response(channel, msg_id);
+            }
+            MESG_ID_LIST_CONFIG => {
+                // Only exclusion lists are simulated
+                if let (Some(ch), true) = (self.channels.get_mut(&channel), data.len() >= 3) {
+                    ch.exclusion_size = if data[2] == 0x01 { data[1] } else { 0 };
+                }
+                self.queue_response(channel, msg_id);
+            }
+            MESG_LIB_CONFIG => {
+                self.lib_config = data.get(1).copied().unwrap_or(0);
+                self.queue_response(channel, msg_id);
+            }
+            MESG_OPEN_CHANNEL => {
+                if let Some(ch) = self.channels.get_mut(&channel) {
+                    ch.open = true;
+                }
+                self.queue_response(channel, msg_id);
+                self.search(channel);
+            }
+            MESG_OPEN_RX_SCAN_MODE => {
+                // Scan mode uses channel 0's configuration
+                if let Some(ch) = self.channels.get_mut(&0) {
+                    ch.open = true;
+                    self.scanning = true;
+                }
+                self.queue_response(0, msg_id);
+            }
+            MESG_CLOSE_CHANNEL => {
+                if let Some(ch) = self.channels.get_mut(&channel) {
+                    ch.open = false;
+                    ch.paired = None;
+                }
+                if channel == 0 {
+                    self.scanning = false;
+                }
+                self.queue_response(channel, msg_id);
+                self.queue_event(channel, EVENT_CHANNEL_CLOSED);
+            }
+            MESG_ACKNOWLEDGED_DATA => self.handle_transfer(channel, data.get(1..9)),
+            MESG_BURST_DATA => {
+                let channel = channel & BURST_CHANNEL_MASK;
+                if data[0] & BURST_LAST_PACKET != 0 {
+                    self.handle_transfer(channel, None);
+                }
+            }
+            MESG_REQUEST_MESSAGE => self.handle_request(channel, data.get(1).copied()),
+            MESG_BROADCAST_DATA => {}
+            _ => self.queue_response(channel, msg_id),
+        }
+    }
+
+    /// Answer a request message for capabilities, channel status or channel ID
+    fn handle_request(&mut self, channel: u8, requested: Option<u8>) {
+        match requested {
+            Some(MESG_CAPABILITIES) => {
+                // [max channels][max networks][standard][advanced][advanced 2][reserved]
+                let capabilities = [
+                    self.max_channels,
+                    SIM_MAX_NETWORKS,
+                    0x00,
+                    SIM_ADVANCED_OPTIONS,
+                    SIM_ADVANCED_OPTIONS_2,
+                    0x00,
+                ];
+                self.queue(MESG_CAPABILITIES, &capabilities);
+            }
+            Some(MESG_CHANNEL_STATUS) => {
+                // Slave channel on network 0: only the state bits are set
+                let state = match self.channels.get(&channel) {
+                    None => 0,
+                    Some(ch) if !ch.open => 1,
+                    Some(ch) if ch.paired.is_none() => 2,
+                    Some(_) => 3,
+                };
+                self.queue(MESG_CHANNEL_STATUS, &[channel, state]);
+            }
+            Some(MESG_CHANNEL_ID) => {
+                let Some(ch) = self.channels.get(&channel) else {
+                    return;
+                };
+                // The paired device's ID once tracking, else the configured one
+                let (number, device_type, transmission_type) =
+                    match ch.paired.and_then(|index| self.devices.get(index)) {
+                        Some(d) => (d.device_number, d.device_type, d.transmission_type),
+                        None => (ch.device_number, ch.device_type, ch.transmission_type),
+                    };
+                let [lo, hi] = number.to_le_bytes();
+                self.queue(
+                    MESG_CHANNEL_ID,
+                    &[channel, lo, hi, device_type, transmission_type],
+                );
+            }
+            _ => {}
+        }
+    }
+
+    /// Confirm (or fail) a transfer and act on FE-C control pages
+    fn handle_transfer(&mut self, channel: u8, page: Option<&[u8]>) {
+        if self.failing_transfers > 0 {
+            self.failing_transfers -= 1;
+            self.queue_event(channel, EVENT_TRANSFER_TX_FAILED);
+            return;
+        }
+
+        self.queue_event(channel, EVENT_TRANSFER_TX_COMPLETED);
+
+        let Some(page) = page else {
+            return;
+        };
+        let mut payload = [0u8; 8];
+        payload.copy_from_slice(page);
+
+        if payload[0] == PAGE_REQUEST_DATA {
+            self.answer_page_request(channel, payload[6]);
+        } else if payload[0] == PAGE_CALIBRATION_REQUEST {
+            self.answer_calibration(channel, payload[1]);
+        } else {
+            self.last_command = Some(payload);
+            self.command_sequence = self.command_sequence.wrapping_add(1);
+        }
+    }
+
+    /// Page 70: transmit the requested page once, after the transfer event
+    fn answer_page_request(&mut self, channel: u8, page_number: u8) {
+        if page_number == PAGE_COMMAND_STATUS {
+            let status = match self.last_command {
+                Some(cmd) => [
+                    PAGE_COMMAND_STATUS,
+                    cmd[0],
+                    self.command_sequence,
+                    COMMAND_STATUS_PASS,
+                    cmd[4],
+                    cmd[5],
+                    cmd[6],
+                    cmd[7],
+                ],
+                None => [
+                    PAGE_COMMAND_STATUS,
+                    0xFF,
+                    0xFF,
+                    COMMAND_STATUS_UNINITIALIZED,
+                    0xFF,
+                    0xFF,
+                    0xFF,
+                    0xFF,
+                ],
+            };
+            self.requested_pages.push_back((channel, status));
+            return;
+        }
+
+        let page = self
+            .fec_pages
+            .iter()
+            .chain(REQUESTED_FEC_PAGES.iter())
+            .find(|p| p[0] == page_number)
+            .copied();
+        if let Some(page) = page {
+            self.requested_pages.push_back((channel, page));
+        }
+    }
+
+    /// Page 1 request: one Page 2 with every condition met, then a
+    /// successful Page 1 response for the requested modes
+    fn answer_calibration(&mut self, channel: u8, mode: u8) {
+        let conditions = (CALIBRATION_CONDITION_OK << 6) | (CALIBRATION_CONDITION_OK << 4);
+        let speed = SIM_CALIBRATION_SPEED_MMS.to_le_bytes();
+        let target_time = SIM_TARGET_SPIN_DOWN_MS.to_le_bytes();
+        let progress = [
+            PAGE_CALIBRATION_PROGRESS,
+            mode,
+            conditions,
+            SIM_TEMPERATURE,
+            speed[0],
+            speed[1],
+            target_time[0],
+            target_time[1],
+        ];
+
+        let zero_offset = if mode & CALIBRATION_ZERO_OFFSET != 0 {
+            SIM_ZERO_OFFSET
+        } else {
+            0xFFFF
+        };
+        let spin_down_time = if mode & CALIBRATION_SPIN_DOWN != 0 {
+            SIM_SPIN_DOWN_MS
+        } else {
+            0xFFFF
+        };
+        let zero_offset = zero_offset.to_le_bytes();
+        let spin_down_time = spin_down_time.to_le_bytes();
+        let response = [
+            PAGE_CALIBRATION_REQUEST,
+            mode,
+            0x00,
+            SIM_TEMPERATURE,
+            zero_offset[0],
+            zero_offset[1],
+            spin_down_time[0],
+            spin_down_time[1],
+        ];
+
+        self.requested_pages.push_back((channel, progress));
+        self.requested_pages.push_back((channel, response));
+    }
+
+    fn pages_for(&self, device_type: u8) -> &[[u8; 8]] {
+        match device_type {
+            FEC_DEVICE_TYPE => &self.fec_pages,
+            HRM_DEVICE_TYPE => &self.hrm_pages,
+            POWER_DEVICE_TYPE => &DEFAULT_POWER_PAGES,
+            _ => &[],
+        }
+    }
+
+    /// Scan mode: one broadcast from every device matching channel 0's ID
+    fn queue_scan_broadcasts(&mut self) {
+        let Some(ch) = self.channels.get(&0) else {
+            return;
+        };
+        let heard: Vec<SimDevice> = self
+            .devices
+            .iter()
+            .filter(|d| d.matches(ch.device_type, ch.device_number, ch.transmission_type))
+            .copied()
+            .collect();
+
+        for device in heard {
+            let pages = self.pages_for(device.device_type);
+            if pages.is_empty() {
+                continue;
+            }
+            let page = pages[self.scan_round % pages.len()];
+            self.queue_device_page(0, Some(device), &page);
+        }
+        self.scan_round += 1;
+    }
+
+    /// One broadcast from every open channel, in channel order
+    fn queue_broadcasts(&mut self) {
+        if self.scanning {
+            self.queue_scan_broadcasts();
+            return;
+        }
+
+        let mut channel_numbers: Vec<u8> = self.channels.keys().copied().collect();
+        channel_numbers.sort_unstable();
+
+        for number in channel_numbers {
+            let Some(ch) = self.channels.get_mut(&number) else {
+                continue;
+            };
+            if !ch.open || ch.paired.is_none() {
+                continue;
+            }
+
+            let pages = match ch.device_type {
+                FEC_DEVICE_TYPE => &self.fec_pages,
+                HRM_DEVICE_TYPE => &self.hrm_pages,
+                _ => continue,
+            };
+            if pages.is_empty() {
+                continue;
+            }
+
+            let page = pages[ch.next_page % pages.len()];
+            ch.next_page += 1;
+            self.queue_page(number, &page);
+        }
+    }
+}
+
+impl Default for SimulatedStick {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl AntTransport for SimulatedStick {
+    fn open(&mut self) -> Result<(), String> {
+        if !self.plugged.load(Ordering::SeqCst) {
+            return Err("ANT+ device not found".to_string());
+        }
+
+        self.open = true;
+        self.outbox.clear();
+        self.requested_pages.clear();
+        self.host_framer.clear();
+        self.framer.clear();
+        Ok(())
+    }
+
+    fn close(&mut self) {
+        self.open = false;
+        self.channels.clear();
+    }
+
+    fn write(&mut self, data: &[u8]) -> Result<usize, String> {
+        if !self.open {
+            return Err("Device not open. Call open() first.".to_string());
+        }
+        self.check_plugged()?;
+
+        for (msg_id, channel, msg) in self.host_framer.push(data) {
+            self.handle_host_message(msg_id, channel, &msg);
+        }
+        Ok(data.len())
+    }
+
+    fn read(&mut self, buffer: &mut [u8]) -> Result<usize, String> {
+        if !self.open {
+            return Err("Device not open. Call open() first.".to_string());
+        }
+        self.check_plugged()?;
+
+        if self.outbox.is_empty() {
+            match self.requested_pages.pop_front() {
+                Some((channel, page)) => self.queue_page(channel, &page),
+                None => self.queue_broadcasts(),
+            }
+        }
+
+        // Like a USB bulk read: as many bytes as fit, frames may be split
+        let count = buffer.len().min(self.outbox.len());
+        for (slot, byte) in buffer.iter_mut().zip(self.outbox.drain(..count)) {
+            *slot = byte;
+        }
+        Ok(count)
+    }
+
+    fn framer(&mut self) -> &mut AntFramer {
+        &mut self.framer
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ant::channel::{
+        ChannelId, ChannelStatus, RadioState, StickCapabilities, MESG_CHANNEL_FREQUENCY,
+    };
+    use crate::ant::fec::FecCommand;
+
+    fn open_fec_channel(stick: &mut SimulatedStick) {
+        let channel = AntChannel::new(0);
+        for msg in channel.get_init_sequence() {
+            stick.write(&msg).unwrap();
+        }
+    }
+
+    #[test]
+    fn test_config_messages_get_no_error_response() {
+        let mut stick = SimulatedStick::new();
+        stick.open().unwrap();
+        stick
+            .write(&AntChannel::new(0).set_channel_frequency())
+            .unwrap();
+
+        let messages = stick.read_messages().unwrap();
+
+        assert_eq!(messages.len(), 1);
+        assert_eq!(messages[0].0, MESG_RESPONSE_EVENT);
+        assert_eq!(
+            messages[0].2,
+            vec![0, MESG_CHANNEL_FREQUENCY, RESPONSE_NO_ERROR]
+        );
+    }
+
+    #[test]
+    fn test_broadcasts_scripted_pages_on_open_channel() {
+        let mut stick = SimulatedStick::new();
+        stick.open().unwrap();
+        open_fec_channel(&mut stick);
+        assert!(stick.is_channel_open(0));
+
+        // Drain the config responses, then collect broadcasts
+        let mut pages = Vec::new();
+        for _ in 0..10 {
+            for (msg_id, _, data) in stick.read_messages().unwrap() {
+                if msg_id == MESG_BROADCAST_DATA {
+                    pages.push(data[1]);
+                }
+            }
+        }
+
+        assert!(pages.contains(&0x10));
+        assert!(pages.contains(&0x19));
+    }
+
+    #[test]
+    fn test_channel_pairs_only_with_its_device_number() {
+        let mut stick = SimulatedStick::new();
+        stick.open().unwrap();
+
+        let missing = AntChannel::with_device(0, 999, 0);
+        for msg in missing.get_init_sequence() {
+            stick.write(&msg).unwrap();
+        }
+        assert!(!stick.is_channel_open(0));
+
+        let paired = AntChannel::with_device(0, 12345, 0);
+        for msg in paired.get_init_sequence() {
+            stick.write(&msg).unwrap();
+        }
+        assert!(stick.is_channel_open(0));
+    }
+
+    #[test]
+    fn test_acknowledged_command_is_echoed_in_command_status() {
+        let mut stick = SimulatedStick::new();
+        stick.open().unwrap();
+        open_fec_channel(&mut stick);
+
+        let channel = AntChannel::new(0);
+        let mut ignore = |_: u8, _: u8, _: &[u8]| {};
+        let command = FecCommand::target_power(180.0);
+        stick
+            .send_acknowledged(
+                0,
+                &channel.acknowledged_data(&command.encode()),
+                &mut ignore,
+            )
+            .unwrap();
+
+        let request = FecCommand::request_page(PAGE_COMMAND_STATUS).encode();
+        stick
+            .send_acknowledged(0, &channel.acknowledged_data(&request), &mut ignore)
+            .unwrap();
+
+        // The requested page replaces the next broadcast
+        let status = stick
+            .read_messages()
+            .unwrap()
+            .into_iter()
+            .find(|(msg_id, _, data)| {
+                *msg_id == MESG_BROADCAST_DATA && data[1] == PAGE_COMMAND_STATUS
+            })
+            .expect("command status page");
+
+        assert_eq!(status.2[2], command.page_number());
+        assert_eq!(status.2[4], COMMAND_STATUS_PASS);
+    }
+
+    #[test]
+    fn test_failed_transfer_is_retried() {
+        let mut stick = SimulatedStick::new();
+        stick.open().unwrap();
+        open_fec_channel(&mut stick);
+        stick.fail_transfers(1);
+
+        let channel = AntChannel::new(0);
+        let page = FecCommand::basic_resistance(20.0).encode();
+        let mut ignore = |_: u8, _: u8, _: &[u8]| {};
+
+        stick
+            .send_acknowledged(0, &channel.acknowledged_data(&page), &mut ignore)
+            .unwrap();
+
+        let attempts = stick
+            .sent_messages()
+            .iter()
+            .filter(|(msg_id, _)| *msg_id == MESG_ACKNOWLEDGED_DATA)
+            .count();
+        assert_eq!(attempts, 2);
+    }
+
+    #[test]
+    fn test_answers_capabilities_status_and_channel_id_requests() {
+        let mut stick = SimulatedStick::new().with_max_channels(4);
+        stick.open().unwrap();
+        open_fec_channel(&mut stick);
+        let mut ignore = |_: u8, _: u8, _: &[u8]| {};
+
+        let reply = stick
+            .request_message(0, MESG_CAPABILITIES, &mut ignore)
+            .unwrap()
+            .unwrap();
+        let capabilities = StickCapabilities::parse(&reply).unwrap();
+        assert_eq!(capabilities.max_channels, 4);
+        assert!(capabilities.scan_mode());
+
+        let reply = stick
+            .request_message(0, MESG_CHANNEL_STATUS, &mut ignore)
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            ChannelStatus::parse(&reply).unwrap().state,
+            RadioState::Tracking
+        );
+
+        // Opened as a wildcard, reports the trainer it paired with
+        let reply = stick
+            .request_message(0, MESG_CHANNEL_ID, &mut ignore)
+            .unwrap()
+            .unwrap();
+        let id = ChannelId::parse(&reply).unwrap();
+        assert_eq!(id.device_number, 12345);
+        assert_eq!(id.transmission_type, 0x05);
+
+        let reply = stick
+            .request_message(3, MESG_CHANNEL_STATUS, &mut ignore)
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            ChannelStatus::parse(&reply).unwrap().state,
+            RadioState::Unassigned
+        );
+    }
+}
@@ -0,0 +1,185 @@
+// ANT+ Transport Abstraction
+//
+// Everything above the physical link (channel setup, FE-C control, the
+// reader thread) talks to the stick through this trait, so the USB stick
+// can be swapped for other links or a simulated stick in tests.
+
+use super::channel::{
+    AntChannel, ChannelEvent, EVENT_TRANSFER_TX_COMPLETED, EVENT_TRANSFER_TX_FAILED,
+    MESG_REQUEST_MESSAGE,
+};
+use super::framer::AntFramer;
+use std::time::{Duration, Instant};
+
+// Acknowledged/burst transfers: wait for EVENT_TRANSFER_TX_* per attempt
+const TRANSFER_TIMEOUT: Duration = Duration::from_millis(1000);
+const TRANSFER_MAX_ATTEMPTS: u32 = 3;
+
+// Requested messages (capabilities, channel status, ...): wait for the reply
+const REQUEST_TIMEOUT: Duration = Duration::from_millis(500);
+
+/// Byte-level link to an ANT stick
+///
+/// Implementors provide raw reads/writes of framed ANT messages and own
+/// the framer that buffers partial frames between reads. Message-level
+/// helpers (framing, acknowledged and burst transfers) are provided.
+pub trait AntTransport: Send {
+    /// Open the link and leave the stick in a clean state
+    fn open(&mut self) -> Result<(), String>;
+
+    /// Close the link; safe to call when already closed
+    fn close(&mut self);
+
+    /// Write one or more complete ANT messages
+    fn write(&mut self, data: &[u8]) -> Result<usize, String>;
+
+    /// Read whatever bytes are available; returns 0 when nothing arrived
+    /// within the transport's short read timeout
+    fn read(&mut self, buffer: &mut [u8]) -> Result<usize, String>;
+
+    /// Framer holding partial frames between reads
+    fn framer(&mut self) -> &mut AntFramer;
+
+    /// Read once and return every complete ANT message
+    ///
+    /// Each message is (message_id, channel, data). Partial frames are
+    /// buffered until the next call.
+    fn read_messages(&mut self) -> Result<Vec<(u8, u8, Vec<u8>)>, String> {
+        let mut buffer = [0u8; 64];
+        let bytes_read = self.read(&mut buffer)?;
+        Ok(self.framer().push(&buffer[..bytes_read]))
+    }
+
+    /// Framing errors seen on the stream so far
+    fn framing_errors(&mut self) -> u64 {
+        self.framer().framing_errors()
+    }
+
+    /// Ask the stick for a message (capabilities, channel status, channel
+    /// ID, ...) and wait for the reply
+    ///
+    /// Returns the reply's data, or None when the stick didn't answer in
+    /// time. `on_message` receives every other message read while waiting.
+    fn request_message(
+        &mut self,
+        channel_number: u8,
+        msg_id: u8,
+        on_message: &mut dyn FnMut(u8, u8, &[u8]),
+    ) -> Result<Option<Vec<u8>>, String> {
+        self.write(&AntChannel::build_message(
+            MESG_REQUEST_MESSAGE,
+            &[channel_number, msg_id],
+        ))?;
+
+        let deadline = Instant::now() + REQUEST_TIMEOUT;
+        while Instant::now() < deadline {
+            let mut reply = None;
+
+            // Finish the whole batch so messages after the reply aren't lost
+            for (id, channel, data) in self.read_messages()? {
+                if id == msg_id && reply.is_none() {
+                    reply = Some(data);
+                } else {
+                    on_message(id, channel, &data);
+                }
+            }
+
+            if reply.is_some() {
+                return Ok(reply);
+            }
+        }
+
+        Ok(None)
+    }
+
+    /// Send an acknowledged data message and wait until the device confirms it
+    ///
+    /// Retries when the stick reports EVENT_TRANSFER_TX_FAILED or no event
+    /// arrives within the timeout. `on_message` receives every other message
+    /// read while waiting.
+    fn send_acknowledged(
+        &mut self,
+        channel_number: u8,
+        message: &[u8],
+        on_message: &mut dyn FnMut(u8, u8, &[u8]),
+    ) -> Result<(), String> {
+        send_transfer(self, channel_number, &[message.to_vec()], on_message)
+    }
+
+    /// Send a burst transfer (from `AntChannel::burst_data`) and wait for it
+    /// to complete, retrying the whole burst on failure
+    fn send_burst(
+        &mut self,
+        channel_number: u8,
+        packets: &[Vec<u8>],
+        on_message: &mut dyn FnMut(u8, u8, &[u8]),
+    ) -> Result<(), String> {
+        send_transfer(self, channel_number, packets, on_message)
+    }
+}
+
+fn send_transfer<T: AntTransport + ?Sized>(
+    ant: &mut T,
+    channel_number: u8,
+    messages: &[Vec<u8>],
+    on_message: &mut dyn FnMut(u8, u8, &[u8]),
+) -> Result<(), String> {
+    for attempt in 1..=TRANSFER_MAX_ATTEMPTS {
+        for msg in messages {
+            ant.write(msg)?;
+        }
+
+        match await_transfer_event(ant, channel_number, on_message)? {
+            Some(true) => return Ok(()),
+            Some(false) => println!(
+                "ANT+ transfer on channel {} failed (attempt {}/{})",
+                channel_number, attempt, TRANSFER_MAX_ATTEMPTS
+            ),
+            None => println!(
+                "ANT+ transfer on channel {} timed out (attempt {}/{})",
+                channel_number, attempt, TRANSFER_MAX_ATTEMPTS
+            ),
+        }
+    }
+
+    Err(format!(
+        "ANT+ transfer on channel {} not acknowledged after {} attempts",
+        channel_number, TRANSFER_MAX_ATTEMPTS
+    ))
+}
+
+/// Wait for EVENT_TRANSFER_TX_COMPLETED (Some(true)) or
+/// EVENT_TRANSFER_TX_FAILED (Some(false)); None on timeout
+fn await_transfer_event<T: AntTransport + ?Sized>(
+    ant: &mut T,
+    channel_number: u8,
+    on_message: &mut dyn FnMut(u8, u8, &[u8]),
+) -> Result<Option<bool>, String> {
+    let deadline = Instant::now() + TRANSFER_TIMEOUT;
+
+    while Instant::now() < deadline {
+        let mut outcome = None;
+
+        // Finish the whole batch so messages after the event aren't lost
+        for (msg_id, channel, data) in ant.read_messages()? {
+            match ChannelEvent::parse(msg_id, &data) {
+                Some(ChannelEvent::Event { channel, code })
+                    if channel == channel_number && outcome.is_none() =>
+                {
+                    match code {
+                        EVENT_TRANSFER_TX_COMPLETED => outcome = Some(true),
+                        EVENT_TRANSFER_TX_FAILED => outcome = Some(false),
+                        _ => on_message(msg_id, channel, &data),
+                    }
+                }
+                _ => on_message(msg_id, channel, &data),
+            }
+        }
+
+        if outcome.is_some() {
+            return Ok(outcome);
+        }
+    }
+
+    Ok(None)
+}
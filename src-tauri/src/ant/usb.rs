@@ -1,9 +1,43 @@
-use rusb::{Context, DeviceHandle, UsbContext};
+use super::framer::AntFramer;
+use super::transport::AntTransport;
+use rusb::{Context, Device, DeviceDescriptor, DeviceHandle, UsbContext};
+use serde::{Deserialize, Serialize};
 use std::time::Duration;
 
-// ANT+ USB Stick vendor/product IDs
-const ANT_USB_VID: u16 = 0x0fcf; // Dynastream
-const ANT_USB_PID: u16 = 0x1008; // ANT USB-m Stick
+// ANT+ USB Stick vendor ID
+pub const ANT_USB_VID: u16 = 0x0fcf; // Dynastream
+
+/// A Dynastream stick product
+struct StickModel {
+    product_id: u16,
+    name: &'static str,
+    serial_bridge: bool, // USB1 sticks sit behind a CP210x USB-serial bridge
+}
+
+const ANT_STICK_MODELS: &[StickModel] = &[
+    StickModel {
+        product_id: 0x1004,
+        name: "ANT USB1 Stick",
+        serial_bridge: true,
+    },
+    StickModel {
+        product_id: 0x1008,
+        name: "ANT USB2 Stick",
+        serial_bridge: false,
+    },
+    StickModel {
+        product_id: 0x1009,
+        name: "ANT USB-m Stick",
+        serial_bridge: false,
+    },
+];
+
+// CP210x vendor requests to bring up the USB1 stick's UART
+const CP210X_REQUEST_TYPE: u8 = 0x41; // Host-to-device, vendor, interface
+const CP210X_IFC_ENABLE: u8 = 0x00;
+const CP210X_SET_BAUDRATE: u8 = 0x1E;
+const CP210X_UART_ENABLE: u16 = 0x0001;
+const ANT_USB1_BAUD_RATE: u32 = 115_200;
 
 // USB endpoints for ANT+ stick
 const ANT_USB_EP_OUT: u8 = 0x01;
@@ -13,10 +47,96 @@ const ANT_USB_EP_IN: u8 = 0x81;
 const USB_WRITE_TIMEOUT: Duration = Duration::from_millis(1000);
 const USB_READ_TIMEOUT: Duration = Duration::from_millis(50); // Short timeout for non-blocking reads
 
+fn stick_model(vendor_id: u16, product_id: u16) -> Option<&'static StickModel> {
+    if vendor_id != ANT_USB_VID {
+        return None;
+    }
+    ANT_STICK_MODELS
+        .iter()
+        .find(|model| model.product_id == product_id)
+}
+
+/// Whether a USB vendor/product ID pair is an ANT stick
+pub fn is_ant_stick(vendor_id: u16, product_id: u16) -> bool {
+    stick_model(vendor_id, product_id).is_some()
+}
+
+/// A device on the USB bus, as listed by `list_usb_devices`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct UsbDeviceInfo {
+    pub bus: u8,
+    pub address: u8,
+    pub vendor_id: u16,
+    pub product_id: u16,
+    pub serial: Option<String>,
+    pub ant_model: Option<&'static str>, // Set for ANT sticks
+}
+
+impl UsbDeviceInfo {
+    fn read<T: UsbContext>(device: &Device<T>) -> Result<Self, String> {
+        let desc = device
+            .device_descriptor()
+            .map_err(|e| format!("Failed to get descriptor: {}", e))?;
+        let model = stick_model(desc.vendor_id(), desc.product_id());
+
+        // Reading the serial number needs a handle, so only sticks get one
+        let serial = model.and_then(|_| read_serial(device, &desc));
+
+        Ok(Self {
+            bus: device.bus_number(),
+            address: device.address(),
+            vendor_id: desc.vendor_id(),
+            product_id: desc.product_id(),
+            serial,
+            ant_model: model.map(|model| model.name),
+        })
+    }
+}
+
+fn read_serial<T: UsbContext>(device: &Device<T>, desc: &DeviceDescriptor) -> Option<String> {
+    let handle = device.open().ok()?;
+    handle.read_serial_number_string_ascii(desc).ok()
+}
+
+/// Which stick to open when more than one is plugged in
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StickSelector {
+    pub bus: u8,
+    pub address: u8,
+    pub serial: Option<String>,
+}
+
+impl StickSelector {
+    /// Match on serial number when both sides have one, since it survives
+    /// re-plugging; otherwise fall back to the bus position
+    pub fn matches(&self, info: &UsbDeviceInfo) -> bool {
+        match (&self.serial, &info.serial) {
+            (Some(wanted), Some(serial)) => wanted == serial,
+            _ => self.bus == info.bus && self.address == info.address,
+        }
+    }
+}
+
+/// Whether an ANT stick is on the bus; quiet, for polling
+pub fn stick_present() -> bool {
+    let Ok(devices) = Context::new().and_then(|context| context.devices()) else {
+        return false;
+    };
+
+    devices.iter().any(|device| {
+        device
+            .device_descriptor()
+            .map(|desc| is_ant_stick(desc.vendor_id(), desc.product_id()))
+            .unwrap_or(false)
+    })
+}
+
 pub struct AntUsb {
     context: Option<Context>,
     handle: Option<DeviceHandle<Context>>,
     found: bool,
+    selector: Option<StickSelector>,
+    framer: AntFramer,
 }
 
 impl AntUsb {
@@ -25,6 +145,16 @@ impl AntUsb {
             context: None,
             handle: None,
             found: false,
+            selector: None,
+            framer: AntFramer::new(),
+        }
+    }
+
+    /// Open the chosen stick instead of the first one found
+    pub fn with_selector(selector: StickSelector) -> Self {
+        Self {
+            selector: Some(selector),
+            ..Self::new()
         }
     }
 
@@ -32,6 +162,21 @@ impl AntUsb {
         let context =
             Context::new().map_err(|e| format!("Failed to create USB context: {}", e))?;
 
+        if let Some((_, model)) = self.select_device(&context)? {
+            println!("Found {}!", model.name);
+            self.found = true;
+            self.context = Some(context);
+            return Ok(true);
+        }
+
+        Ok(false)
+    }
+
+    /// The selected stick, or the first stick when none was chosen
+    fn select_device(
+        &self,
+        context: &Context,
+    ) -> Result<Option<(Device<Context>, &'static StickModel)>, String> {
         for device in context
             .devices()
             .map_err(|e| format!("Failed to list devices: {}", e))?
@@ -41,15 +186,20 @@ impl AntUsb {
                 .device_descriptor()
                 .map_err(|e| format!("Failed to get descriptor: {}", e))?;
 
-            if desc.vendor_id() == ANT_USB_VID && desc.product_id() == ANT_USB_PID {
-                println!("Found ANT+ USB stick!");
-                self.found = true;
-                self.context = Some(context);
-                return Ok(true);
+            let Some(model) = stick_model(desc.vendor_id(), desc.product_id()) else {
+                continue;
+            };
+
+            let selected = match &self.selector {
+                Some(selector) => selector.matches(&UsbDeviceInfo::read(&device)?),
+                None => true,
+            };
+            if selected {
+                return Ok(Some((device, model)));
             }
         }
 
-        Ok(false)
+        Ok(None)
     }
 
     pub fn is_found(&self) -> bool {
@@ -57,52 +207,47 @@ impl AntUsb {
     }
 
     pub fn open(&mut self) -> Result<(), String> {
-        let context = self
-            .context
-            .as_ref()
-            .ok_or("USB context not initialized. Call find_device first.")?;
+        if self.context.is_none() {
+            self.find_device()?;
+        }
 
-        for device in context
-            .devices()
-            .map_err(|e| format!("Failed to list devices: {}", e))?
-            .iter()
-        {
-            let desc = device
-                .device_descriptor()
-                .map_err(|e| format!("Failed to get descriptor: {}", e))?;
+        let context = self.context.as_ref().ok_or("ANT+ device not found")?;
+        let (device, model) = self
+            .select_device(context)?
+            .ok_or("ANT+ device not found")?;
 
-            if desc.vendor_id() == ANT_USB_VID && desc.product_id() == ANT_USB_PID {
-                let mut handle = device
-                    .open()
-                    .map_err(|e| format!("Failed to open device: {}", e))?;
-
-                // Detach kernel driver if necessary (Linux)
-                #[cfg(target_os = "linux")]
-                {
-                    if handle.kernel_driver_active(0).unwrap_or(false) {
-                        handle
-                            .detach_kernel_driver(0)
-                            .map_err(|e| format!("Failed to detach kernel driver: {}", e))?;
-                    }
-                }
-
-                // Claim interface 0
-                handle
-                    .claim_interface(0)
-                    .map_err(|e| format!("Failed to claim interface: {}", e))?;
+        let mut handle = device
+            .open()
+            .map_err(|e| format!("Failed to open device: {}", e))?;
 
-                // Reset the device to ensure clean state
+        // Detach kernel driver if necessary (Linux)
+        #[cfg(target_os = "linux")]
+        {
+            if handle.kernel_driver_active(0).unwrap_or(false) {
                 handle
-                    .reset()
-                    .map_err(|e| format!("Failed to reset device: {}", e))?;
-
-                self.handle = Some(handle);
-                println!("ANT+ USB device opened successfully");
-                return Ok(());
+                    .detach_kernel_driver(0)
+                    .map_err(|e| format!("Failed to detach kernel driver: {}", e))?;
             }
         }
 
-        Err("ANT+ device not found".to_string())
+        // Claim interface 0
+        handle
+            .claim_interface(0)
+            .map_err(|e| format!("Failed to claim interface: {}", e))?;
+
+        // Reset the device to ensure clean state
+        handle
+            .reset()
+            .map_err(|e| format!("Failed to reset device: {}", e))?;
+
+        if model.serial_bridge {
+            configure_serial_bridge(&handle)?;
+        }
+
+        self.handle = Some(handle);
+        self.framer.clear();
+        println!("{} opened successfully", model.name);
+        Ok(())
     }
 
     pub fn close(&mut self) {
@@ -136,7 +281,7 @@ impl AntUsb {
         }
     }
 
-    pub fn list_usb_devices(&self) -> Result<Vec<String>, String> {
+    pub fn list_usb_devices(&self) -> Result<Vec<UsbDeviceInfo>, String> {
         let context =
             Context::new().map_err(|e| format!("Failed to create USB context: {}", e))?;
 
@@ -147,23 +292,107 @@ impl AntUsb {
             .map_err(|e| format!("Failed to list devices: {}", e))?
             .iter()
         {
-            let desc = device
-                .device_descriptor()
-                .map_err(|e| format!("Failed to get descriptor: {}", e))?;
-
-            devices.push(format!(
-                "VID:{:04x} PID:{:04x}",
-                desc.vendor_id(),
-                desc.product_id()
-            ));
+            devices.push(UsbDeviceInfo::read(&device)?);
         }
 
         Ok(devices)
     }
 }
 
+/// Enable the CP210x UART and set the baud rate the USB1 stick talks at
+fn configure_serial_bridge(handle: &DeviceHandle<Context>) -> Result<(), String> {
+    handle
+        .write_control(
+            CP210X_REQUEST_TYPE,
+            CP210X_IFC_ENABLE,
+            CP210X_UART_ENABLE,
+            0,
+            &[],
+            USB_WRITE_TIMEOUT,
+        )
+        .map_err(|e| format!("Failed to enable serial bridge: {}", e))?;
+
+    handle
+        .write_control(
+            CP210X_REQUEST_TYPE,
+            CP210X_SET_BAUDRATE,
+            0,
+            0,
+            &ANT_USB1_BAUD_RATE.to_le_bytes(),
+            USB_WRITE_TIMEOUT,
+        )
+        .map_err(|e| format!("Failed to set serial bridge baud rate: {}", e))?;
+
+    Ok(())
+}
+
+impl AntTransport for AntUsb {
+    fn open(&mut self) -> Result<(), String> {
+        AntUsb::open(self)
+    }
+
+    fn close(&mut self) {
+        AntUsb::close(self)
+    }
+
+    fn write(&mut self, data: &[u8]) -> Result<usize, String> {
+        AntUsb::write(self, data)
+    }
+
+    fn read(&mut self, buffer: &mut [u8]) -> Result<usize, String> {
+        AntUsb::read(self, buffer)
+    }
+
+    fn framer(&mut self) -> &mut AntFramer {
+        &mut self.framer
+    }
+}
+
 impl Default for AntUsb {
     fn default() -> Self {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stick(bus: u8, address: u8, serial: Option<&str>) -> UsbDeviceInfo {
+        UsbDeviceInfo {
+            bus,
+            address,
+            vendor_id: ANT_USB_VID,
+            product_id: 0x1009,
+            serial: serial.map(str::to_string),
+            ant_model: Some("ANT USB-m Stick"),
+        }
+    }
+
+    #[test]
+    fn test_every_dynastream_stick_is_recognised() {
+        assert!(is_ant_stick(ANT_USB_VID, 0x1004));
+        assert!(is_ant_stick(ANT_USB_VID, 0x1008));
+        assert!(is_ant_stick(ANT_USB_VID, 0x1009));
+        assert!(!is_ant_stick(ANT_USB_VID, 0x1234));
+        assert!(!is_ant_stick(0x10c4, 0x1008));
+        assert!(stick_model(ANT_USB_VID, 0x1004).unwrap().serial_bridge);
+    }
+
+    #[test]
+    fn test_selector_prefers_serial_over_bus_position() {
+        let selector = StickSelector {
+            bus: 1,
+            address: 4,
+            serial: Some("123".to_string()),
+        };
+
+        // Re-plugged onto another port: same serial, new address
+        assert!(selector.matches(&stick(2, 7, Some("123"))));
+        // Another stick that took over the old address
+        assert!(!selector.matches(&stick(1, 4, Some("456"))));
+        // No serial readable: fall back to the bus position
+        assert!(selector.matches(&stick(1, 4, None)));
+        assert!(!selector.matches(&stick(1, 5, None)));
+    }
+}
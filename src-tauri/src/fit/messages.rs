@@ -56,12 +56,7 @@ pub fn write_event(encoder: &mut FitEncoder, timestamp: u32, event_type: u8) {
 /// Local message type: 2
 pub fn write_record(
     encoder: &mut FitEncoder,
-    timestamp: u32,
-    power: u16,
-    heart_rate: u8,
-    cadence: u8,
-    speed: u16,      // m/s * 1000
-    distance: u32,   // meters * 100
+    record: &RecordData,
     first: bool,     // if true, write definition first
 ) {
     if first {
@@ -72,6 +67,9 @@ pub fn write_record(
             FieldDef::new(4, 1, BASE_TYPE_UINT8),     // cadence
             FieldDef::new(6, 2, BASE_TYPE_UINT16),    // speed (m/s * 1000)
             FieldDef::new(5, 4, BASE_TYPE_UINT32),    // distance (m * 100)
+            FieldDef::new(9, 2, BASE_TYPE_SINT16),    // grade (% * 100)
+            FieldDef::new(10, 1, BASE_TYPE_UINT8),    // resistance (0-254)
+            FieldDef::new(33, 2, BASE_TYPE_UINT16),   // calories (kcal)
         ];
         encoder.write_definition(2, MESG_RECORD, &fields);
     }
@@ -79,12 +77,15 @@ pub fn write_record(
     encoder.write_data(
         2,
         &[
-            &timestamp.to_le_bytes(),
-            &power.to_le_bytes(),
-            &[heart_rate],
-            &[cadence],
-            &speed.to_le_bytes(),
-            &distance.to_le_bytes(),
+            &record.timestamp.to_le_bytes(),
+            &record.power.to_le_bytes(),
+            &[record.heart_rate],
+            &[record.cadence],
+            &record.speed.to_le_bytes(),
+            &record.distance.to_le_bytes(),
+            &record.grade.to_le_bytes(),
+            &[record.resistance],
+            &record.calories.to_le_bytes(),
         ],
     );
 }
@@ -109,6 +110,7 @@ pub fn write_lap(encoder: &mut FitEncoder, lap: &LapData) {
         FieldDef::new(16, 1, BASE_TYPE_UINT8),    // max_heart_rate
         FieldDef::new(17, 1, BASE_TYPE_UINT8),    // avg_cadence
         FieldDef::new(18, 1, BASE_TYPE_UINT8),    // max_cadence
+        FieldDef::new(11, 2, BASE_TYPE_UINT16),   // total_calories (kcal)
     ];
 
     let elapsed_ms = (lap.total_elapsed_time * 1000.0) as u32;
@@ -128,14 +130,15 @@ pub fn write_lap(encoder: &mut FitEncoder, lap: &LapData) {
             &[EVENT_TIMER],
             &[EVENT_TYPE_STOP_ALL],
             &[LAP_TRIGGER_SESSION_END],
-            &[SPORT_CYCLING],
-            &[SUB_SPORT_INDOOR_CYCLING],
+            &[lap.sport],
+            &[lap.sub_sport],
             &lap.avg_power.to_le_bytes(),
             &lap.max_power.to_le_bytes(),
             &[lap.avg_heart_rate],
             &[lap.max_heart_rate],
             &[lap.avg_cadence],
             &[lap.max_cadence],
+            &lap.total_calories.to_le_bytes(),
         ],
     );
 }
@@ -163,6 +166,7 @@ pub fn write_session(encoder: &mut FitEncoder, session: &SessionData) {
         FieldDef::new(1, 1, BASE_TYPE_ENUM),      // event_type
         FieldDef::new(25, 2, BASE_TYPE_UINT16),   // first_lap_index
         FieldDef::new(26, 2, BASE_TYPE_UINT16),   // num_laps
+        FieldDef::new(11, 2, BASE_TYPE_UINT16),   // total_calories (kcal)
     ];
 
     let elapsed_ms = (session.total_elapsed_time * 1000.0) as u32;
@@ -179,8 +183,8 @@ pub fn write_session(encoder: &mut FitEncoder, session: &SessionData) {
             &elapsed_ms.to_le_bytes(),
             &timer_ms.to_le_bytes(),
             &distance_cm.to_le_bytes(),
-            &[SPORT_CYCLING],
-            &[SUB_SPORT_INDOOR_CYCLING],
+            &[session.sport],
+            &[session.sub_sport],
             &session.avg_power.to_le_bytes(),
             &session.max_power.to_le_bytes(),
             &[session.avg_heart_rate],
@@ -193,6 +197,7 @@ pub fn write_session(encoder: &mut FitEncoder, session: &SessionData) {
             &[EVENT_TYPE_STOP_ALL],
             &0u16.to_le_bytes(),   // first_lap_index = 0
             &1u16.to_le_bytes(),   // num_laps = 1
+            &session.total_calories.to_le_bytes(),
         ],
     );
 }
@@ -227,6 +232,59 @@ pub fn write_activity(encoder: &mut FitEncoder, timestamp: u32, total_timer_time
     );
 }
 
+/// Device info message — a sensor that contributed to the recording.
+/// Local message type: 6
+pub fn write_device_info(encoder: &mut FitEncoder, device: &DeviceInfoData) {
+    // Null-terminated, and the definition holds the field size in one byte
+    let mut product_name: Vec<u8> = device.product_name.as_bytes().to_vec();
+    product_name.truncate(254);
+    product_name.push(0);
+
+    let fields = vec![
+        FieldDef::new(253, 4, BASE_TYPE_UINT32),  // timestamp
+        FieldDef::new(0, 1, BASE_TYPE_UINT8),     // device_index
+        FieldDef::new(1, 1, BASE_TYPE_UINT8),     // device_type (ANT+ device type)
+        FieldDef::new(2, 2, BASE_TYPE_UINT16),    // manufacturer
+        FieldDef::new(3, 4, BASE_TYPE_UINT32Z),   // serial_number
+        FieldDef::new(4, 2, BASE_TYPE_UINT16),    // product
+        FieldDef::new(5, 2, BASE_TYPE_UINT16),    // software_version (* 100)
+        FieldDef::new(21, 2, BASE_TYPE_UINT16Z),  // ant_device_number
+        FieldDef::new(25, 1, BASE_TYPE_ENUM),     // source_type
+        FieldDef::new(27, product_name.len() as u8, BASE_TYPE_STRING), // product_name
+    ];
+
+    encoder.write_message_with_def(
+        6,
+        MESG_DEVICE_INFO,
+        &fields,
+        &[
+            &device.timestamp.to_le_bytes(),
+            &[device.device_index],
+            &[device.device_type],
+            &device.manufacturer.to_le_bytes(),
+            &device.serial_number.to_le_bytes(),
+            &device.product.to_le_bytes(),
+            &device.software_version.to_le_bytes(),
+            &device.device_number.to_le_bytes(),
+            &[SOURCE_TYPE_ANTPLUS],
+            &product_name,
+        ],
+    );
+}
+
+/// Data needed to write a Record message.
+pub struct RecordData {
+    pub timestamp: u32,
+    pub power: u16,
+    pub heart_rate: u8,
+    pub cadence: u8,
+    pub speed: u16,       // m/s * 1000
+    pub distance: u32,    // meters * 100
+    pub grade: i16,       // % * 100, SINT16_INVALID if unknown
+    pub resistance: u8,   // 0-254 of maximum, UINT8_INVALID if unknown
+    pub calories: u16,    // kcal since start, UINT16_INVALID if unknown
+}
+
 /// Data needed to write a Lap message.
 pub struct LapData {
     pub timestamp: u32,
@@ -234,12 +292,15 @@ pub struct LapData {
     pub total_elapsed_time: f64,
     pub total_timer_time: f64,
     pub total_distance: f64,
+    pub sport: u8,
+    pub sub_sport: u8,
     pub avg_power: u16,
     pub max_power: u16,
     pub avg_heart_rate: u8,
     pub max_heart_rate: u8,
     pub avg_cadence: u8,
     pub max_cadence: u8,
+    pub total_calories: u16, // UINT16_INVALID if unknown
 }
 
 /// Data needed to write a Session message.
@@ -249,6 +310,8 @@ pub struct SessionData {
     pub total_elapsed_time: f64,
     pub total_timer_time: f64,
     pub total_distance: f64,
+    pub sport: u8,
+    pub sub_sport: u8,
     pub avg_power: u16,
     pub max_power: u16,
     pub avg_heart_rate: u8,
@@ -257,4 +320,18 @@ pub struct SessionData {
     pub max_cadence: u8,
     pub avg_speed: u16,   // m/s * 1000
     pub max_speed: u16,   // m/s * 1000
+    pub total_calories: u16, // UINT16_INVALID if unknown
+}
+
+/// Data needed to write a Device Info message.
+pub struct DeviceInfoData {
+    pub timestamp: u32,
+    pub device_index: u8,
+    pub device_type: u8,       // ANT+ device type
+    pub device_number: u16,
+    pub manufacturer: u16,     // UINT16_INVALID if unknown
+    pub product: u16,          // model number, UINT16_INVALID if unknown
+    pub serial_number: u32,    // UINT32Z_INVALID if unknown
+    pub software_version: u16, // version * 100, UINT16_INVALID if unknown
+    pub product_name: String,  // empty if unknown
 }
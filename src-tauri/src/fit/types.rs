@@ -7,9 +7,18 @@ pub const FIT_EPOCH_OFFSET: u32 = 631065600;
 // Base types (used in field definitions)
 pub const BASE_TYPE_ENUM: u8 = 0x00;
 pub const BASE_TYPE_UINT8: u8 = 0x0D;
+pub const BASE_TYPE_SINT16: u8 = 0x83;
 pub const BASE_TYPE_UINT16: u8 = 0x84;
 pub const BASE_TYPE_UINT32: u8 = 0x86;
 pub const BASE_TYPE_STRING: u8 = 0x07;
+pub const BASE_TYPE_UINT16Z: u8 = 0x8B;
+pub const BASE_TYPE_UINT32Z: u8 = 0x8C;
+
+// Invalid values (field not recorded)
+pub const UINT8_INVALID: u8 = 0xFF;
+pub const SINT16_INVALID: i16 = 0x7FFF;
+pub const UINT16_INVALID: u16 = 0xFFFF;
+pub const UINT32Z_INVALID: u32 = 0;
 
 // Message numbers (Global Message Numbers)
 pub const MESG_FILE_ID: u16 = 0;
@@ -18,6 +27,7 @@ pub const MESG_RECORD: u16 = 20;
 pub const MESG_LAP: u16 = 19;
 pub const MESG_SESSION: u16 = 18;
 pub const MESG_ACTIVITY: u16 = 34;
+pub const MESG_DEVICE_INFO: u16 = 23;
 
 // File type
 pub const FILE_TYPE_ACTIVITY: u8 = 4;
@@ -28,9 +38,21 @@ pub const MANUFACTURER_DEVELOPMENT: u16 = 255;
 // Product
 pub const PRODUCT_NOTCHRIDER: u16 = 1;
 
+// Device info source
+pub const SOURCE_TYPE_ANTPLUS: u8 = 1;
+
 // Sport
+pub const SPORT_RUNNING: u8 = 1;
 pub const SPORT_CYCLING: u8 = 2;
+pub const SPORT_FITNESS_EQUIPMENT: u8 = 4;
+pub const SPORT_CROSS_COUNTRY_SKIING: u8 = 12;
+pub const SPORT_ROWING: u8 = 15;
+pub const SUB_SPORT_TREADMILL: u8 = 1;
 pub const SUB_SPORT_INDOOR_CYCLING: u8 = 6;
+pub const SUB_SPORT_INDOOR_ROWING: u8 = 14;
+pub const SUB_SPORT_ELLIPTICAL: u8 = 15;
+pub const SUB_SPORT_STAIR_CLIMBING: u8 = 16;
+pub const SUB_SPORT_INDOOR_SKIING: u8 = 25;
 
 // Event
 pub const EVENT_TIMER: u8 = 0;
@@ -2,39 +2,285 @@ mod ant;
 mod fit;
 mod workout;
 
-use ant::channel::AntChannel;
-use ant::fec::FecParser;
-use ant::hrm::HrmParser;
-use ant::usb::AntUsb;
+use ant::allocator::{ChannelAllocator, ChannelInfo, StickInfo};
+use ant::calibration::{CalibrationKind, CalibrationStatus};
+use ant::capture::{RecordingTransport, ReplayTransport};
+use ant::channel::{
+    AntChannel, ChannelId, ChannelStatus, ExtendedData, StickCapabilities, LIB_CONFIG_CHANNEL_ID,
+    LIB_CONFIG_RSSI, LIB_CONFIG_RX_TIMESTAMP, MESG_BROADCAST_DATA, MESG_CAPABILITIES,
+    MESG_CHANNEL_ID, MESG_CHANNEL_STATUS,
+};
+use ant::control::{FecController, TrainerInfo};
+use ant::discovery::{DeviceSearch, DiscoveredDevice, DEFAULT_SEARCH_TIMEOUT};
+use ant::fec::{FecDataPage, FecParser, UserConfig, DEFAULT_WHEEL_CIRCUMFERENCE_M};
+use ant::hotplug::{spawn_stick_watcher, StickEvent};
+use ant::monitor::{ChannelState, SensorStatus};
+use ant::pairing::{PairedDevice, PairedDevices, PAIRING_FILE_NAME};
+use ant::profile::SensorProfile;
+use ant::scan::{ScanSession, ScannedDevice, DEFAULT_SCAN_DURATION};
+use ant::serial::{AntSerial, DEFAULT_BAUD_RATE};
+#[cfg(feature = "simulated-ant")]
+use ant::sim::SimulatedStick;
+use ant::transport::AntTransport;
+use ant::usb::{AntUsb, StickSelector, UsbDeviceInfo};
 use ant::TrainerData;
+use std::collections::HashMap;
+use std::fs::OpenOptions;
+use std::path::{Path, PathBuf};
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Mutex;
 use std::thread;
-use std::time::Duration;
-use tauri::{Manager, State};
+use std::time::{Duration, Instant};
+use tauri::{Emitter, Manager, State};
 use workout::{WorkoutRecorder, WorkoutSummary};
 
 // macOS-specific imports are used inline in setup()
 
+// Rate of `trainer-data` events pushed to the webview (10 Hz)
+const TRAINER_DATA_EMIT_INTERVAL: Duration = Duration::from_millis(100);
+
+// Set to use the in-process simulated stick instead of USB hardware; only
+// honoured in development builds with the `simulated-ant` feature
+const SIMULATED_ANT_ENV: &str = "NOTCH_RIDER_SIMULATED_ANT";
+
+// Set to a capture file to play back instead of USB hardware
+const REPLAY_ANT_ENV: &str = "NOTCH_RIDER_ANT_REPLAY";
+const REPLAY_SPEED_ENV: &str = "NOTCH_RIDER_ANT_REPLAY_SPEED"; // 1 = real time
+
+// Set to a file path to record all ANT traffic to it
+const CAPTURE_ANT_ENV: &str = "NOTCH_RIDER_ANT_CAPTURE";
+
+// Reader thread back-off while disconnected or after a read error
+const ANT_IDLE_INTERVAL: Duration = Duration::from_millis(100);
+
+// Reader thread pause after a read that returned nothing, so it neither
+// spins a core nor keeps the transport lock from control commands
+const ANT_READ_IDLE_DELAY: Duration = Duration::from_millis(5);
+
+// Consecutive read errors before the stick is treated as unplugged
+const READ_FAILURES_BEFORE_LOST: u32 = 3;
+
+// Re-open attempts while the stick is missing (hot-plug arrival retries at once)
+const STICK_RETRY_INTERVAL: Duration = Duration::from_millis(2000);
+
+// Wheel circumferences accepted for the FE-C Page 26 wheel speed, in meters
+const WHEEL_CIRCUMFERENCE_MIN_M: f32 = 0.5;
+const WHEEL_CIRCUMFERENCE_MAX_M: f32 = 3.0;
+
 struct AppState {
-    ant: Mutex<AntUsb>,
+    ant: Mutex<Box<dyn AntTransport>>,
     trainer_data: Mutex<TrainerData>,
-    fec_channel: Mutex<Option<AntChannel>>,  // Channel 0: FE-C (trainer)
-    hrm_channel: Mutex<Option<AntChannel>>,  // Channel 1: HRM (heart rate)
+    channels: Mutex<ChannelAllocator>, // Channel per sensor profile
+    trainer_info: Mutex<TrainerInfo>,  // FE-C Pages 54, 80 and 81
+    fec_parsers: Mutex<HashMap<u8, FecParser>>, // Per FE-C channel, for accumulated values
+    wheel_circumference: Mutex<f32>,   // Meters, for the FE-C Page 26 wheel speed
+    calibration: Mutex<CalibrationStatus>, // From FE-C Pages 1 and 2
+    paired_devices: Mutex<PairedDevices>,
+    pairing_file: Option<PathBuf>, // Where pairings are saved; None keeps them in memory
     connected: AtomicBool,
+    stick_lost: AtomicBool,    // Stick unplugged mid-session, being re-opened
+    stick_arrived: AtomicBool, // Set by the hot-plug watcher
+    stick_left: AtomicBool,    // Set by the hot-plug watcher
     workout: Mutex<Option<WorkoutRecorder>>,
 }
 
+impl AppState {
+    fn new(transport: Box<dyn AntTransport>) -> Self {
+        Self {
+            ant: Mutex::new(transport),
+            trainer_data: Mutex::new(TrainerData::default()),
+            channels: Mutex::new(ChannelAllocator::new()),
+            trainer_info: Mutex::new(TrainerInfo::default()),
+            fec_parsers: Mutex::new(HashMap::new()),
+            wheel_circumference: Mutex::new(DEFAULT_WHEEL_CIRCUMFERENCE_M),
+            calibration: Mutex::new(CalibrationStatus::default()),
+            paired_devices: Mutex::new(PairedDevices::default()),
+            pairing_file: None,
+            connected: AtomicBool::new(false),
+            stick_lost: AtomicBool::new(false),
+            stick_arrived: AtomicBool::new(false),
+            stick_left: AtomicBool::new(false),
+            workout: Mutex::new(None),
+        }
+    }
+
+    fn with_paired_devices(mut self, paired: PairedDevices) -> Self {
+        self.paired_devices = Mutex::new(paired);
+        self
+    }
+
+    fn with_pairing_file(mut self, path: Option<PathBuf>) -> Self {
+        self.pairing_file = path;
+        self
+    }
+}
+
+#[tauri::command]
+fn find_ant_device() -> Result<bool, String> {
+    if virtual_stick() {
+        return Ok(true);
+    }
+    AntUsb::new().find_device()
+}
+
+#[tauri::command]
+fn list_usb_devices() -> Result<Vec<UsbDeviceInfo>, String> {
+    AntUsb::new().list_usb_devices()
+}
+
+/// Choose which stick to open when several are plugged in; `None` goes back
+/// to the first one found
 #[tauri::command]
-fn find_ant_device(state: State<AppState>) -> Result<bool, String> {
+fn select_ant_stick(state: State<AppState>, stick: Option<StickSelector>) -> Result<(), String> {
+    if virtual_stick() {
+        return Err("Simulated ANT+ stick in use".to_string());
+    }
+    if state.connected.load(Ordering::SeqCst) {
+        return Err("Disconnect before switching ANT+ sticks".to_string());
+    }
+
+    let transport = match stick {
+        Some(selector) => AntUsb::with_selector(selector),
+        None => AntUsb::new(),
+    };
+    *state.ant.lock().map_err(|e| e.to_string())? = with_capture(Box::new(transport));
+    Ok(())
+}
+
+#[tauri::command]
+fn list_serial_ports() -> Result<Vec<String>, String> {
+    AntSerial::list_ports()
+}
+
+/// Talk to a USB1 stick or UART ANT module on a serial port instead of USB
+#[tauri::command]
+fn select_serial_ant_stick(
+    state: State<AppState>,
+    path: String,
+    baud_rate: Option<u32>,
+) -> Result<(), String> {
+    if virtual_stick() {
+        return Err("Simulated ANT+ stick in use".to_string());
+    }
+    if state.connected.load(Ordering::SeqCst) {
+        return Err("Disconnect before switching ANT+ sticks".to_string());
+    }
+
+    let transport = AntSerial::new(path, baud_rate.unwrap_or(DEFAULT_BAUD_RATE));
+    *state.ant.lock().map_err(|e| e.to_string())? = with_capture(Box::new(transport));
+    Ok(())
+}
+
+// The search blocks until its timeout, so it runs on a blocking thread
+#[tauri::command]
+async fn search_ant_devices(
+    app: tauri::AppHandle,
+    timeout_secs: Option<u64>,
+) -> Result<Vec<DiscoveredDevice>, String> {
+    let timeout = timeout_secs
+        .map(Duration::from_secs)
+        .unwrap_or(DEFAULT_SEARCH_TIMEOUT);
+    tauri::async_runtime::spawn_blocking(move || search_devices(&app.state::<AppState>(), timeout))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+/// Run a discovery search; the stick can't search while channels are in use
+fn search_devices(state: &AppState, timeout: Duration) -> Result<Vec<DiscoveredDevice>, String> {
+    if state.connected.load(Ordering::SeqCst) {
+        return Err("Disconnect before searching for sensors".to_string());
+    }
+
     let mut ant = state.ant.lock().map_err(|e| e.to_string())?;
-    ant.find_device()
+    ant.open()?;
+    let devices = DeviceSearch::new().run(&mut **ant, timeout);
+    ant.close();
+
+    let devices = devices?;
+    println!("ANT+ search found {} device(s)", devices.len());
+    Ok(devices)
+}
+
+// The scan blocks for its whole duration, so it runs on a blocking thread
+// and pushes the live table to the webview as `scan-update` events
+#[tauri::command]
+async fn scan_ant_devices(
+    app: tauri::AppHandle,
+    duration_secs: Option<u64>,
+) -> Result<Vec<ScannedDevice>, String> {
+    let duration = duration_secs
+        .map(Duration::from_secs)
+        .unwrap_or(DEFAULT_SCAN_DURATION);
+    tauri::async_runtime::spawn_blocking(move || {
+        let state = app.state::<AppState>();
+        scan_devices(&state, duration, |devices| {
+            let _ = app.emit("scan-update", devices);
+        })
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// Listen to every sensor in range in continuous scan mode; like a search,
+/// this takes over the whole stick
+fn scan_devices(
+    state: &AppState,
+    duration: Duration,
+    on_update: impl FnMut(&[ScannedDevice]),
+) -> Result<Vec<ScannedDevice>, String> {
+    if state.connected.load(Ordering::SeqCst) {
+        return Err("Disconnect before scanning for sensors".to_string());
+    }
+
+    let mut ant = state.ant.lock().map_err(|e| e.to_string())?;
+    ant.open()?;
+    let devices = ScanSession::new().run(&mut **ant, duration, on_update);
+    ant.close();
+
+    let devices = devices?;
+    println!("ANT+ scan heard {} device(s)", devices.len());
+    Ok(devices)
 }
 
 #[tauri::command]
-fn list_usb_devices(state: State<AppState>) -> Result<Vec<String>, String> {
-    let ant = state.ant.lock().map_err(|e| e.to_string())?;
-    ant.list_usb_devices()
+fn get_paired_devices(state: State<AppState>) -> Result<PairedDevices, String> {
+    let paired = state.paired_devices.lock().map_err(|e| e.to_string())?;
+    Ok(paired.clone())
+}
+
+#[tauri::command]
+fn pair_ant_device(
+    state: State<AppState>,
+    device_type: u8,
+    device_number: u16,
+    transmission_type: u8,
+) -> Result<(), String> {
+    let device = PairedDevice {
+        device_number,
+        transmission_type,
+    };
+    update_pairing(&state, device_type, Some(device))?;
+    println!("Paired ANT+ device type {} #{}", device_type, device_number);
+    Ok(())
+}
+
+#[tauri::command]
+fn forget_ant_device(state: State<AppState>, device_type: u8) -> Result<(), String> {
+    update_pairing(&state, device_type, None)
+}
+
+/// Change a pairing and persist it; takes effect on the next connect
+fn update_pairing(
+    state: &AppState,
+    device_type: u8,
+    device: Option<PairedDevice>,
+) -> Result<(), String> {
+    let mut paired = state.paired_devices.lock().map_err(|e| e.to_string())?;
+    paired.set(device_type, device)?;
+    match &state.pairing_file {
+        Some(path) => paired.save_to(path),
+        None => Ok(()),
+    }
 }
 
 #[tauri::command]
@@ -45,69 +291,272 @@ fn get_trainer_data(state: State<AppState>) -> Result<TrainerData, String> {
 
 #[tauri::command]
 fn connect_ant_device(state: State<AppState>) -> Result<bool, String> {
+    connect_ant(&state)
+}
+
+/// Open the stick and set up the FE-C and HRM channels
+fn connect_ant(state: &AppState) -> Result<bool, String> {
+    open_channels(state)?;
+
+    state.stick_lost.store(false, Ordering::SeqCst);
+    state.connected.store(true, Ordering::SeqCst);
+    println!("ANT+ device connected - FE-C and HRM channels ready");
+    Ok(true)
+}
+
+/// Open the stick, reset it and open a channel for every sensor profile
+fn open_channels(state: &AppState) -> Result<(), String> {
+    let paired = state
+        .paired_devices
+        .lock()
+        .map_err(|e| e.to_string())?
+        .clone();
     let mut ant = state.ant.lock().map_err(|e| e.to_string())?;
 
-    // Open USB device
+    // Open the ANT+ stick
     ant.open()?;
+    ant.write(&AntChannel::reset_system())?;
+    thread::sleep(Duration::from_millis(50));
 
-    // Initialize ANT+ channel 0 for FE-C (trainer)
-    let fec_channel = AntChannel::new(0);
-    let fec_init_sequence = fec_channel.get_init_sequence();
+    // The channel count decides how many sensors fit on the stick
+    let mut early_messages = Vec::new();
+    let capabilities = ant
+        .request_message(0, MESG_CAPABILITIES, &mut |msg_id, channel, data| {
+            early_messages.push((msg_id, channel, data.to_vec()))
+        })?
+        .and_then(|data| StickCapabilities::parse(&data));
 
-    // Send FE-C initialization sequence
-    for msg in fec_init_sequence {
-        ant.write(&msg)?;
+    ant.write(&AntChannel::default().set_network_key())?;
+    thread::sleep(Duration::from_millis(50));
+
+    // Extended messages name the device behind each broadcast, so the device
+    // a wildcard channel found can be offered for pairing
+    if capabilities.map_or(true, |c| c.extended_messages()) {
+        ant.write(&AntChannel::set_lib_config(
+            LIB_CONFIG_CHANNEL_ID | LIB_CONFIG_RSSI | LIB_CONFIG_RX_TIMESTAMP,
+        ))?;
         thread::sleep(Duration::from_millis(50));
     }
 
-    println!("ANT+ FE-C channel 0 initialized");
+    {
+        let mut channels = state.channels.lock().map_err(|e| e.to_string())?;
+        match capabilities {
+            Some(capabilities) => channels.set_capabilities(&capabilities),
+            None => eprintln!("ANT+ stick did not report its capabilities"),
+        }
 
-    // Initialize ANT+ channel 1 for HRM (heart rate monitor)
-    let hrm_channel = AntChannel::new(1);
-    let hrm_init_sequence = hrm_channel.get_hrm_init_sequence();
+        for profile in SensorProfile::ALL {
+            let allocation = match channels.allocate(profile, &paired) {
+                Ok(allocation) => allocation,
+                Err(e) => {
+                    eprintln!("{}", e);
+                    continue;
+                }
+            };
 
-    // Send HRM initialization sequence (no reset needed, network key already set)
-    for msg in hrm_init_sequence {
-        ant.write(&msg)?;
-        thread::sleep(Duration::from_millis(50));
+            for msg in profile.init_sequence(&allocation.channel) {
+                ant.write(&msg)?;
+                thread::sleep(Duration::from_millis(50));
+            }
+            allocation.monitor.opened();
+
+            println!(
+                "ANT+ {} channel {} initialized",
+                profile.name(),
+                allocation.channel.channel_number()
+            );
+        }
     }
 
-    println!("ANT+ HRM channel 1 initialized");
+    // Anything that arrived during the capabilities wait, now that the
+    // channels can be routed
+    for (msg_id, channel, data) in early_messages {
+        handle_ant_message(state, msg_id, channel, &data);
+    }
 
-    // Store channels
-    {
-        let mut ch = state.fec_channel.lock().map_err(|e| e.to_string())?;
-        *ch = Some(fec_channel);
+    Ok(())
+}
+
+#[tauri::command]
+fn set_target_power(state: State<AppState>, watts: u16) -> Result<(), String> {
+    run_fec_control(&state, |controller, ant, on_message| {
+        controller.set_target_power(ant, watts as f32, on_message)
+    })?;
+    println!("ERG target power set to {}W", watts);
+    Ok(())
+}
+
+#[tauri::command]
+fn set_grade(state: State<AppState>, grade: f32) -> Result<(), String> {
+    run_fec_control(&state, |controller, ant, on_message| {
+        controller.set_grade(ant, grade, on_message)
+    })?;
+    println!("Simulation grade set to {:.1}%", grade);
+    Ok(())
+}
+
+#[tauri::command]
+fn set_resistance(state: State<AppState>, percent: f32) -> Result<(), String> {
+    run_fec_control(&state, |controller, ant, on_message| {
+        controller.set_resistance(ant, percent, on_message)
+    })?;
+    println!("Basic resistance set to {:.1}%", percent);
+    Ok(())
+}
+
+#[tauri::command]
+fn set_user_config(state: State<AppState>, config: UserConfig) -> Result<(), String> {
+    run_fec_control(&state, |controller, ant, on_message| {
+        controller.set_user_config(ant, &config, on_message)
+    })
+}
+
+#[tauri::command]
+fn set_wheel_circumference(state: State<AppState>, circumference_m: f32) -> Result<(), String> {
+    update_wheel_circumference(&state, circumference_m)
+}
+
+/// Use the rider's wheel for the speed derived from FE-C Page 26, now and
+/// for the parsers of later connects
+fn update_wheel_circumference(state: &AppState, circumference_m: f32) -> Result<(), String> {
+    if !(WHEEL_CIRCUMFERENCE_MIN_M..=WHEEL_CIRCUMFERENCE_MAX_M).contains(&circumference_m) {
+        return Err(format!(
+            "Wheel circumference must be between {} and {} m",
+            WHEEL_CIRCUMFERENCE_MIN_M, WHEEL_CIRCUMFERENCE_MAX_M
+        ));
     }
+
+    *state
+        .wheel_circumference
+        .lock()
+        .map_err(|e| e.to_string())? = circumference_m;
+    for parser in state
+        .fec_parsers
+        .lock()
+        .map_err(|e| e.to_string())?
+        .values_mut()
     {
-        let mut ch = state.hrm_channel.lock().map_err(|e| e.to_string())?;
-        *ch = Some(hrm_channel);
+        parser.set_wheel_circumference(circumference_m);
     }
+    Ok(())
+}
 
-    state.connected.store(true, Ordering::SeqCst);
-    println!("ANT+ device connected - FE-C and HRM channels ready");
-    Ok(true)
+#[tauri::command]
+fn start_calibration(
+    state: State<AppState>,
+    kind: CalibrationKind,
+) -> Result<CalibrationStatus, String> {
+    request_calibration(&state, kind)
+}
+
+/// Ask the trainer for a calibration; progress arrives with the broadcasts
+fn request_calibration(
+    state: &AppState,
+    kind: CalibrationKind,
+) -> Result<CalibrationStatus, String> {
+    // Set before sending so progress read during the exchange isn't lost
+    let previous = {
+        let mut calibration = state.calibration.lock().map_err(|e| e.to_string())?;
+        std::mem::replace(
+            &mut *calibration,
+            CalibrationStatus::requested(kind, Instant::now()),
+        )
+    };
+
+    let result = run_fec_control(state, |controller, ant, on_message| {
+        controller.request_calibration(ant, kind, on_message)
+    });
+
+    let mut calibration = state.calibration.lock().map_err(|e| e.to_string())?;
+    if let Err(e) = result {
+        *calibration = previous;
+        return Err(e);
+    }
+    println!("Trainer calibration requested: {:?}", kind);
+    Ok(calibration.clone())
+}
+
+#[tauri::command]
+fn get_calibration_status(state: State<AppState>) -> Result<CalibrationStatus, String> {
+    state
+        .calibration
+        .lock()
+        .map(|c| c.clone())
+        .map_err(|e| e.to_string())
+}
+
+/// Run a control request on the FE-C channel
+///
+/// Holds the transport lock for the whole exchange and keeps parsing trainer/HR
+/// data that arrives while we wait for the trainer.
+fn run_fec_control<F>(state: &AppState, control: F) -> Result<(), String>
+where
+    F: FnOnce(
+        &FecController,
+        &mut dyn AntTransport,
+        &mut dyn FnMut(u8, u8, &[u8]),
+    ) -> Result<(), String>,
+{
+    if !state.connected.load(Ordering::SeqCst) {
+        return Err("ANT+ device not connected".to_string());
+    }
+
+    let (capabilities, quirks) = {
+        let info = state.trainer_info.lock().map_err(|e| e.to_string())?;
+        (info.capabilities, info.quirks())
+    };
+    let controller = {
+        let channels = state.channels.lock().map_err(|e| e.to_string())?;
+        let allocation = channels
+            .get(SensorProfile::Fec)
+            .ok_or("FE-C channel not open")?;
+        FecController::new(allocation.channel.channel_number())
+            .with_capabilities(capabilities)
+            .with_quirks(quirks)
+    };
+
+    let mut ant = state.ant.lock().map_err(|e| e.to_string())?;
+
+    let mut on_message = |msg_id: u8, channel: u8, data: &[u8]| {
+        handle_ant_message(state, msg_id, channel, data);
+    };
+
+    control(&controller, &mut **ant, &mut on_message)
 }
 
 #[tauri::command]
 fn disconnect_ant_device(state: State<AppState>) -> Result<(), String> {
+    disconnect_ant(&state)
+}
+
+/// Close every channel and the stick
+fn disconnect_ant(state: &AppState) -> Result<(), String> {
     state.connected.store(false, Ordering::SeqCst);
+    state.stick_lost.store(false, Ordering::SeqCst);
 
     let mut ant = state.ant.lock().map_err(|e| e.to_string())?;
 
-    // Close FE-C channel
-    if let Ok(mut ch) = state.fec_channel.lock() {
-        if let Some(channel) = ch.take() {
-            let close_msg = channel.close_channel();
+    // Close and free every sensor channel
+    if let Ok(mut channels) = state.channels.lock() {
+        for allocation in channels.release_all() {
+            let close_msg = allocation.channel.close_channel();
             let _ = ant.write(&close_msg);
         }
     }
 
-    // Close HRM channel
-    if let Ok(mut ch) = state.hrm_channel.lock() {
-        if let Some(channel) = ch.take() {
-            let close_msg = channel.close_channel();
-            let _ = ant.write(&close_msg);
+    if let Ok(mut info) = state.trainer_info.lock() {
+        *info = TrainerInfo::default();
+    }
+    if let Ok(mut parsers) = state.fec_parsers.lock() {
+        parsers.clear();
+    }
+    if let Ok(mut calibration) = state.calibration.lock() {
+        *calibration = CalibrationStatus::default();
+    }
+    // The next session may be on other equipment
+    if let Ok(mut trainer_data) = state.trainer_data.lock() {
+        for profile in SensorProfile::ALL {
+            profile.clear_trainer_data(&mut trainer_data);
         }
     }
 
@@ -121,6 +570,86 @@ fn is_connected(state: State<AppState>) -> bool {
     state.connected.load(Ordering::SeqCst)
 }
 
+#[tauri::command]
+fn get_trainer_info(state: State<AppState>) -> Result<TrainerInfo, String> {
+    state
+        .trainer_info
+        .lock()
+        .map(|info| info.clone())
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+fn get_sensor_status(state: State<AppState>) -> SensorStatus {
+    sensor_status(&state)
+}
+
+fn sensor_status(state: &AppState) -> SensorStatus {
+    let channel_state = |profile: SensorProfile| {
+        state
+            .channels
+            .lock()
+            .map(|channels| channels.state(profile))
+            .unwrap_or(ChannelState::Closed)
+    };
+
+    SensorStatus {
+        trainer: channel_state(SensorProfile::Fec),
+        heart_rate: channel_state(SensorProfile::Hrm),
+        stick_present: !state.stick_lost.load(Ordering::SeqCst),
+    }
+}
+
+#[tauri::command]
+fn get_stick_info(state: State<AppState>) -> Result<StickInfo, String> {
+    stick_info(&state)
+}
+
+/// Capabilities from the last connect plus the live status of each sensor
+/// channel, queried from the stick
+fn stick_info(state: &AppState) -> Result<StickInfo, String> {
+    let (capabilities, channels): (_, Vec<(SensorProfile, u8)>) = {
+        let channels = state.channels.lock().map_err(|e| e.to_string())?;
+        let allocated = channels
+            .iter()
+            .map(|a| (a.profile, a.channel.channel_number()))
+            .collect();
+        (channels.capabilities(), allocated)
+    };
+
+    let mut ant = state.ant.lock().map_err(|e| e.to_string())?;
+    let mut on_message = |msg_id: u8, channel: u8, data: &[u8]| {
+        handle_ant_message(state, msg_id, channel, data);
+    };
+
+    // The stick is closed (and answers nothing) while disconnected
+    let mut channel_info = Vec::new();
+    if state.connected.load(Ordering::SeqCst) && !state.stick_lost.load(Ordering::SeqCst) {
+        for (profile, channel_number) in channels {
+            let status = ant
+                .request_message(channel_number, MESG_CHANNEL_STATUS, &mut on_message)?
+                .and_then(|data| ChannelStatus::parse(&data));
+            let channel_id = ant
+                .request_message(channel_number, MESG_CHANNEL_ID, &mut on_message)?
+                .and_then(|data| ChannelId::parse(&data));
+
+            channel_info.push(ChannelInfo {
+                profile,
+                channel_number,
+                status,
+                channel_id,
+            });
+        }
+    }
+
+    Ok(StickInfo {
+        capabilities,
+        features: capabilities.map(|c| c.features()).unwrap_or_default(),
+        channels: channel_info,
+        framing_errors: ant.framing_errors(),
+    })
+}
+
 #[tauri::command]
 fn set_window_y(window: tauri::Window, y: i32) -> Result<(), String> {
     window
@@ -128,53 +657,321 @@ fn set_window_y(window: tauri::Window, y: i32) -> Result<(), String> {
         .map_err(|e| e.to_string())
 }
 
-#[tauri::command]
-fn poll_trainer_data(state: State<AppState>) -> Result<Option<TrainerData>, String> {
-    if !state.connected.load(Ordering::SeqCst) {
-        return Ok(None);
+/// Background reader: drains the ANT+ stick continuously, reopens channels
+/// the stick closed, and pushes `trainer-data` events to the webview at a
+/// fixed rate and `sensor-status` / `calibration-status` events on every change
+fn spawn_ant_reader(app: tauri::AppHandle) {
+    thread::spawn(move || {
+        let state = app.state::<AppState>();
+        let mut last_emit = Instant::now();
+        let mut last_status = sensor_status(&state);
+        let mut last_calibration = CalibrationStatus::default();
+        let mut recovery = StickRecovery::new();
+
+        loop {
+            if !state.connected.load(Ordering::SeqCst) {
+                thread::sleep(ANT_IDLE_INTERVAL);
+                continue;
+            }
+
+            poll_ant(&state, &mut recovery, Instant::now());
+
+            let status = sensor_status(&state);
+            if status != last_status {
+                last_status = status;
+                let _ = app.emit("sensor-status", status);
+            }
+
+            if let Ok(calibration) = state.calibration.lock() {
+                if *calibration != last_calibration {
+                    last_calibration = calibration.clone();
+                    let _ = app.emit("calibration-status", calibration.clone());
+                }
+            }
+
+            if last_emit.elapsed() >= TRAINER_DATA_EMIT_INTERVAL {
+                last_emit = Instant::now();
+                if let Ok(data) = state.trainer_data.lock() {
+                    let _ = app.emit("trainer-data", data.clone());
+                }
+            }
+        }
+    });
+}
+
+/// Read once from the stick and dispatch every complete message; returns
+/// how many there were
+fn read_ant_messages(state: &AppState) -> Result<usize, String> {
+    // Hold the transport lock only for a single read so control commands
+    // can interleave their writes
+    let messages = state
+        .ant
+        .lock()
+        .map_err(|e| e.to_string())?
+        .read_messages()?;
+
+    let count = messages.len();
+    for (msg_id, channel, data) in messages {
+        handle_ant_message(state, msg_id, channel, &data);
     }
+    Ok(count)
+}
 
-    let ant = state.ant.lock().map_err(|e| e.to_string())?;
+/// Reader-thread bookkeeping for recovering an unplugged stick
+struct StickRecovery {
+    read_failures: u32,
+    next_retry: Instant,
+}
 
-    // Read data from USB
-    let mut buffer = [0u8; 64];
-    let bytes_read = ant.read(&mut buffer)?;
+impl StickRecovery {
+    fn new() -> Self {
+        Self {
+            read_failures: 0,
+            next_retry: Instant::now(),
+        }
+    }
+}
 
-    if bytes_read == 0 {
-        // No data available
-        let data = state.trainer_data.lock().map_err(|e| e.to_string())?;
-        return Ok(Some(data.clone()));
+/// One reader iteration while connected: read and dispatch messages, or
+/// bring an unplugged stick back without touching the workout recording
+fn poll_ant(state: &AppState, recovery: &mut StickRecovery, now: Instant) {
+    if state.stick_left.swap(false, Ordering::SeqCst) && !state.stick_lost.load(Ordering::SeqCst) {
+        mark_stick_lost(state);
+        recovery.next_retry = now + STICK_RETRY_INTERVAL;
     }
 
-    // Parse received ANT+ message
-    if let Some((msg_id, channel, data)) = AntChannel::parse_message(&buffer[..bytes_read]) {
-        // Check if it's broadcast data (0x4E)
-        if msg_id == 0x4E && data.len() >= 8 {
-            let mut trainer_data = state.trainer_data.lock().map_err(|e| e.to_string())?;
+    if state.stick_lost.load(Ordering::SeqCst) {
+        let arrived = state.stick_arrived.swap(false, Ordering::SeqCst);
+        if !arrived && now < recovery.next_retry {
+            thread::sleep(ANT_IDLE_INTERVAL);
+            return;
+        }
 
-            match channel {
-                0 => {
-                    // Channel 0: FE-C (trainer) data
-                    if let Some(page) = FecParser::parse_data_page(&data[1..9]) {
-                        FecParser::update_trainer_data(&mut trainer_data, &page);
-                    }
+        match open_channels(state) {
+            Ok(()) => {
+                state.stick_lost.store(false, Ordering::SeqCst);
+                recovery.read_failures = 0;
+                println!("ANT+ stick re-opened, channels restored");
+            }
+            Err(e) => {
+                eprintln!("ANT+ stick re-open failed: {}", e);
+                if let Ok(mut ant) = state.ant.lock() {
+                    ant.close();
                 }
-                1 => {
-                    // Channel 1: HRM (heart rate) data
-                    if let Some(hr) = HrmParser::parse_heart_rate(&data[1..9]) {
-                        trainer_data.heart_rate = hr;
-                    }
+                recovery.next_retry = now + STICK_RETRY_INTERVAL;
+            }
+        }
+        return;
+    }
+
+    match read_ant_messages(state) {
+        Ok(count) => {
+            recovery.read_failures = 0;
+            if count == 0 {
+                thread::sleep(ANT_READ_IDLE_DELAY);
+            }
+        }
+        Err(e) => {
+            eprintln!("ANT+ read failed: {}", e);
+            recovery.read_failures += 1;
+            if recovery.read_failures >= READ_FAILURES_BEFORE_LOST {
+                mark_stick_lost(state);
+                recovery.next_retry = now + STICK_RETRY_INTERVAL;
+            } else {
+                thread::sleep(ANT_IDLE_INTERVAL);
+            }
+        }
+    }
+
+    if let Err(e) = reopen_lost_channels(state, now) {
+        eprintln!("ANT+ channel reopen failed: {}", e);
+    }
+
+    if let Err(e) = request_trainer_info(state, now) {
+        eprintln!("FE-C info request failed: {}", e);
+    }
+
+    if let Ok(mut calibration) = state.calibration.lock() {
+        if calibration.expire(now) {
+            eprintln!("Trainer calibration timed out");
+        }
+    }
+}
+
+/// Once the trainer is tracked, ask it (Page 70) for the capability and
+/// identity pages it hasn't broadcast on its own
+fn request_trainer_info(state: &AppState, now: Instant) -> Result<(), String> {
+    let tracking = state
+        .channels
+        .lock()
+        .map(|channels| channels.state(SensorProfile::Fec) == ChannelState::Tracking)
+        .unwrap_or(false);
+    if !tracking {
+        return Ok(());
+    }
+
+    let pages = state
+        .trainer_info
+        .lock()
+        .map_err(|e| e.to_string())?
+        .due_requests(now);
+    if pages.is_empty() {
+        return Ok(());
+    }
+
+    run_fec_control(state, |controller, ant, on_message| {
+        controller.request_pages(ant, &pages, on_message)
+    })
+}
+
+/// The stick is gone: close it, mark every sensor lost and clear readings
+fn mark_stick_lost(state: &AppState) {
+    println!("ANT+ stick lost, waiting for it to come back");
+    state.stick_lost.store(true, Ordering::SeqCst);
+
+    if let Ok(mut ant) = state.ant.lock() {
+        ant.close();
+    }
+
+    if let Ok(mut channels) = state.channels.lock() {
+        for allocation in channels.iter_mut() {
+            allocation.monitor.lost();
+        }
+    }
+
+    if let Ok(mut trainer_data) = state.trainer_data.lock() {
+        *trainer_data = TrainerData::default();
+    }
+}
+
+/// Reopen channels the stick closed after a search timeout
+fn reopen_lost_channels(state: &AppState, now: Instant) -> Result<(), String> {
+    let messages: Vec<Vec<u8>> = {
+        let mut channels = state.channels.lock().map_err(|e| e.to_string())?;
+        channels
+            .iter_mut()
+            .filter_map(|allocation| {
+                if !allocation.monitor.take_reopen(now) {
+                    return None;
                 }
-                _ => {}
+                println!(
+                    "Reopening ANT+ channel {}",
+                    allocation.channel.channel_number()
+                );
+                Some(allocation.channel.open_channel())
+            })
+            .collect()
+    };
+
+    // The allocator lock is released before taking the transport lock
+    if !messages.is_empty() {
+        let mut ant = state.ant.lock().map_err(|e| e.to_string())?;
+        for msg in messages {
+            ant.write(&msg)?;
+        }
+    }
+    Ok(())
+}
+
+/// Follow the channel state; a lost sensor's readings are cleared so the
+/// UI doesn't show stale values
+fn track_channel_state(
+    state: &AppState,
+    profile: SensorProfile,
+    msg_id: u8,
+    channel: u8,
+    data: &[u8],
+) {
+    let Some(new_state) = state.channels.lock().ok().and_then(|mut channels| {
+        channels
+            .for_channel_mut(channel)?
+            .monitor
+            .handle_message(msg_id, data, Instant::now())
+    }) else {
+        return;
+    };
+
+    println!(
+        "ANT+ {} channel {} {:?}",
+        profile.name(),
+        channel,
+        new_state
+    );
+    if new_state != ChannelState::Lost {
+        return;
+    }
+
+    if let Ok(mut trainer_data) = state.trainer_data.lock() {
+        profile.clear_trainer_data(&mut trainer_data);
+    }
+}
+
+/// Route a received ANT+ message to the parser of the profile on its channel
+fn handle_ant_message(state: &AppState, msg_id: u8, channel: u8, data: &[u8]) {
+    let Some(profile) = state
+        .channels
+        .lock()
+        .ok()
+        .and_then(|channels| channels.profile_for(channel))
+    else {
+        return;
+    };
+
+    track_channel_state(state, profile, msg_id, channel, data);
+
+    // Only broadcast data (0x4E) carries sensor pages: [channel][8-byte page]
+    if msg_id != MESG_BROADCAST_DATA || data.len() < 9 {
+        return;
+    }
+    let page = &data[1..9];
+
+    let fec_page = match profile {
+        SensorProfile::Fec => update_fec_data(state, channel, page),
+        _ => {
+            if let Ok(mut trainer_data) = state.trainer_data.lock() {
+                profile.update_trainer_data(&mut trainer_data, page);
             }
+            None
+        }
+    };
 
-            return Ok(Some(trainer_data.clone()));
+    // A wildcard channel's device is only reported; the user pairs it from
+    // the Devices panel
+    let extended = ExtendedData::parse(data);
+    if let Some(device_number) = extended.and_then(|e| e.device_number) {
+        if let Ok(mut trainer_data) = state.trainer_data.lock() {
+            profile.set_device_number(&mut trainer_data, Some(device_number));
         }
     }
 
-    // Return current data if parsing failed
-    let data = state.trainer_data.lock().map_err(|e| e.to_string())?;
-    Ok(Some(data.clone()))
+    if let Some(page) = &fec_page {
+        // Page 54 also tells which control modes the trainer supports
+        if let Ok(mut info) = state.trainer_info.lock() {
+            info.update(page);
+        }
+        if let Ok(mut calibration) = state.calibration.lock() {
+            calibration.update(page, Instant::now());
+        }
+    }
+}
+
+/// Run an FE-C page through its channel's parser, which carries average
+/// power, distance and elapsed time across pages
+fn update_fec_data(state: &AppState, channel: u8, page: &[u8]) -> Option<FecDataPage> {
+    let mut parsers = state.fec_parsers.lock().ok()?;
+    let parser = parsers.entry(channel).or_insert_with(|| {
+        let mut parser = FecParser::new();
+        if let Ok(circumference) = state.wheel_circumference.lock() {
+            parser.set_wheel_circumference(*circumference);
+        }
+        parser
+    });
+    let parsed = parser.parse_broadcast(page)?;
+
+    if let Ok(mut trainer_data) = state.trainer_data.lock() {
+        parser.apply(&mut trainer_data, &parsed);
+    }
+    Some(parsed)
 }
 
 #[tauri::command]
@@ -225,9 +1022,15 @@ fn start_workout(state: State<AppState>) -> Result<(), String> {
 #[tauri::command]
 fn add_workout_sample(state: State<AppState>) -> Result<(), String> {
     let trainer_data = state.trainer_data.lock().map_err(|e| e.to_string())?.clone();
+    let trainer_info = state
+        .trainer_info
+        .lock()
+        .map_err(|e| e.to_string())?
+        .clone();
     let mut workout = state.workout.lock().map_err(|e| e.to_string())?;
     if let Some(ref mut recorder) = *workout {
         recorder.add_sample(&trainer_data);
+        recorder.note_devices(&trainer_data, &trainer_info);
     }
     Ok(())
 }
@@ -254,26 +1057,104 @@ fn stop_workout(state: State<AppState>) -> Result<WorkoutSummary, String> {
     }
 }
 
+/// Simulated or replayed stick: nothing to find, select or unplug
+fn virtual_stick() -> bool {
+    let simulated =
+        cfg!(feature = "simulated-ant") && std::env::var_os(SIMULATED_ANT_ENV).is_some();
+    simulated || std::env::var_os(REPLAY_ANT_ENV).is_some()
+}
+
+/// USB stick by default; a capture played back when NOTCH_RIDER_ANT_REPLAY is
+/// set, or in `simulated-ant` builds the simulated stick when
+/// NOTCH_RIDER_SIMULATED_ANT is
+fn ant_transport() -> Box<dyn AntTransport> {
+    if let Some(path) = std::env::var_os(REPLAY_ANT_ENV) {
+        let speed = replay_speed(std::env::var(REPLAY_SPEED_ENV).ok());
+        let replay = match ReplayTransport::from_file(Path::new(&path), speed) {
+            Ok(replay) => {
+                println!("Replaying ANT+ capture at {}x", speed);
+                replay
+            }
+            Err(e) => {
+                eprintln!("ANT+ replay unavailable: {}", e);
+                ReplayTransport::new(Vec::new(), speed)
+            }
+        };
+        return with_capture(Box::new(replay));
+    }
+
+    #[cfg(feature = "simulated-ant")]
+    if std::env::var_os(SIMULATED_ANT_ENV).is_some() {
+        println!("Using simulated ANT+ stick");
+        return with_capture(Box::new(SimulatedStick::new()));
+    }
+
+    with_capture(Box::new(AntUsb::new()))
+}
+
+/// NOTCH_RIDER_ANT_REPLAY_SPEED as a positive multiplier; real time otherwise
+fn replay_speed(value: Option<String>) -> f64 {
+    let Some(value) = value else {
+        return 1.0;
+    };
+
+    match value.parse::<f64>() {
+        Ok(speed) if speed > 0.0 => speed,
+        _ => {
+            eprintln!("Ignoring replay speed {}: must be above 0", value);
+            1.0
+        }
+    }
+}
+
+/// Record the stick's traffic when NOTCH_RIDER_ANT_CAPTURE names a file.
+/// Appends, so reselecting a stick doesn't wipe what was already captured
+fn with_capture(transport: Box<dyn AntTransport>) -> Box<dyn AntTransport> {
+    let Some(path) = std::env::var_os(CAPTURE_ANT_ENV) else {
+        return transport;
+    };
+
+    match OpenOptions::new().create(true).append(true).open(&path) {
+        Ok(file) => {
+            println!("Capturing ANT+ traffic to {}", Path::new(&path).display());
+            Box::new(RecordingTransport::new(transport, file))
+        }
+        Err(e) => {
+            eprintln!("ANT+ capture unavailable: {}", e);
+            transport
+        }
+    }
+}
+
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
     tauri::Builder::default()
         .plugin(tauri_plugin_log::Builder::new().build())
-        .manage(AppState {
-            ant: Mutex::new(AntUsb::new()),
-            trainer_data: Mutex::new(TrainerData::default()),
-            fec_channel: Mutex::new(None),
-            hrm_channel: Mutex::new(None),
-            connected: AtomicBool::new(false),
-            workout: Mutex::new(None),
-        })
         .invoke_handler(tauri::generate_handler![
             find_ant_device,
             list_usb_devices,
+            select_ant_stick,
+            list_serial_ports,
+            select_serial_ant_stick,
+            search_ant_devices,
+            scan_ant_devices,
+            get_paired_devices,
+            pair_ant_device,
+            forget_ant_device,
             get_trainer_data,
             connect_ant_device,
             disconnect_ant_device,
+            set_target_power,
+            set_grade,
+            set_resistance,
+            set_user_config,
+            set_wheel_circumference,
+            start_calibration,
+            get_calibration_status,
+            get_trainer_info,
             is_connected,
-            poll_trainer_data,
+            get_sensor_status,
+            get_stick_info,
             set_window_y,
             show_panel,
             hide_panel,
@@ -284,6 +1165,36 @@ pub fn run() {
             stop_workout,
         ])
         .setup(|app| {
+            // Pairings are kept in the platform's app data directory
+            let pairing_file = app
+                .path()
+                .app_data_dir()
+                .map(|dir| dir.join(PAIRING_FILE_NAME))
+                .ok();
+            let paired = pairing_file
+                .as_deref()
+                .map(PairedDevices::load)
+                .unwrap_or_default();
+            app.manage(
+                AppState::new(ant_transport())
+                    .with_paired_devices(paired)
+                    .with_pairing_file(pairing_file),
+            );
+
+            spawn_ant_reader(app.handle().clone());
+
+            // A simulated or replayed stick can't be unplugged
+            if !virtual_stick() {
+                let handle = app.handle().clone();
+                spawn_stick_watcher(move |event| {
+                    let state = handle.state::<AppState>();
+                    match event {
+                        StickEvent::Arrived => state.stick_arrived.store(true, Ordering::SeqCst),
+                        StickEvent::Left => state.stick_left.store(true, Ordering::SeqCst),
+                    }
+                });
+            }
+
             let window = app
                 .get_webview_window("main")
                 .ok_or("Main window not found")?;
@@ -331,3 +1242,402 @@ pub fn run() {
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ant::calibration::CalibrationPhase;
+    use ant::channel::{
+        RadioState, EVENT_RX_FAIL_GO_TO_SEARCH, FEC_DEVICE_TYPE, MESG_EVENT_ID, MESG_RESPONSE_EVENT,
+    };
+    use ant::sim::{SimDevice, SimulatedStick};
+
+    fn connected_state() -> AppState {
+        let state = AppState::new(Box::new(SimulatedStick::new()));
+        assert!(connect_ant(&state).unwrap());
+        state
+    }
+
+    fn read_until(state: &AppState, done: impl Fn(&TrainerData) -> bool) -> TrainerData {
+        for _ in 0..50 {
+            read_ant_messages(state).unwrap();
+            let data = state.trainer_data.lock().unwrap().clone();
+            if done(&data) {
+                return data;
+            }
+        }
+        panic!("simulated stick did not deliver the expected data");
+    }
+
+    #[test]
+    fn test_connect_and_parse_simulated_pages() {
+        let state = connected_state();
+        assert!(state.connected.load(Ordering::SeqCst));
+
+        let data = read_until(&state, |d| d.power > 0 && d.speed > 0.0 && d.heart_rate > 0);
+
+        assert_eq!(data.power, 200);
+        assert_eq!(data.cadence, 90);
+        assert_eq!(data.heart_rate, 140);
+        assert!((data.speed - 30.0).abs() < 0.1);
+
+        let status = sensor_status(&state);
+        assert_eq!(status.trainer, ChannelState::Tracking);
+        assert_eq!(status.heart_rate, ChannelState::Tracking);
+    }
+
+    #[test]
+    fn test_trainer_totals_and_average_power_accumulate_across_pages() {
+        let stick = SimulatedStick::new().with_fec_pages(vec![
+            [0x10, 25, 0, 0, 0x8D, 0x20, 0xFF, 0x34],
+            [0x19, 1, 90, 0xC8, 0x00, 0xC8, 0x00, 0x00],
+            [0x10, 25, 8, 20, 0x8D, 0x20, 0xFF, 0x34],
+            [0x19, 3, 90, 0x6C, 0x02, 0xD2, 0x00, 0x00],
+        ]);
+        let state = AppState::new(Box::new(stick));
+        connect_ant(&state).unwrap();
+
+        let data = read_until(&state, |d| d.average_power > 0);
+
+        // 420W over two events: 200W then 220W
+        assert_eq!(data.average_power, 210);
+        assert_eq!(data.power, 210);
+        assert_eq!(data.trainer_distance, 20);
+        assert_eq!(data.trainer_elapsed_time, 2.0);
+
+        disconnect_ant(&state).unwrap();
+        assert!(state.fec_parsers.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_dropped_heart_rate_strap_is_lost_and_cleared() {
+        let state = connected_state();
+        read_until(&state, |d| d.heart_rate > 0);
+
+        let dropped = [1, MESG_EVENT_ID, EVENT_RX_FAIL_GO_TO_SEARCH];
+        handle_ant_message(&state, MESG_RESPONSE_EVENT, 1, &dropped);
+
+        assert_eq!(sensor_status(&state).heart_rate, ChannelState::Lost);
+        assert_eq!(state.trainer_data.lock().unwrap().heart_rate, 0);
+    }
+
+    #[test]
+    fn test_set_target_power_through_simulated_stick() {
+        let state = connected_state();
+
+        run_fec_control(&state, |controller, ant, on_message| {
+            controller.set_target_power(ant, 250.0, on_message)
+        })
+        .unwrap();
+
+        // Data keeps flowing after the command exchange
+        read_until(&state, |d| d.power > 0);
+    }
+
+    #[test]
+    fn test_spin_down_calibration_through_simulated_stick() {
+        let state = connected_state();
+
+        let status = request_calibration(&state, CalibrationKind::SpinDown).unwrap();
+        assert!(status.is_active());
+
+        for _ in 0..50 {
+            if !state.calibration.lock().unwrap().is_active() {
+                break;
+            }
+            read_ant_messages(&state).unwrap();
+        }
+
+        let status = state.calibration.lock().unwrap().clone();
+        assert_eq!(status.phase, CalibrationPhase::Succeeded);
+        assert_eq!(status.spin_down_time_ms, Some(4800));
+        assert_eq!(status.zero_offset, None);
+
+        disconnect_ant(&state).unwrap();
+        assert_eq!(
+            state.calibration.lock().unwrap().phase,
+            CalibrationPhase::Idle
+        );
+    }
+
+    #[test]
+    fn test_trainer_info_is_requested_once_tracking() {
+        let state = connected_state();
+
+        // Nothing is requested before the trainer is heard
+        request_trainer_info(&state, Instant::now()).unwrap();
+        assert!(state.trainer_info.lock().unwrap().capabilities.is_none());
+
+        read_until(&state, |d| d.power > 0);
+        request_trainer_info(&state, Instant::now()).unwrap();
+        for _ in 0..50 {
+            let missing = state.trainer_info.lock().unwrap().missing_pages();
+            if missing.is_empty() {
+                break;
+            }
+            read_ant_messages(&state).unwrap();
+        }
+
+        let info = state.trainer_info.lock().unwrap().clone();
+        let capabilities = info.capabilities.unwrap();
+        assert_eq!(capabilities.max_resistance, 2000);
+        assert!(capabilities.supports_target_power);
+        assert_eq!(info.manufacturer_id, Some(32));
+        assert_eq!(info.model_number, Some(2620));
+        assert_eq!(info.serial_number, Some(1234567));
+        assert_eq!(info.firmware.as_deref(), Some("2.5"));
+
+        disconnect_ant(&state).unwrap();
+        assert!(state.trainer_info.lock().unwrap().capabilities.is_none());
+    }
+
+    #[test]
+    fn test_wheel_circumference_applies_to_torque_pages() {
+        let state = AppState::new(Box::new(SimulatedStick::new()));
+        assert!(update_wheel_circumference(&state, 10.0).is_err());
+        update_wheel_circumference(&state, 2.2).unwrap();
+
+        // Two revolutions in 0.5s
+        update_fec_data(&state, 0, &[0x1A, 1, 10, 0x00, 0x00, 0x00, 0x00, 0x30]);
+        update_fec_data(&state, 0, &[0x1A, 3, 12, 0x00, 0x04, 0x80, 0x02, 0x30]);
+
+        let data = state.trainer_data.lock().unwrap().clone();
+        assert!((data.wheel_speed.unwrap() - 31.68).abs() < 0.01);
+        assert_eq!(data.torque, Some(10.0));
+    }
+
+    #[test]
+    fn test_calibration_requires_connection() {
+        let state = AppState::new(Box::new(SimulatedStick::new()));
+        assert!(request_calibration(&state, CalibrationKind::ZeroOffset).is_err());
+        assert!(!state.calibration.lock().unwrap().is_active());
+    }
+
+    fn two_trainer_stick() -> SimulatedStick {
+        SimulatedStick::new().with_devices(vec![
+            SimDevice::new(FEC_DEVICE_TYPE, 111, 0x05, -50),
+            SimDevice::new(FEC_DEVICE_TYPE, 222, 0x05, -70),
+        ])
+    }
+
+    fn paired_trainer(device_number: u16) -> PairedDevices {
+        PairedDevices {
+            fec: Some(PairedDevice {
+                device_number,
+                transmission_type: 0x05,
+            }),
+            hrm: None,
+        }
+    }
+
+    #[test]
+    fn test_search_lists_every_trainer() {
+        let state = AppState::new(Box::new(two_trainer_stick()));
+
+        let devices = search_devices(&state, Duration::from_secs(5)).unwrap();
+        let numbers: Vec<u16> = devices.iter().map(|d| d.device_number).collect();
+
+        assert_eq!(numbers, vec![111, 222]);
+        assert!(!state.connected.load(Ordering::SeqCst));
+    }
+
+    #[test]
+    fn test_search_refused_while_connected() {
+        let state = connected_state();
+        assert!(search_devices(&state, Duration::from_secs(1)).is_err());
+        assert!(scan_devices(&state, Duration::from_secs(1), |_| {}).is_err());
+    }
+
+    #[test]
+    fn test_connect_uses_paired_trainer() {
+        let state =
+            AppState::new(Box::new(two_trainer_stick())).with_paired_devices(paired_trainer(222));
+        connect_ant(&state).unwrap();
+
+        read_until(&state, |d| d.power > 0);
+        let channels = state.channels.lock().unwrap();
+        let trainer = channels.get(SensorProfile::Fec).unwrap();
+        assert_eq!(trainer.channel.device_number(), 222);
+    }
+
+    #[test]
+    fn test_wildcard_connect_reports_devices_without_pairing_them() {
+        let path = std::env::temp_dir()
+            .join(format!("notchrider-unpaired-{}", std::process::id()))
+            .join("devices.json");
+        let state =
+            AppState::new(Box::new(SimulatedStick::new())).with_pairing_file(Some(path.clone()));
+        connect_ant(&state).unwrap();
+
+        let data = read_until(&state, |d| {
+            d.trainer_device.is_some() && d.heart_rate_device.is_some()
+        });
+        assert_eq!(data.trainer_device, Some(12345));
+        assert_eq!(data.heart_rate_device, Some(54321));
+
+        // Nearby sensors may not be the rider's, so nothing is saved unasked
+        assert!(!path.exists());
+        assert_eq!(
+            *state.paired_devices.lock().unwrap(),
+            PairedDevices::default()
+        );
+    }
+
+    #[test]
+    fn test_paired_trainer_out_of_range_is_lost_and_reopened() {
+        let state =
+            AppState::new(Box::new(two_trainer_stick())).with_paired_devices(paired_trainer(333));
+        connect_ant(&state).unwrap();
+
+        for _ in 0..20 {
+            read_ant_messages(&state).unwrap();
+        }
+        assert_eq!(state.trainer_data.lock().unwrap().power, 0);
+
+        // The search timed out and the stick closed the channel
+        assert_eq!(sensor_status(&state).trainer, ChannelState::Lost);
+
+        reopen_lost_channels(&state, Instant::now() + Duration::from_secs(2)).unwrap();
+        assert_eq!(sensor_status(&state).trainer, ChannelState::Searching);
+        let channels = state.channels.lock().unwrap();
+        let trainer = channels.get(SensorProfile::Fec).unwrap();
+        assert_eq!(trainer.monitor.reopen_count(), 1);
+    }
+
+    #[test]
+    fn test_unplugged_stick_is_reopened_without_ending_workout() {
+        let stick = SimulatedStick::new();
+        let plugged = stick.plug_switch();
+        let state = AppState::new(Box::new(stick));
+        connect_ant(&state).unwrap();
+        read_until(&state, |d| d.power > 0);
+        *state.workout.lock().unwrap() = Some(WorkoutRecorder::new());
+
+        plugged.store(false, Ordering::SeqCst);
+        let mut recovery = StickRecovery::new();
+        let now = Instant::now();
+        for _ in 0..READ_FAILURES_BEFORE_LOST {
+            poll_ant(&state, &mut recovery, now);
+        }
+
+        let status = sensor_status(&state);
+        assert!(!status.stick_present);
+        assert_eq!(status.trainer, ChannelState::Lost);
+        assert_eq!(status.heart_rate, ChannelState::Lost);
+        assert_eq!(state.trainer_data.lock().unwrap().power, 0);
+
+        // Still missing: the retry fails and waits for the next interval
+        poll_ant(&state, &mut recovery, now + STICK_RETRY_INTERVAL);
+        assert!(!sensor_status(&state).stick_present);
+
+        plugged.store(true, Ordering::SeqCst);
+        state.stick_arrived.store(true, Ordering::SeqCst);
+        poll_ant(&state, &mut recovery, now + STICK_RETRY_INTERVAL);
+
+        assert!(sensor_status(&state).stick_present);
+        assert!(state.connected.load(Ordering::SeqCst));
+        read_until(&state, |d| d.power > 0 && d.heart_rate > 0);
+        assert!(state.workout.lock().unwrap().is_some());
+    }
+
+    #[test]
+    fn test_hotplug_left_marks_stick_lost() {
+        let state = connected_state();
+        state.stick_left.store(true, Ordering::SeqCst);
+
+        let mut recovery = StickRecovery::new();
+        poll_ant(&state, &mut recovery, Instant::now());
+
+        assert!(!sensor_status(&state).stick_present);
+        assert_eq!(sensor_status(&state).trainer, ChannelState::Lost);
+    }
+
+    #[test]
+    fn test_replayed_capture_drives_trainer_data() {
+        let capture = include_str!("ant/testdata/fec_session.cap");
+        let entries = ant::capture::parse_capture(capture).unwrap();
+        let state = AppState::new(Box::new(ReplayTransport::new(entries, f64::INFINITY)));
+        connect_ant(&state).unwrap();
+
+        let data = read_until(&state, |d| d.power > 0 && d.speed > 0.0 && d.heart_rate > 0);
+
+        assert_eq!(data.power, 200);
+        assert_eq!(data.cadence, 90);
+        assert_eq!(data.heart_rate, 140);
+        assert_eq!(sensor_status(&state).trainer, ChannelState::Tracking);
+    }
+
+    #[test]
+    fn test_replay_speed_must_be_positive() {
+        assert_eq!(replay_speed(None), 1.0);
+        assert_eq!(replay_speed(Some("10".to_string())), 10.0);
+        assert_eq!(replay_speed(Some("0".to_string())), 1.0);
+        assert_eq!(replay_speed(Some("-2".to_string())), 1.0);
+        assert_eq!(replay_speed(Some("fast".to_string())), 1.0);
+    }
+
+    #[test]
+    fn test_sensors_beyond_stick_channel_count_stay_closed() {
+        let state = AppState::new(Box::new(SimulatedStick::new().with_max_channels(1)));
+        connect_ant(&state).unwrap();
+
+        read_until(&state, |d| d.power > 0);
+        let status = sensor_status(&state);
+        assert_eq!(status.trainer, ChannelState::Tracking);
+        assert_eq!(status.heart_rate, ChannelState::Closed);
+    }
+
+    #[test]
+    fn test_disconnect_frees_channels() {
+        let state = connected_state();
+        read_until(&state, |d| d.power > 0);
+
+        disconnect_ant(&state).unwrap();
+
+        assert_eq!(state.channels.lock().unwrap().profile_for(0), None);
+        assert_eq!(sensor_status(&state).trainer, ChannelState::Closed);
+    }
+
+    #[test]
+    fn test_disconnect_clears_equipment_readings() {
+        let state = connected_state();
+        read_until(&state, |d| d.equipment.is_some());
+
+        disconnect_ant(&state).unwrap();
+
+        let data = state.trainer_data.lock().unwrap().clone();
+        assert_eq!(data.equipment, None);
+        assert_eq!(data.power, 0);
+        assert_eq!(data.heart_rate, 0);
+    }
+
+    #[test]
+    fn test_stick_info_reports_capabilities_and_paired_devices() {
+        let state = connected_state();
+        read_until(&state, |d| d.power > 0 && d.heart_rate > 0);
+
+        let info = stick_info(&state).unwrap();
+
+        assert_eq!(info.capabilities.unwrap().max_channels, 8);
+        assert!(info.features.contains(&"extended messages"));
+        assert_eq!(info.framing_errors, 0);
+        assert_eq!(info.channels.len(), 2);
+
+        let trainer = &info.channels[0];
+        assert_eq!(trainer.profile, SensorProfile::Fec);
+        assert_eq!(trainer.status.unwrap().state, RadioState::Tracking);
+        assert_eq!(trainer.channel_id.unwrap().device_number, 12345);
+        assert_eq!(info.channels[1].channel_id.unwrap().device_number, 54321);
+    }
+
+    #[test]
+    fn test_control_requires_connection() {
+        let state = AppState::new(Box::new(SimulatedStick::new()));
+
+        let result = run_fec_control(&state, |controller, ant, on_message| {
+            controller.set_target_power(ant, 250.0, on_message)
+        });
+
+        assert!(result.is_err());
+    }
+}
@@ -1,8 +1,12 @@
 /// Workout recorder — captures trainer data samples and produces FIT files.
 
+use crate::ant::control::TrainerInfo;
+use crate::ant::fec::Equipment;
+use crate::ant::manufacturer::device_name;
+use crate::ant::profile::SensorProfile;
 use crate::ant::TrainerData;
 use crate::fit::encoder::FitEncoder;
-use crate::fit::messages::{self, LapData, SessionData};
+use crate::fit::messages::{self, DeviceInfoData, LapData, RecordData, SessionData};
 use crate::fit::types;
 use serde::Serialize;
 use std::fs;
@@ -17,6 +21,21 @@ struct Sample {
     cadence: u8,
     speed_mps_1000: u16,  // speed in m/s * 1000
     distance_cm: u32,     // cumulative distance in cm
+    grade: i16,           // incline in % * 100
+    resistance: u8,       // 0-254 of the trainer's maximum
+    calories: u16,        // trainer-computed kcal since the start
+}
+
+/// A sensor that contributed to the workout.
+#[derive(Debug, Clone, PartialEq)]
+struct Device {
+    device_type: u8,                // ANT+ device type
+    device_number: u16,
+    manufacturer: Option<u16>,
+    product: Option<u16>,           // model number
+    serial_number: Option<u32>,
+    software_version: Option<u16>,  // version * 100
+    name: Option<String>,
 }
 
 /// Records a workout and produces a FIT file.
@@ -26,6 +45,11 @@ pub struct WorkoutRecorder {
     cumulative_distance: f64, // meters
     paused: bool,
     pause_duration: f64, // total seconds paused
+    start_calories: Option<u32>, // trainer calorie count at the first sample
+    last_calories: Option<u32>,  // trainer calorie count at the latest sample
+    earlier_calories: u32,       // kcal counted before the trainer's count restarted
+    devices: Vec<Device>,        // trainer and HR strap, as last identified
+    equipment: Option<Equipment>, // as reported by the trainer, for the FIT sport
 }
 
 /// Summary returned to the frontend after saving.
@@ -56,6 +80,11 @@ impl WorkoutRecorder {
             cumulative_distance: 0.0,
             paused: false,
             pause_duration: 0.0,
+            start_calories: None,
+            last_calories: None,
+            earlier_calories: 0,
+            devices: Vec::new(),
+            equipment: None,
         }
     }
 
@@ -76,10 +105,31 @@ impl WorkoutRecorder {
             .as_secs();
         let fit_ts = types::unix_to_fit_timestamp(now);
 
+        if data.equipment.is_some() {
+            self.equipment = data.equipment;
+        }
+
         // Accumulate distance from speed (km/h -> m/s * 1 sec)
         let speed_mps = data.speed as f64 / 3.6;
         self.cumulative_distance += speed_mps; // 1 second interval
 
+        // The trainer counts calories from the connect, not the workout start
+        let calories = match data.calories {
+            Some(total) => {
+                // A reconnect restarts the count from 0: bank what was counted so far
+                if let Some(last) = self.last_calories.filter(|&last| total < last) {
+                    self.earlier_calories += last.saturating_sub(self.start_calories.unwrap_or(0));
+                    self.start_calories = Some(0);
+                }
+                self.last_calories = Some(total);
+
+                let start = *self.start_calories.get_or_insert(total);
+                (self.earlier_calories + total.saturating_sub(start))
+                    .min(types::UINT16_INVALID as u32 - 1) as u16
+            }
+            None => types::UINT16_INVALID,
+        };
+
         self.samples.push(Sample {
             timestamp: fit_ts,
             power: data.power,
@@ -87,9 +137,66 @@ impl WorkoutRecorder {
             cadence: data.cadence,
             speed_mps_1000: (speed_mps * 1000.0) as u16,
             distance_cm: (self.cumulative_distance * 100.0) as u32,
+            grade: data
+                .incline
+                .map(|percent| (percent * 100.0) as i16)
+                .unwrap_or(types::SINT16_INVALID),
+            resistance: data
+                .resistance_level
+                .map(|percent| (percent * 2.54).round() as u8)
+                .unwrap_or(types::UINT8_INVALID),
+            calories,
         });
     }
 
+    /// Note the trainer and HR strap behind the samples, for the FIT
+    /// device_info messages. Called with each sample, as Page 80/81 and
+    /// HRM Page 2 can arrive well after the workout started.
+    pub fn note_devices(&mut self, data: &TrainerData, info: &TrainerInfo) {
+        if let Some(device_number) = data.trainer_device {
+            self.note_device(Device {
+                device_type: SensorProfile::Fec.device_type(),
+                device_number,
+                manufacturer: info.manufacturer_id,
+                product: info.model_number,
+                serial_number: info.serial_number,
+                software_version: info.software_version(),
+                name: info.name.clone(),
+            });
+        }
+        if let Some(device_number) = data.heart_rate_device {
+            self.note_device(Device {
+                device_type: SensorProfile::Hrm.device_type(),
+                device_number,
+                manufacturer: data.heart_rate_manufacturer,
+                product: None,
+                serial_number: None,
+                software_version: None,
+                name: data.heart_rate_manufacturer.and_then(|id| device_name(id, None)),
+            });
+        }
+    }
+
+    fn note_device(&mut self, device: Device) {
+        let known = self.devices.iter_mut().find(|d| {
+            d.device_type == device.device_type && d.device_number == device.device_number
+        });
+        match known {
+            Some(known) => *known = device,
+            None => self.devices.push(device),
+        }
+    }
+
+    /// Trainer-computed calories for the workout, if the trainer reported any
+    fn total_calories(&self) -> u16 {
+        self.samples
+            .iter()
+            .rev()
+            .map(|s| s.calories)
+            .find(|&c| c != types::UINT16_INVALID)
+            .unwrap_or(types::UINT16_INVALID)
+    }
+
     /// Encode all samples into a FIT file binary.
     fn encode_fit(&self) -> Vec<u8> {
         let mut encoder = FitEncoder::new();
@@ -100,51 +207,80 @@ impl WorkoutRecorder {
         // 1. File ID
         messages::write_file_id(&mut encoder, self.start_time, serial);
 
-        // 2. Event: timer start
+        // 2. Device info (index 0 is this app, the file's creator)
+        for (i, device) in self.devices.iter().enumerate() {
+            messages::write_device_info(
+                &mut encoder,
+                &DeviceInfoData {
+                    timestamp: self.start_time,
+                    device_index: (i + 1) as u8,
+                    device_type: device.device_type,
+                    device_number: device.device_number,
+                    manufacturer: device.manufacturer.unwrap_or(types::UINT16_INVALID),
+                    product: device.product.unwrap_or(types::UINT16_INVALID),
+                    serial_number: device.serial_number.unwrap_or(types::UINT32Z_INVALID),
+                    software_version: device.software_version.unwrap_or(types::UINT16_INVALID),
+                    product_name: device.name.clone().unwrap_or_default(),
+                },
+            );
+        }
+
+        // 3. Event: timer start
         messages::write_event(&mut encoder, self.start_time, types::EVENT_TYPE_START);
 
-        // 3. Records
+        // 4. Records
         for (i, sample) in self.samples.iter().enumerate() {
             messages::write_record(
                 &mut encoder,
-                sample.timestamp,
-                sample.power,
-                sample.heart_rate,
-                sample.cadence,
-                sample.speed_mps_1000,
-                sample.distance_cm,
+                &RecordData {
+                    timestamp: sample.timestamp,
+                    power: sample.power,
+                    heart_rate: sample.heart_rate,
+                    cadence: sample.cadence,
+                    speed: sample.speed_mps_1000,
+                    distance: sample.distance_cm,
+                    grade: sample.grade,
+                    resistance: sample.resistance,
+                    calories: sample.calories,
+                },
                 i == 0,
             );
         }
 
-        // 4. Event: timer stop
+        // 5. Event: timer stop
         messages::write_event(&mut encoder, end_time, types::EVENT_TYPE_STOP_ALL);
 
         // Compute summary stats
         let stats = self.compute_stats();
+        let (sport, sub_sport) = fit_sport(self.equipment);
 
-        // 5. Lap
+        // 6. Lap
         messages::write_lap(&mut encoder, &LapData {
             timestamp: end_time,
             start_time: self.start_time,
             total_elapsed_time: stats.elapsed_time,
             total_timer_time: stats.timer_time,
             total_distance: self.cumulative_distance,
+            sport,
+            sub_sport,
             avg_power: stats.avg_power,
             max_power: stats.max_power,
             avg_heart_rate: stats.avg_hr,
             max_heart_rate: stats.max_hr,
             avg_cadence: stats.avg_cadence,
             max_cadence: stats.max_cadence,
+            total_calories: self.total_calories(),
         });
 
-        // 6. Session
+        // 7. Session
         messages::write_session(&mut encoder, &SessionData {
             timestamp: end_time,
             start_time: self.start_time,
             total_elapsed_time: stats.elapsed_time,
             total_timer_time: stats.timer_time,
             total_distance: self.cumulative_distance,
+            sport,
+            sub_sport,
             avg_power: stats.avg_power,
             max_power: stats.max_power,
             avg_heart_rate: stats.avg_hr,
@@ -153,9 +289,10 @@ impl WorkoutRecorder {
             max_cadence: stats.max_cadence,
             avg_speed: stats.avg_speed,
             max_speed: stats.max_speed,
+            total_calories: self.total_calories(),
         });
 
-        // 7. Activity
+        // 8. Activity
         messages::write_activity(&mut encoder, end_time, stats.timer_time);
 
         encoder.finish()
@@ -268,6 +405,27 @@ struct Stats {
     max_speed: u16,
 }
 
+/// FIT sport and sub-sport for the equipment; bikes and unknown equipment
+/// record as indoor cycling
+fn fit_sport(equipment: Option<Equipment>) -> (u8, u8) {
+    match equipment {
+        Some(Equipment::Treadmill) => (types::SPORT_RUNNING, types::SUB_SPORT_TREADMILL),
+        Some(Equipment::Elliptical) => {
+            (types::SPORT_FITNESS_EQUIPMENT, types::SUB_SPORT_ELLIPTICAL)
+        }
+        Some(Equipment::Rower) => (types::SPORT_ROWING, types::SUB_SPORT_INDOOR_ROWING),
+        Some(Equipment::Climber) => (
+            types::SPORT_FITNESS_EQUIPMENT,
+            types::SUB_SPORT_STAIR_CLIMBING,
+        ),
+        Some(Equipment::NordicSkier) => (
+            types::SPORT_CROSS_COUNTRY_SKIING,
+            types::SUB_SPORT_INDOOR_SKIING,
+        ),
+        _ => (types::SPORT_CYCLING, types::SUB_SPORT_INDOOR_CYCLING),
+    }
+}
+
 fn workout_dir() -> Result<PathBuf, String> {
     let home = std::env::var("HOME").map_err(|_| "HOME not set".to_string())?;
     Ok(PathBuf::from(home)
@@ -318,6 +476,7 @@ fn days_to_ymd(days: u64) -> (u64, u64, u64) {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::ant::fec::FecParser;
 
     #[test]
     fn test_days_to_ymd() {
@@ -351,6 +510,97 @@ mod tests {
         assert_eq!(recorder.samples.len(), 2);
     }
 
+    #[test]
+    fn test_recorder_keeps_trainer_settings_and_calories() {
+        let mut recorder = WorkoutRecorder::new();
+        let mut data = TrainerData::new(200, 30.0, 90, 140);
+
+        recorder.add_sample(&data);
+        assert_eq!(recorder.samples[0].grade, types::SINT16_INVALID);
+        assert_eq!(recorder.total_calories(), types::UINT16_INVALID);
+
+        data.incline = Some(2.5);
+        data.resistance_level = Some(50.0);
+        data.calories = Some(120);
+        recorder.add_sample(&data);
+        data.calories = Some(135);
+        recorder.add_sample(&data);
+
+        let sample = &recorder.samples[2];
+        assert_eq!(sample.grade, 250);
+        assert_eq!(sample.resistance, 127);
+        // Counted from the first sample the trainer reported calories in
+        assert_eq!(sample.calories, 15);
+        assert_eq!(recorder.total_calories(), 15);
+    }
+
+    #[test]
+    fn test_calories_carry_over_a_reconnect() {
+        let mut recorder = WorkoutRecorder::new();
+        let mut data = TrainerData::new(200, 30.0, 90, 140);
+
+        data.calories = Some(120);
+        recorder.add_sample(&data);
+        data.calories = Some(135);
+        recorder.add_sample(&data);
+
+        // Reconnected: the trainer's count starts over
+        data.calories = Some(4);
+        recorder.add_sample(&data);
+        assert_eq!(recorder.samples[2].calories, 19);
+
+        data.calories = Some(10);
+        recorder.add_sample(&data);
+        assert_eq!(recorder.total_calories(), 25);
+    }
+
+    #[test]
+    fn test_recorder_notes_devices_once_identified() {
+        let mut recorder = WorkoutRecorder::new();
+        let mut data = TrainerData::new(200, 30.0, 90, 140);
+        data.trainer_device = Some(1234);
+        data.heart_rate_device = Some(42);
+        let mut info = TrainerInfo::default();
+
+        recorder.note_devices(&data, &info);
+        assert_eq!(recorder.devices.len(), 2);
+        assert_eq!(recorder.devices[0].name, None);
+
+        // Page 80 and HRM Page 2 arrive later and replace the first entries
+        let page_80 = FecParser::parse_data_page(&[0x50, 0xFF, 0xFF, 1, 89, 0, 0x3B, 0x0B]).unwrap();
+        info.update(&page_80);
+        data.heart_rate_manufacturer = Some(1);
+        recorder.note_devices(&data, &info);
+        assert_eq!(recorder.devices.len(), 2);
+        assert_eq!(recorder.devices[0].product, Some(2875));
+        assert_eq!(recorder.devices[0].name.as_deref(), Some("Tacx Neo 2T Smart"));
+        assert_eq!(recorder.devices[1].name.as_deref(), Some("Garmin"));
+
+        recorder.add_sample(&data);
+        let fit_bytes = recorder.encode_fit();
+        assert!(fit_bytes.windows(18).any(|w| w == b"Tacx Neo 2T Smart\0"));
+    }
+
+    #[test]
+    fn test_fit_sport_follows_equipment() {
+        let mut recorder = WorkoutRecorder::new();
+        let mut data = TrainerData::new(200, 12.0, 28, 140);
+        data.equipment = Some(Equipment::Rower);
+        recorder.add_sample(&data);
+        data.equipment = None;
+        recorder.add_sample(&data);
+
+        assert_eq!(recorder.equipment, Some(Equipment::Rower));
+        assert_eq!(
+            fit_sport(recorder.equipment),
+            (types::SPORT_ROWING, types::SUB_SPORT_INDOOR_ROWING)
+        );
+        assert_eq!(
+            fit_sport(None),
+            (types::SPORT_CYCLING, types::SUB_SPORT_INDOOR_CYCLING)
+        );
+    }
+
     #[test]
     fn test_encode_fit_produces_valid_file() {
         let mut recorder = WorkoutRecorder::new();
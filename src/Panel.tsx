@@ -3,6 +3,7 @@ import { listen, emit } from '@tauri-apps/api/event';
 import { invoke } from '@tauri-apps/api/core';
 import { useListNavigation } from './hooks/useListNavigation';
 import { useKeyboard } from './hooks/useKeyboard';
+import type { BackendSensorStatus } from './hooks/useTrainer';
 
 type PanelView = 'menu' | 'devices' | 'help' | 'trainings' | 'settings' | 'about' | 'confirm-stop' | 'summary';
 
@@ -18,6 +19,82 @@ interface WorkoutSummary {
   file_path: string;
 }
 
+interface DiscoveredDevice {
+  device_number: number;
+  device_type: number;
+  transmission_type: number;
+  manufacturer_id: number | null;
+  model_number: number | null;
+  name: string | null;
+  rssi: number | null;
+  message_count: number;
+}
+
+// One row of the continuous scan's live table
+interface ScannedDevice {
+  device_number: number;
+  device_type: number;
+  transmission_type: number;
+  rssi: number | null;
+  message_count: number;
+  age_secs: number;
+}
+
+interface PairedDevice {
+  device_number: number;
+  transmission_type: number;
+}
+
+interface PairedDevices {
+  fec: PairedDevice | null;
+  hrm: PairedDevice | null;
+}
+
+interface UsbDeviceInfo {
+  bus: number;
+  address: number;
+  vendor_id: number;
+  product_id: number;
+  serial: string | null;
+  ant_model: string | null;
+}
+
+type CalibrationKind = 'spin_down' | 'zero_offset';
+type CalibrationCondition = 'not_applicable' | 'too_low' | 'ok' | 'too_high';
+
+interface CalibrationStatus {
+  kind: CalibrationKind | null;
+  phase: 'idle' | 'requested' | 'running' | 'succeeded' | 'failed' | 'timed_out';
+  speed_condition: CalibrationCondition;
+  temperature_condition: CalibrationCondition;
+  temperature: number | null;
+  target_speed: number | null;
+  target_spin_down_time_ms: number | null;
+  spin_down_time_ms: number | null;
+  zero_offset: number | null;
+}
+
+interface FecCapabilities {
+  max_resistance: number;
+  supports_basic_resistance: boolean;
+  supports_target_power: boolean;
+  supports_simulation: boolean;
+}
+
+interface TrainerInfo {
+  capabilities: FecCapabilities | null;
+  manufacturer_id: number | null;
+  model_number: number | null;
+  name: string | null;
+  hw_revision: number | null;
+  serial_number: number | null;
+  firmware: string | null;
+}
+
+const FEC_DEVICE_TYPE = 17;
+const HRM_DEVICE_TYPE = 120;
+const POWER_DEVICE_TYPE = 11;
+
 const MENU_ITEMS = [
   { id: 'trainings', label: 'Trainings' },
   { id: 'devices', label: 'Devices' },
@@ -161,35 +238,274 @@ function TrainingsView({ onBack }: { onBack: () => void }) {
   );
 }
 
-type DeviceStatus = 'idle' | 'scanning' | 'found' | 'connecting' | 'connected' | 'error';
+type DeviceStatus = 'idle' | 'scanning' | 'found' | 'searching' | 'listening' | 'connecting' | 'connected' | 'error';
+
+interface DeviceAction {
+  id: string;
+  label: string;
+  sensor?: DiscoveredDevice;
+  stick?: UsbDeviceInfo;
+  serialPort?: string;
+  found?: FoundDevice;
+}
+
+// Device a wildcard channel locked onto, reported in `trainer-data`
+interface FoundDevice {
+  deviceType: number;
+  deviceNumber: number;
+}
+
+function foundLabel(found: FoundDevice, paired: PairedDevices | null): string {
+  const name = found.deviceType === FEC_DEVICE_TYPE ? 'trainer' : 'HR strap';
+  const isPaired = pairedFor(paired, found.deviceType)?.device_number === found.deviceNumber;
+  return `${isPaired ? 'Forget' : 'Pair'} ${name} #${found.deviceNumber}`;
+}
+
+const pairedFor = (paired: PairedDevices | null, deviceType: number) =>
+  (deviceType === FEC_DEVICE_TYPE ? paired?.fec : paired?.hrm) ?? null;
+
+function sensorLabel(sensor: DiscoveredDevice, paired: PairedDevices | null): string {
+  const profile = sensor.device_type === FEC_DEVICE_TYPE ? 'FE-C' : 'HRM';
+  const mfr = sensor.name !== null
+    ? ` ${sensor.name}`
+    : sensor.manufacturer_id !== null ? ` mfr ${sensor.manufacturer_id}` : '';
+  const rssi = sensor.rssi !== null ? ` ${sensor.rssi}dBm` : '';
+  const isPaired = pairedFor(paired, sensor.device_type)?.device_number === sensor.device_number;
+  return `${profile} #${sensor.device_number}${mfr}${rssi}${isPaired ? ' [paired]' : ''}`;
+}
+
+function scannedLabel(device: ScannedDevice): string {
+  const profile = device.device_type === FEC_DEVICE_TYPE ? 'FE-C'
+    : device.device_type === HRM_DEVICE_TYPE ? 'HRM'
+    : device.device_type === POWER_DEVICE_TYPE ? 'PWR'
+    : `type ${device.device_type}`;
+  const rssi = device.rssi !== null ? ` ${device.rssi}dBm` : '';
+  return `${profile} #${device.device_number}${rssi} ${device.age_secs.toFixed(0)}s ago`;
+}
+
+function stickLabel(stick: UsbDeviceInfo, selected: UsbDeviceInfo | null): string {
+  const serial = stick.serial ? ` #${stick.serial}` : ` ${stick.bus}:${stick.address}`;
+  const isSelected = selected?.bus === stick.bus && selected?.address === stick.address;
+  return `${stick.ant_model}${serial}${isSelected ? ' [selected]' : ''}`;
+}
+
+// What the rider should do next, from the trainer's calibration pages
+function calibrationLine(calibration: CalibrationStatus): string {
+  const name = calibration.kind === 'zero_offset' ? 'Zero offset' : 'Spin-down';
+  switch (calibration.phase) {
+    case 'idle': return '';
+    case 'requested': return `${name}: waiting for trainer...`;
+    case 'running':
+      if (calibration.temperature_condition === 'too_low') return `${name}: trainer warming up...`;
+      if (calibration.temperature_condition === 'too_high') return `${name}: trainer too hot, wait`;
+      if (calibration.speed_condition === 'too_low') {
+        const target = calibration.target_speed !== null ? ` to ${calibration.target_speed.toFixed(0)} km/h` : '';
+        return `${name}: speed up${target}`;
+      }
+      return calibration.kind === 'spin_down' ? `${name}: stop pedaling and coast` : `${name}: keep still...`;
+    case 'succeeded':
+      if (calibration.spin_down_time_ms !== null) return `${name} done: ${calibration.spin_down_time_ms} ms`;
+      if (calibration.zero_offset !== null) return `${name} done: offset ${calibration.zero_offset}`;
+      return `${name} done`;
+    case 'failed': return `${name} failed`;
+    case 'timed_out': return `${name}: no answer from trainer`;
+  }
+}
+
+function trainerInfoLine(info: TrainerInfo): string {
+  const parts: string[] = [];
+  if (info.name !== null) {
+    parts.push(info.name);
+  } else if (info.manufacturer_id !== null) {
+    parts.push(`mfr ${info.manufacturer_id}`);
+  }
+  if (info.model_number !== null) parts.push(`model ${info.model_number}`);
+  if (info.serial_number !== null) parts.push(`#${info.serial_number}`);
+  if (info.firmware !== null) parts.push(`fw ${info.firmware}`);
+  const caps = info.capabilities;
+  if (caps) {
+    const modes = [
+      caps.supports_target_power && 'ERG',
+      caps.supports_simulation && 'SIM',
+      caps.supports_basic_resistance && 'RES',
+    ].filter(Boolean);
+    parts.push(`${modes.join('/')} max ${caps.max_resistance}N`);
+  }
+  return parts.join(' ');
+}
 
 function DevicesView({ onBack }: { onBack: () => void }) {
   const [status, setStatus] = useState<DeviceStatus>('idle');
   const [error, setError] = useState<string | null>(null);
-  const [usbDevices, setUsbDevices] = useState<string[]>([]);
+  const [usbDevices, setUsbDevices] = useState<UsbDeviceInfo[]>([]);
+  const [selectedStick, setSelectedStick] = useState<UsbDeviceInfo | null>(null);
+  const [serialPorts, setSerialPorts] = useState<string[]>([]);
+  const [sensors, setSensors] = useState<DiscoveredDevice[]>([]);
+  const [roomDevices, setRoomDevices] = useState<ScannedDevice[]>([]);
+  const [paired, setPaired] = useState<PairedDevices | null>(null);
+  const [sensorStatus, setSensorStatus] = useState<BackendSensorStatus | null>(null);
+  const [calibration, setCalibration] = useState<CalibrationStatus | null>(null);
+  const [trainerInfo, setTrainerInfo] = useState<TrainerInfo | null>(null);
+  const [trainerDevice, setTrainerDevice] = useState<number | null>(null);
+  const [heartRateDevice, setHeartRateDevice] = useState<number | null>(null);
+
+  // Info pages are requested once the trainer is tracked; refresh on status changes
+  useEffect(() => {
+    if (status !== 'connected') return;
+    invoke<TrainerInfo>('get_trainer_info').then(setTrainerInfo);
+  }, [status, sensorStatus]);
 
-  // Check initial connection state
+  // Check initial connection state and saved pairings
   useEffect(() => {
     invoke<boolean>('is_connected').then(connected => {
       if (connected) setStatus('connected');
     });
+    invoke<PairedDevices>('get_paired_devices').then(setPaired);
+    invoke<BackendSensorStatus>('get_sensor_status').then(setSensorStatus);
+    invoke<CalibrationStatus>('get_calibration_status').then(setCalibration);
+
+    const unlisten = listen<BackendSensorStatus>('sensor-status', (event) => {
+      setSensorStatus(event.payload);
+    });
+    const unlistenCalibration = listen<CalibrationStatus>('calibration-status', (event) => {
+      setCalibration(event.payload);
+    });
+    const unlistenScan = listen<ScannedDevice[]>('scan-update', (event) => {
+      setRoomDevices(event.payload);
+    });
+    // Wildcard channels report the device they found; pairing it is up to the user
+    const unlistenData = listen<{ trainer_device: number | null; heart_rate_device: number | null }>(
+      'trainer-data',
+      (event) => {
+        setTrainerDevice(event.payload.trainer_device);
+        setHeartRateDevice(event.payload.heart_rate_device);
+      },
+    );
+
+    return () => {
+      unlisten.then(fn => fn());
+      unlistenCalibration.then(fn => fn());
+      unlistenScan.then(fn => fn());
+      unlistenData.then(fn => fn());
+    };
   }, []);
 
   const scan = useCallback(async () => {
     setStatus('scanning');
     setError(null);
     try {
-      const devices = await invoke<string[]>('list_usb_devices');
+      const devices = await invoke<UsbDeviceInfo[]>('list_usb_devices');
       setUsbDevices(devices);
       const found = await invoke<boolean>('find_ant_device');
       setStatus(found ? 'found' : 'idle');
-      if (!found) setError('ANT+ dongle not found');
+      if (!found) {
+        setError('ANT+ dongle not found');
+        // USB1 sticks and UART modules show up as serial ports instead
+        setSerialPorts(await invoke<string[]>('list_serial_ports'));
+      }
+    } catch (e) {
+      setStatus('error');
+      setError(String(e));
+    }
+  }, []);
+
+  // With several sticks plugged in, Enter on one makes it the stick to open
+  const selectStick = useCallback(async (stick: UsbDeviceInfo) => {
+    try {
+      await invoke('select_ant_stick', {
+        stick: { bus: stick.bus, address: stick.address, serial: stick.serial },
+      });
+      setSelectedStick(stick);
+    } catch (e) {
+      setError(String(e));
+    }
+  }, []);
+
+  const selectSerialPort = useCallback(async (path: string) => {
+    setError(null);
+    try {
+      await invoke('select_serial_ant_stick', { path });
+      setStatus('found');
+    } catch (e) {
+      setError(String(e));
+    }
+  }, []);
+
+  const sticks = usbDevices.filter(device => device.ant_model !== null);
+
+  const search = useCallback(async () => {
+    setStatus('searching');
+    setError(null);
+    try {
+      const found = await invoke<DiscoveredDevice[]>('search_ant_devices');
+      setSensors(found);
+      setStatus('found');
+      if (found.length === 0) setError('No sensors heard');
+    } catch (e) {
+      setStatus('error');
+      setError(String(e));
+    }
+  }, []);
+
+  // Continuous scan: every sensor in range, updated live by `scan-update`
+  const scanRoom = useCallback(async () => {
+    setStatus('listening');
+    setError(null);
+    setRoomDevices([]);
+    try {
+      const heard = await invoke<ScannedDevice[]>('scan_ant_devices');
+      setRoomDevices(heard);
+      setStatus('found');
+      if (heard.length === 0) setError('No sensors heard');
     } catch (e) {
       setStatus('error');
       setError(String(e));
     }
   }, []);
 
+  // Enter on a sensor pairs it; on the paired sensor it forgets the pairing
+  const togglePairing = useCallback(async (sensor: DiscoveredDevice) => {
+    try {
+      const current = pairedFor(paired, sensor.device_type);
+      if (current?.device_number === sensor.device_number) {
+        await invoke('forget_ant_device', { deviceType: sensor.device_type });
+      } else {
+        await invoke('pair_ant_device', {
+          deviceType: sensor.device_type,
+          deviceNumber: sensor.device_number,
+          transmissionType: sensor.transmission_type,
+        });
+      }
+      setPaired(await invoke<PairedDevices>('get_paired_devices'));
+    } catch (e) {
+      setError(String(e));
+    }
+  }, [paired]);
+
+  // Enter on a found device pairs it (transmission type 0 matches any);
+  // on the paired one it forgets the pairing
+  const toggleFoundPairing = useCallback(async (found: FoundDevice) => {
+    try {
+      if (pairedFor(paired, found.deviceType)?.device_number === found.deviceNumber) {
+        await invoke('forget_ant_device', { deviceType: found.deviceType });
+      } else {
+        await invoke('pair_ant_device', {
+          deviceType: found.deviceType,
+          deviceNumber: found.deviceNumber,
+          transmissionType: 0,
+        });
+      }
+      setPaired(await invoke<PairedDevices>('get_paired_devices'));
+    } catch (e) {
+      setError(String(e));
+    }
+  }, [paired]);
+
+  const foundDevices: FoundDevice[] = [
+    ...(trainerDevice !== null ? [{ deviceType: FEC_DEVICE_TYPE, deviceNumber: trainerDevice }] : []),
+    ...(heartRateDevice !== null ? [{ deviceType: HRM_DEVICE_TYPE, deviceNumber: heartRateDevice }] : []),
+  ];
+
   const connect = useCallback(async () => {
     setStatus('connecting');
     setError(null);
@@ -202,6 +518,15 @@ function DevicesView({ onBack }: { onBack: () => void }) {
     }
   }, []);
 
+  const calibrate = useCallback(async (kind: CalibrationKind) => {
+    setError(null);
+    try {
+      setCalibration(await invoke<CalibrationStatus>('start_calibration', { kind }));
+    } catch (e) {
+      setError(String(e));
+    }
+  }, []);
+
   const disconnect = useCallback(async () => {
     try {
       await invoke('disconnect_ant_device');
@@ -211,18 +536,46 @@ function DevicesView({ onBack }: { onBack: () => void }) {
     }
   }, []);
 
-  const DEVICE_ACTIONS = (() => {
+  const DEVICE_ACTIONS: DeviceAction[] = (() => {
     switch (status) {
       case 'idle':
       case 'error':
-        return [{ id: 'scan', label: 'Scan for ANT+ dongle' }];
+        return [
+          { id: 'scan', label: 'Scan for ANT+ dongle' },
+          ...serialPorts.map(serialPort => ({
+            id: `serial-${serialPort}`,
+            label: `Use serial ${serialPort}`,
+            serialPort,
+          })),
+        ];
       case 'found':
         return [
           { id: 'connect', label: 'Connect' },
+          { id: 'search', label: 'Search sensors' },
+          { id: 'scan-room', label: 'Scan room' },
+          ...(sticks.length > 1 ? sticks.map(stick => ({
+            id: `stick-${stick.bus}-${stick.address}`,
+            label: stickLabel(stick, selectedStick),
+            stick,
+          })) : []),
+          ...sensors.map(sensor => ({
+            id: `sensor-${sensor.device_type}-${sensor.device_number}`,
+            label: sensorLabel(sensor, paired),
+            sensor,
+          })),
           { id: 'scan', label: 'Rescan' },
         ];
       case 'connected':
-        return [{ id: 'disconnect', label: 'Disconnect' }];
+        return [
+          ...foundDevices.map(found => ({
+            id: `found-${found.deviceType}`,
+            label: foundLabel(found, paired),
+            found,
+          })),
+          { id: 'spin-down', label: 'Calibrate: spin-down' },
+          { id: 'zero-offset', label: 'Calibrate: zero offset' },
+          { id: 'disconnect', label: 'Disconnect' },
+        ];
       default:
         return [];
     }
@@ -231,8 +584,16 @@ function DevicesView({ onBack }: { onBack: () => void }) {
   const { selectedIndex } = useListNavigation({
     items: DEVICE_ACTIONS,
     onSelect: (item) => {
-      if (item.id === 'scan') scan();
+      if (item.sensor) togglePairing(item.sensor);
+      else if (item.found) toggleFoundPairing(item.found);
+      else if (item.stick) selectStick(item.stick);
+      else if (item.serialPort) selectSerialPort(item.serialPort);
+      else if (item.id === 'scan') scan();
+      else if (item.id === 'search') search();
+      else if (item.id === 'scan-room') scanRoom();
       else if (item.id === 'connect') connect();
+      else if (item.id === 'spin-down') calibrate('spin_down');
+      else if (item.id === 'zero-offset') calibrate('zero_offset');
       else if (item.id === 'disconnect') disconnect();
     },
     onCancel: onBack,
@@ -243,8 +604,11 @@ function DevicesView({ onBack }: { onBack: () => void }) {
       case 'idle': return 'No device';
       case 'scanning': return 'Scanning...';
       case 'found': return 'ANT+ dongle found';
+      case 'searching': return 'Searching sensors...';
+      case 'listening': return 'Scanning room...';
       case 'connecting': return 'Connecting...';
-      case 'connected': return 'Connected';
+      case 'connected':
+        return sensorStatus?.stick_present === false ? 'Stick unplugged, reconnecting...' : 'Connected';
       case 'error': return 'Error';
     }
   })();
@@ -268,18 +632,42 @@ function DevicesView({ onBack }: { onBack: () => void }) {
 
       {usbDevices.length > 0 && status !== 'connected' && (
         <div style={{ marginBottom: '12px', opacity: 0.5, fontSize: '11px' }}>
-          USB: {usbDevices.length} device(s)
+          USB: {usbDevices.length} device(s), {sticks.length} ANT stick(s)
         </div>
       )}
 
       {status === 'connected' && (
         <div style={{ marginBottom: '12px', opacity: 0.7, fontSize: '11px' }}>
-          <div>CH0: FE-C (trainer)</div>
-          <div>CH1: HRM (heart rate)</div>
+          <div>FE-C (trainer) {sensorStatus?.trainer ?? ''}</div>
+          <div>HRM (heart rate) {sensorStatus?.heart_rate ?? ''}</div>
+          {trainerInfo && trainerInfoLine(trainerInfo) && (
+            <div>Trainer: {trainerInfoLine(trainerInfo)}</div>
+          )}
+        </div>
+      )}
+
+      {status === 'connected' && calibration && calibration.phase !== 'idle' && (
+        <div style={{ marginBottom: '12px', fontSize: '11px', color: calibration.phase === 'failed' || calibration.phase === 'timed_out' ? '#ff4444' : 'var(--text-primary)' }}>
+          {calibrationLine(calibration)}
+        </div>
+      )}
+
+      {paired && (
+        <div style={{ marginBottom: '12px', opacity: 0.5, fontSize: '11px' }}>
+          <div>Trainer: {paired.fec ? `#${paired.fec.device_number}` : 'any'}</div>
+          <div>HR strap: {paired.hrm ? `#${paired.hrm.device_number}` : 'any'}</div>
+        </div>
+      )}
+
+      {(status === 'listening' || status === 'found') && roomDevices.length > 0 && (
+        <div style={{ marginBottom: '12px', opacity: 0.7, fontSize: '11px' }}>
+          {roomDevices.map(device => (
+            <div key={`${device.device_type}-${device.device_number}`}>{scannedLabel(device)}</div>
+          ))}
         </div>
       )}
 
-      {(status === 'scanning' || status === 'connecting') ? (
+      {(status === 'scanning' || status === 'searching' || status === 'listening' || status === 'connecting') ? (
         <div style={{ opacity: 0.5 }}>
           {'> '}{statusLine}
         </div>
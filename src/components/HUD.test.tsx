@@ -11,6 +11,8 @@ const mockData: TrainerData = {
   distance: 5000,
   elapsedTime: 900,
   grade: 3,
+  feState: 'in_use',
+  alerts: [],
 };
 
 const mockZone: TargetZone = {
@@ -44,4 +46,11 @@ describe('HUD', () => {
     render(<HUD data={mockData} targetZone={mockZone} />);
     expect(screen.getByText(/▲.*3%/)).toBeInTheDocument();
   });
+
+  it('displays the first trainer alert', () => {
+    const data: TrainerData = { ...mockData, alerts: ['speed_too_low_for_target', 'user_config_required'] };
+    render(<HUD data={data} targetZone={mockZone} />);
+    expect(screen.getByText(/pedal faster/)).toBeInTheDocument();
+    expect(screen.queryByText(/rider weight/)).not.toBeInTheDocument();
+  });
 });
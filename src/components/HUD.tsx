@@ -1,4 +1,4 @@
-import type { TrainerData, TargetZone } from '../types/trainer';
+import type { TrainerData, TargetZone, TrainerAlert } from '../types/trainer';
 
 interface HUDProps {
   data: TrainerData;
@@ -15,6 +15,16 @@ function formatDistance(meters: number): string {
   return (meters / 1000).toFixed(1);
 }
 
+// Short enough to fit next to the target zone
+const ALERT_LABELS: Record<TrainerAlert, string> = {
+  speed_too_low_for_target: 'pedal faster',
+  speed_too_high_for_target: 'pedal slower',
+  target_power_limit_reached: 'target out of reach',
+  bicycle_power_calibration_required: 'calibrate power',
+  resistance_calibration_required: 'spin-down needed',
+  user_config_required: 'set rider weight',
+};
+
 export function HUD({ data, targetZone }: HUDProps) {
   const gradeSymbol = data.grade >= 0 ? '▲' : '▼';
   const gradeValue = Math.abs(data.grade);
@@ -48,9 +58,14 @@ export function HUD({ data, targetZone }: HUDProps) {
         </span>
       </div>
 
-      {/* Center: Target zone */}
+      {/* Center: Target zone, with the trainer's most urgent alert */}
       <div>
         🎯 {targetZone.min}-{targetZone.max}W
+        {data.alerts.length > 0 && (
+          <span style={{ marginLeft: '12px', color: 'var(--text-warning)' }}>
+            ! {ALERT_LABELS[data.alerts[0]]}
+          </span>
+        )}
       </div>
 
       {/* Right: Distance and time */}
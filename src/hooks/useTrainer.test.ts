@@ -7,9 +7,16 @@ vi.mock('@tauri-apps/api/core', () => ({
   invoke: vi.fn(),
 }));
 
+// Mock Tauri's event API (backend pushes trainer-data events)
+vi.mock('@tauri-apps/api/event', () => ({
+  listen: vi.fn(),
+}));
+
 import { invoke } from '@tauri-apps/api/core';
+import { listen } from '@tauri-apps/api/event';
 
 const mockInvoke = invoke as ReturnType<typeof vi.fn>;
+const mockListen = listen as ReturnType<typeof vi.fn>;
 
 describe('useTrainer', () => {
   beforeEach(() => {
@@ -17,6 +24,8 @@ describe('useTrainer', () => {
     mockInvoke.mockReset();
     // Default mock for find_ant_device
     mockInvoke.mockResolvedValue(false);
+    mockListen.mockReset();
+    mockListen.mockResolvedValue(() => {});
   });
 
   afterEach(() => {
@@ -66,6 +75,110 @@ describe('useTrainer', () => {
     expect(result.current.isSimulation).toBe(false);
   });
 
+  it('updates data from trainer-data events on real hardware', async () => {
+    mockInvoke
+      .mockResolvedValueOnce(true) // find_ant_device
+      .mockResolvedValueOnce(true) // connect_ant_device
+      .mockResolvedValue(undefined); // disconnect_ant_device
+
+    const { result } = renderHook(() => useTrainer());
+
+    await act(async () => {
+      await vi.runAllTimersAsync();
+    });
+
+    expect(mockListen).toHaveBeenCalledWith('trainer-data', expect.any(Function));
+    const handler = mockListen.mock.calls[0][1];
+    const payload = {
+      power: 210,
+      speed: 32.4,
+      cadence: 88,
+      heart_rate: 145,
+      fe_state: 'in_use',
+      alerts: ['speed_too_low_for_target'],
+    };
+
+    await act(async () => {
+      vi.advanceTimersByTime(100);
+    });
+    act(() => {
+      handler({ payload });
+    });
+
+    expect(result.current.data.power).toBe(210);
+    expect(result.current.data.cadence).toBe(88);
+    expect(result.current.data.heartRate).toBe(145);
+    expect(result.current.data.feState).toBe('in_use');
+    expect(result.current.data.alerts).toEqual(['speed_too_low_for_target']);
+    expect(result.current.data.elapsedTime).toBeGreaterThan(0);
+  });
+
+  it('advances time and distance by the time between trainer-data events', async () => {
+    mockInvoke
+      .mockResolvedValueOnce(true) // find_ant_device
+      .mockResolvedValueOnce(true) // connect_ant_device
+      .mockResolvedValue(undefined); // disconnect_ant_device
+
+    const { result } = renderHook(() => useTrainer());
+
+    await act(async () => {
+      await vi.runAllTimersAsync();
+    });
+
+    const handler = mockListen.mock.calls.find(([name]) => name === 'trainer-data')![1];
+    const payload = {
+      power: 200,
+      speed: 36,
+      cadence: 90,
+      heart_rate: 140,
+      fe_state: 'in_use',
+      alerts: [],
+    };
+
+    act(() => {
+      handler({ payload });
+    });
+    const { elapsedTime, distance } = result.current.data;
+
+    // Half a second between events, as when several were dropped
+    await act(async () => {
+      vi.advanceTimersByTime(500);
+    });
+    act(() => {
+      handler({ payload });
+    });
+
+    expect(result.current.data.elapsedTime - elapsedTime).toBeCloseTo(0.5);
+    expect(result.current.data.distance - distance).toBeCloseTo(36 / 3600 * 0.5);
+  });
+
+  it('tracks sensor-status events on real hardware', async () => {
+    mockInvoke
+      .mockResolvedValueOnce(true) // find_ant_device
+      .mockResolvedValueOnce(true) // connect_ant_device
+      .mockResolvedValue(undefined); // disconnect_ant_device
+
+    const { result } = renderHook(() => useTrainer());
+
+    await act(async () => {
+      await vi.runAllTimersAsync();
+    });
+
+    expect(result.current.sensorStatus).toBeNull();
+    const call = mockListen.mock.calls.find(([name]) => name === 'sensor-status');
+    expect(call).toBeDefined();
+
+    act(() => {
+      call![1]({ payload: { trainer: 'tracking', heart_rate: 'lost', stick_present: true } });
+    });
+
+    expect(result.current.sensorStatus).toEqual({
+      trainer: 'tracking',
+      heartRate: 'lost',
+      stickPresent: true,
+    });
+  });
+
   it('accumulates elapsed time in simulation mode', async () => {
     const { result, unmount } = renderHook(() => useTrainer());
 
@@ -1,14 +1,34 @@
 import { useState, useEffect, useCallback } from 'react';
 import { invoke } from '@tauri-apps/api/core';
-import type { TrainerData } from '../types/trainer';
+import { listen } from '@tauri-apps/api/event';
+import type { TrainerData, SensorState, SensorStatus, FeState, TrainerAlert } from '../types/trainer';
+
+// Payload of the `trainer-data` event pushed by the backend reader thread
+interface BackendTrainerData {
+  power: number;
+  speed: number;
+  cadence: number;
+  heart_rate: number;
+  fe_state: FeState | null;
+  alerts: TrainerAlert[];
+}
+
+// Payload of the `sensor-status` event, sent whenever a channel changes state
+export interface BackendSensorStatus {
+  trainer: SensorState;
+  heart_rate: SensorState;
+  stick_present: boolean;
+}
 
 interface UseTrainerResult {
   data: TrainerData;
   isConnected: boolean;
   isSimulation: boolean;
+  sensorStatus: SensorStatus | null;
   error: string | null;
   connect: () => Promise<void>;
   disconnect: () => Promise<void>;
+  setGrade: (grade: number) => Promise<void>;
 }
 
 export function useTrainer(): UseTrainerResult {
@@ -20,9 +40,12 @@ export function useTrainer(): UseTrainerResult {
     distance: 0,
     elapsedTime: 0,
     grade: 0,
+    feState: null,
+    alerts: [],
   });
   const [isConnected, setIsConnected] = useState(false);
   const [isSimulation, setIsSimulation] = useState(false);
+  const [sensorStatus, setSensorStatus] = useState<SensorStatus | null>(null);
   const [error, setError] = useState<string | null>(null);
 
   // Try to connect to real ANT+ device, fallback to simulation
@@ -65,72 +88,106 @@ export function useTrainer(): UseTrainerResult {
     setIsSimulation(false);
   }, [isSimulation]);
 
-  // Poll for trainer data when connected
+  // Change the road grade; on real hardware this drives trainer resistance
+  const setGrade = useCallback(async (grade: number) => {
+    setData(prev => ({ ...prev, grade }));
+    if (!isConnected || isSimulation) return;
+
+    try {
+      await invoke('set_grade', { grade });
+    } catch (e) {
+      console.error('Failed to set grade:', e);
+    }
+  }, [isConnected, isSimulation]);
+
+  // Real hardware: the backend reader thread pushes `trainer-data` events at
+  // about 10 Hz. Events can arrive late or not at all, so distance and time
+  // advance by the time measured between them, not a fixed step
   useEffect(() => {
-    if (!isConnected) return;
-
-    const interval = setInterval(async () => {
-      try {
-        if (isSimulation) {
-          // Simulation mode: generate realistic cycling data
-          setData(prev => {
-            const time = Date.now() / 1000;
-            // Simulate variations in power, cadence, speed
-            const basePower = 150;
-            const powerVariation = Math.sin(time * 0.5) * 30 + Math.random() * 10;
-            const power = Math.round(basePower + powerVariation);
-
-            const baseCadence = 85;
-            const cadenceVariation = Math.sin(time * 0.3) * 10 + Math.random() * 5;
-            const cadence = Math.round(baseCadence + cadenceVariation);
-
-            // Speed roughly correlates with power
-            const speed = Math.round((power / 10) * 10) / 10;
-
-            // Heart rate increases with effort
-            const baseHr = 120;
-            const hrVariation = power / 10 + Math.random() * 5;
-            const heartRate = Math.round(baseHr + hrVariation);
-
-            // Accumulate distance based on speed
-            const elapsedTime = prev.elapsedTime + 0.1; // 100ms intervals
-            const distance = prev.distance + (speed / 3600) * 0.1; // km
-
-            return {
-              power,
-              cadence,
-              speed,
-              heartRate,
-              distance: Math.round(distance * 100) / 100,
-              elapsedTime: Math.round(elapsedTime * 10) / 10,
-              grade: prev.grade,
-            };
-          });
-        } else {
-          // Real hardware mode: poll from backend
-          const trainerData = await invoke<{
-            power: number;
-            speed: number;
-            cadence: number;
-            heart_rate: number;
-          } | null>('poll_trainer_data');
-
-          if (trainerData) {
-            setData(prev => ({
-              ...prev,
-              power: trainerData.power,
-              speed: trainerData.speed,
-              cadence: trainerData.cadence,
-              heartRate: trainerData.heart_rate,
-              // Accumulate distance and time
-              distance: prev.distance + (trainerData.speed / 3600) * 0.1,
-              elapsedTime: prev.elapsedTime + 0.1,
-            }));
-          }
-        }
-      } catch (e) {
-        console.error('Failed to get trainer data:', e);
-      }
+    if (!isConnected || isSimulation) return;
+
+    let lastEventAt = performance.now();
+    const unlisten = listen<BackendTrainerData>('trainer-data', (event) => {
+      const trainerData = event.payload;
+      const now = performance.now();
+      const stepSeconds = (now - lastEventAt) / 1000;
+      lastEventAt = now;
+      setData(prev => ({
+        ...prev,
+        power: trainerData.power,
+        speed: trainerData.speed,
+        cadence: trainerData.cadence,
+        heartRate: trainerData.heart_rate,
+        feState: trainerData.fe_state,
+        alerts: trainerData.alerts,
+        // Accumulate distance and time
+        distance: prev.distance + (trainerData.speed / 3600) * stepSeconds,
+        elapsedTime: prev.elapsedTime + stepSeconds,
+      }));
+    });
+
+    return () => {
+      unlisten.then(fn => fn());
+    };
+  }, [isConnected, isSimulation]);
+
+  // Real hardware: per-sensor channel state (searching / tracking / lost)
+  useEffect(() => {
+    if (!isConnected || isSimulation) return;
+
+    const unlisten = listen<BackendSensorStatus>('sensor-status', (event) => {
+      setSensorStatus({
+        trainer: event.payload.trainer,
+        heartRate: event.payload.heart_rate,
+        stickPresent: event.payload.stick_present,
+      });
+    });
+
+    return () => {
+      unlisten.then(fn => fn());
+    };
+  }, [isConnected, isSimulation]);
+
+  // Simulation mode: generate realistic cycling data
+  useEffect(() => {
+    if (!isConnected || !isSimulation) return;
+
+    const interval = setInterval(() => {
+      setData(prev => {
+        const time = Date.now() / 1000;
+        // Simulate variations in power, cadence, speed
+        const basePower = 150;
+        const powerVariation = Math.sin(time * 0.5) * 30 + Math.random() * 10;
+        const power = Math.round(basePower + powerVariation);
+
+        const baseCadence = 85;
+        const cadenceVariation = Math.sin(time * 0.3) * 10 + Math.random() * 5;
+        const cadence = Math.round(baseCadence + cadenceVariation);
+
+        // Speed roughly correlates with power
+        const speed = Math.round((power / 10) * 10) / 10;
+
+        // Heart rate increases with effort
+        const baseHr = 120;
+        const hrVariation = power / 10 + Math.random() * 5;
+        const heartRate = Math.round(baseHr + hrVariation);
+
+        // Accumulate distance based on speed
+        const elapsedTime = prev.elapsedTime + 0.1; // 100ms intervals
+        const distance = prev.distance + (speed / 3600) * 0.1; // km
+
+        return {
+          power,
+          cadence,
+          speed,
+          heartRate,
+          distance: Math.round(distance * 100) / 100,
+          elapsedTime: Math.round(elapsedTime * 10) / 10,
+          grade: prev.grade,
+          feState: prev.feState,
+          alerts: prev.alerts,
+        };
+      });
     }, 100); // 10 updates per second
 
     return () => clearInterval(interval);
@@ -181,5 +238,5 @@ export function useTrainer(): UseTrainerResult {
     };
   }, []);
 
-  return { data, isConnected, isSimulation, error, connect, disconnect };
+  return { data, isConnected, isSimulation, sensorStatus, error, connect, disconnect, setGrade };
 }
@@ -6,10 +6,33 @@ export interface TrainerData {
   distance: number;    // meters
   elapsedTime: number; // seconds
   grade: number;       // percent (-20 to +20)
+  feState: FeState | null;   // null until the trainer reports it
+  alerts: TrainerAlert[];    // from FE-C Page 25, empty when all is well
 }
 
+// Equipment state reported by the trainer
+export type FeState = 'asleep' | 'ready' | 'in_use' | 'finished';
+
+// ERG limits and calibration/configuration requests from the trainer
+export type TrainerAlert =
+  | 'speed_too_low_for_target'
+  | 'speed_too_high_for_target'
+  | 'target_power_limit_reached'
+  | 'bicycle_power_calibration_required'
+  | 'resistance_calibration_required'
+  | 'user_config_required';
+
 export interface TargetZone {
   min: number;
   max: number;
   metric: 'power' | 'heartRate';
 }
+
+// Sensor channel state reported by the backend (`sensor-status` events)
+export type SensorState = 'closed' | 'searching' | 'tracking' | 'lost';
+
+export interface SensorStatus {
+  trainer: SensorState;
+  heartRate: SensorState;
+  stickPresent: boolean;  // false while the stick is unplugged and being re-opened
+}